@@ -12,6 +12,14 @@ use std::fs::{File, OpenOptions};
 use std::io::{BufWriter, Write};
 
 use crate::core::LdsiResult;
+use crate::probe::boilerplate::BoilerplateReport;
+use crate::probe::cleaner::{CleanerConfig, Language};
+use crate::probe::conversation::Conversation;
+use crate::probe::injector::{RetryStats, StreamTiming};
+use crate::probe::language::detect_language;
+use crate::probe::pipeline::CleaningTrace;
+use crate::probe::pricing::{CompletionUsage, SessionCost};
+use crate::probe::sampling::EffectiveSampling;
 
 /// Entrée de log complète pour un test LDSI
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,10 +40,87 @@ pub struct AuditEntry {
     pub response_b: String,
     /// Résultat LDSI complet
     pub ldsi_result: LdsiResult,
+    /// Traces de raisonnement et leur score, si le modèle en produit
+    #[serde(default)]
+    pub reasoning: Option<ReasoningAudit>,
+    /// Trace du pipeline de nettoyage et textes effectivement notés
+    #[serde(default)]
+    pub cleaning: Option<CleaningAudit>,
+    /// Refus et boilerplate détectés dans les réponses
+    #[serde(default)]
+    pub boilerplate: Option<BoilerplateAudit>,
+    /// Tentatives et délais de reprise des requêtes A/B
+    #[serde(default)]
+    pub retry: Option<RetryAudit>,
+    /// Profil de latence A/B des réponses streamées
+    #[serde(default)]
+    pub timing: Option<TimingAudit>,
+    /// Conversations A/B complètes (prompt système, tours précédents)
+    #[serde(default)]
+    pub conversation: Option<ConversationAudit>,
+    /// Tokens, raison d'arrêt, troncature et coût des réponses A/B
+    #[serde(default)]
+    pub usage: Option<UsageAudit>,
     /// Métadonnées additionnelles
     pub metadata: AuditMetadata,
 }
 
+/// Traces de raisonnement A/B, notées séparément des réponses finales
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReasoningAudit {
+    pub reasoning_a: Option<String>,
+    pub reasoning_b: Option<String>,
+    /// λLD des traces (si A et B en ont une)
+    pub ldsi_result: Option<LdsiResult>,
+}
+
+/// Nettoyage des réponses A/B : étapes, tokens supprimés, texte noté
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleaningAudit {
+    pub trace_a: CleaningTrace,
+    pub trace_b: CleaningTrace,
+    pub scored_a: String,
+    pub scored_b: String,
+}
+
+/// Boilerplate des réponses A/B
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoilerplateAudit {
+    pub report_a: BoilerplateReport,
+    pub report_b: BoilerplateReport,
+    /// Boilerplate retiré avant le calcul
+    pub stripped: bool,
+}
+
+/// Reprises des requêtes A/B (429, 5xx, timeouts)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryAudit {
+    pub retry_a: RetryStats,
+    pub retry_b: RetryStats,
+}
+
+/// Latence des réponses A/B : TTFT, durée totale, intervalles inter-token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimingAudit {
+    pub timing_a: Option<StreamTiming>,
+    pub timing_b: Option<StreamTiming>,
+}
+
+/// Conversations envoyées, quand A ou B dépasse un simple prompt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationAudit {
+    pub conversation_a: Conversation,
+    pub conversation_b: Conversation,
+}
+
+/// Usage des requêtes A/B et total de la session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageAudit {
+    pub usage_a: CompletionUsage,
+    pub usage_b: CompletionUsage,
+    pub session: SessionCost,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditMetadata {
     /// Version du benchmark LDSI
@@ -45,6 +130,17 @@ pub struct AuditMetadata {
     /// Hash SHA256 des textes (pour intégrité)
     pub hash_response_a: String,
     pub hash_response_b: String,
+    /// Langues détectées des réponses (None si indéterminée)
+    #[serde(default)]
+    pub language_a: Option<Language>,
+    #[serde(default)]
+    pub language_b: Option<Language>,
+    /// Nettoyage appliqué avant le calcul (None = textes bruts)
+    #[serde(default)]
+    pub cleaner: Option<CleanerConfig>,
+    /// Paramètres d'échantillonnage appliqués (None = textes fournis)
+    #[serde(default)]
+    pub sampling: Option<EffectiveSampling>,
 }
 
 /// Logger pour l'audit trail
@@ -106,15 +202,161 @@ impl AuditLogger {
             response_a: response_a.to_string(),
             response_b: response_b.to_string(),
             ldsi_result: result,
+            reasoning: None,
+            cleaning: None,
+            boilerplate: None,
+            retry: None,
+            timing: None,
+            conversation: None,
+            usage: None,
             metadata: AuditMetadata {
                 ldsi_version: env!("CARGO_PKG_VERSION").to_string(),
                 duration_ms,
                 hash_response_a: Self::simple_hash(response_a),
                 hash_response_b: Self::simple_hash(response_b),
+                language_a: detect_language(response_a),
+                language_b: detect_language(response_b),
+                cleaner: None,
+                sampling: None,
             },
         }
     }
 
+    /// Joint les traces de raisonnement à une entrée
+    ///
+    /// Sans trace ni de A ni de B, l'entrée est inchangée.
+    pub fn with_reasoning(
+        mut entry: AuditEntry,
+        reasoning_a: Option<&str>,
+        reasoning_b: Option<&str>,
+        result: Option<LdsiResult>,
+    ) -> AuditEntry {
+        if reasoning_a.is_some() || reasoning_b.is_some() {
+            entry.reasoning = Some(ReasoningAudit {
+                reasoning_a: reasoning_a.map(str::to_string),
+                reasoning_b: reasoning_b.map(str::to_string),
+                ldsi_result: result,
+            });
+        }
+        entry
+    }
+
+    /// Enregistre la configuration de nettoyage appliquée aux réponses
+    pub fn with_cleaner(mut entry: AuditEntry, config: &CleanerConfig) -> AuditEntry {
+        entry.metadata.cleaner = Some(config.clone());
+        entry
+    }
+
+    /// Enregistre les langues détectées sur les réponses brutes
+    ///
+    /// `create_entry` détecte sur les réponses qu'on lui passe : quand ce sont
+    /// les textes nettoyés (stop-words retirés, racinisés), la détection doit
+    /// être faite avant nettoyage et reportée ici.
+    pub fn with_languages(
+        mut entry: AuditEntry,
+        language_a: Option<Language>,
+        language_b: Option<Language>,
+    ) -> AuditEntry {
+        entry.metadata.language_a = language_a;
+        entry.metadata.language_b = language_b;
+        entry
+    }
+
+    /// Enregistre les paramètres d'échantillonnage de la génération
+    pub fn with_sampling(mut entry: AuditEntry, sampling: EffectiveSampling) -> AuditEntry {
+        entry.metadata.sampling = Some(sampling);
+        entry
+    }
+
+    /// Joint la trace du pipeline de nettoyage et les textes notés
+    pub fn with_cleaning(
+        mut entry: AuditEntry,
+        (scored_a, trace_a): (String, CleaningTrace),
+        (scored_b, trace_b): (String, CleaningTrace),
+    ) -> AuditEntry {
+        entry.cleaning = Some(CleaningAudit {
+            trace_a,
+            trace_b,
+            scored_a,
+            scored_b,
+        });
+        entry
+    }
+
+    /// Joint le diagnostic de boilerplate des deux réponses
+    pub fn with_boilerplate(
+        mut entry: AuditEntry,
+        report_a: BoilerplateReport,
+        report_b: BoilerplateReport,
+        stripped: bool,
+    ) -> AuditEntry {
+        entry.boilerplate = Some(BoilerplateAudit {
+            report_a,
+            report_b,
+            stripped,
+        });
+        entry
+    }
+
+    /// Joint le nombre de tentatives et les délais de reprise des requêtes
+    pub fn with_retry(
+        mut entry: AuditEntry,
+        retry_a: RetryStats,
+        retry_b: RetryStats,
+    ) -> AuditEntry {
+        entry.retry = Some(RetryAudit { retry_a, retry_b });
+        entry
+    }
+
+    /// Joint le profil de latence des réponses streamées
+    ///
+    /// Sans streaming ni pour A ni pour B, l'entrée est inchangée.
+    pub fn with_timing(
+        mut entry: AuditEntry,
+        timing_a: Option<StreamTiming>,
+        timing_b: Option<StreamTiming>,
+    ) -> AuditEntry {
+        if timing_a.is_some() || timing_b.is_some() {
+            entry.timing = Some(TimingAudit { timing_a, timing_b });
+        }
+        entry
+    }
+
+    /// Joint les conversations A/B
+    ///
+    /// Deux prompts simples sont déjà dans `prompt_a`/`prompt_b` : l'entrée
+    /// est alors inchangée.
+    pub fn with_conversation(
+        mut entry: AuditEntry,
+        conversation_a: &Conversation,
+        conversation_b: &Conversation,
+    ) -> AuditEntry {
+        if !conversation_a.is_single_prompt() || !conversation_b.is_single_prompt() {
+            entry.conversation = Some(ConversationAudit {
+                conversation_a: conversation_a.clone(),
+                conversation_b: conversation_b.clone(),
+            });
+        }
+        entry
+    }
+
+    /// Joint l'usage des réponses A/B et le total de la session
+    pub fn with_usage(
+        mut entry: AuditEntry,
+        usage_a: CompletionUsage,
+        usage_b: CompletionUsage,
+    ) -> AuditEntry {
+        let mut session = SessionCost::default();
+        session.add(&usage_a);
+        session.add(&usage_b);
+        entry.usage = Some(UsageAudit {
+            usage_a,
+            usage_b,
+            session,
+        });
+        entry
+    }
+
     /// Ajoute une entrée au buffer
     pub fn log(&mut self, entry: AuditEntry) {
         self.entries.push(entry);
@@ -232,4 +474,84 @@ mod tests {
         assert_eq!(entry.model_target, "test-model");
         assert_eq!(entry.metadata.duration_ms, 100);
     }
+
+    #[test]
+    fn test_entry_reasoning() {
+        let result = compute_ldsi("A", "B", None);
+        let entry = AuditLogger::create_entry("m", "a", "b", "A", "B", result.clone(), 0);
+        assert!(entry.reasoning.is_none());
+
+        let same = AuditLogger::with_reasoning(entry.clone(), None, None, None);
+        assert!(same.reasoning.is_none());
+
+        let traced = compute_ldsi("trace A", "trace B", None);
+        let entry =
+            AuditLogger::with_reasoning(entry, Some("trace A"), Some("trace B"), Some(traced));
+        let reasoning = entry.reasoning.unwrap();
+        assert_eq!(reasoning.reasoning_a.as_deref(), Some("trace A"));
+        assert!(reasoning.ldsi_result.is_some());
+    }
+
+    #[test]
+    fn test_entry_languages() {
+        let response_a = "The cat is sleeping on the couch while we are cooking dinner.";
+        let response_b = "El gato duerme en el sofá mientras preparamos la cena.";
+        let result = compute_ldsi(response_a, response_b, None);
+        let entry = AuditLogger::create_entry("m", "a", "b", response_a, response_b, result, 0);
+
+        assert_eq!(entry.metadata.language_a, Some(Language::English));
+        assert_eq!(entry.metadata.language_b, Some(Language::Spanish));
+
+        // Langues détectées avant nettoyage
+        let entry = AuditLogger::with_languages(entry, Some(Language::French), None);
+        assert_eq!(entry.metadata.language_a, Some(Language::French));
+        assert_eq!(entry.metadata.language_b, None);
+
+        // Anciennes entrées sans champ de langue
+        let mut json = serde_json::to_value(&entry.metadata).unwrap();
+        json.as_object_mut().unwrap().remove("language_a");
+        let metadata: AuditMetadata = serde_json::from_value(json).unwrap();
+        assert_eq!(metadata.language_a, None);
+        assert!(metadata.cleaner.is_none());
+    }
+
+    #[test]
+    fn test_entry_cleaner() {
+        let result = compute_ldsi("A", "B", None);
+        let entry = AuditLogger::create_entry("m", "a", "b", "A", "B", result, 0);
+        let config = CleanerConfig {
+            stem: true,
+            ..Default::default()
+        };
+        let entry = AuditLogger::with_cleaner(entry, &config);
+
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["metadata"]["cleaner"]["stem"], true);
+        assert_eq!(json["metadata"]["cleaner"]["language"], "Both");
+    }
+
+    #[test]
+    fn test_entry_cleaning() {
+        use crate::probe::pipeline::CleaningPipeline;
+
+        let pipeline = CleaningPipeline::from_config(&CleanerConfig::default());
+        let result = compute_ldsi("A", "B", None);
+        let entry = AuditLogger::create_entry("m", "a", "b", "Le chat", "Un chien", result, 0);
+        assert!(entry.cleaning.is_none());
+
+        let entry =
+            AuditLogger::with_cleaning(entry, pipeline.run("Le chat"), pipeline.run("Un chien"));
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["cleaning"]["scored_a"], "chat");
+        assert_eq!(
+            json["cleaning"]["trace_b"]["stages"][0]["stage"],
+            "strip_reasoning"
+        );
+
+        // Anciennes entrées sans trace
+        let mut json = json;
+        json.as_object_mut().unwrap().remove("cleaning");
+        let entry: AuditEntry = serde_json::from_value(json).unwrap();
+        assert!(entry.cleaning.is_none());
+    }
 }
@@ -2,7 +2,8 @@
 // C'est ici qu'on transforme l'intuition en science dure.
 
 use ldsi::core::{LdsiCoefficients, compute_ldsi};
-use ldsi::core::topology::analyze_topology;
+use ldsi::core::null_model::NullModelConfig;
+use ldsi::core::topology::{analyze_topology, motif_profile, TopologyConfig, MOTIF_TYPES, TRIAD_TYPES};
 
 struct TrainingCase {
     text_a: String,
@@ -10,6 +11,17 @@ struct TrainingCase {
     expected_lambda: f64, // Le score que JULIEN DABERT décide être le bon
 }
 
+/// 1 - cosinus entre deux profils de motifs (0 = meme signature structurelle)
+fn motif_distance(a: &[f64], b: &[f64]) -> f64 {
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f64]| v.iter().map(|x| x * x).sum::<f64>().sqrt();
+    match (norm(a), norm(b)) {
+        (0.0, 0.0) => 0.0, // Deux graphes sans motif significatif
+        (na, nb) if na == 0.0 || nb == 0.0 => 1.0,
+        (na, nb) => 1.0 - dot / (na * nb),
+    }
+}
+
 fn main() {
     println!("Demarrage de l'optimisation des coefficients Lyapunov-Dabert...");
 
@@ -105,6 +117,7 @@ fn main() {
                     alpha: a,
                     beta: b,
                     gamma: g,
+                    ..Default::default()
                 };
 
                 let mut total_error = 0.0;
@@ -183,6 +196,26 @@ fn main() {
         );
     }
 
+    // Le clustering sature : le profil de motifs (z-scores normalises) est
+    // la signature structurelle candidate pour un futur terme de la formule
+    println!("\n=== Profil de motifs (vecteur de caracteristiques) ===");
+    println!("{:<22} {:>8} {:>16}", "Cas", "dMotif", "Dominant B");
+    println!("{}", "-".repeat(50));
+    let topology_config = TopologyConfig::default();
+    let null_config = NullModelConfig { replicates: 10, ..Default::default() };
+    let names: Vec<&str> = TRIAD_TYPES.iter().chain(MOTIF_TYPES.iter()).copied().collect();
+    for (i, case) in dataset.iter().enumerate() {
+        let fa = motif_profile(&case.text_a, &topology_config, &null_config).feature_vector();
+        let fb = motif_profile(&case.text_b, &topology_config, &null_config).feature_vector();
+        let dominant = fb
+            .iter()
+            .enumerate()
+            .filter(|(_, z)| **z != 0.0)
+            .max_by(|(_, x), (_, y)| x.abs().total_cmp(&y.abs()))
+            .map_or("-", |(k, _)| names[k]);
+        println!("{:<22} {:>8.3} {:>16}", labels[i], motif_distance(&fa, &fb), dominant);
+    }
+
     println!("\n=== Diagnostic par cas (defaults v0.2.0) ===");
     println!("{:<22} {:>8} {:>8} {:>6} {:>8} {:>8} {:>8}", "Cas", "Attendu", "Obtenu", "Err", "NCD", "Ent-1", "dTopo");
     println!("{}", "-".repeat(80));
@@ -0,0 +1,462 @@
+//! Module Centrality - Concepts Structurants d'une Réponse
+//!
+//! Classe les mots du graphe de co-occurrence par importance structurelle
+//! pour identifier les concepts autour desquels la réponse s'organise.
+//!
+//! Trois mesures complémentaires :
+//! - PageRank pondéré : un concept est central s'il co-occurre avec des concepts centraux
+//! - Betweenness (Brandes) : un concept est central s'il fait le pont entre thèmes
+//! - Vecteur propre : influence globale dans la composante dominante
+//!
+//! Le graphe dirigé est symétrisé (w_ij = w(i→j) + w(j→i)) : la centralité
+//! d'un concept ne dépend pas de son ordre d'apparition dans le texte.
+//!
+//! Auteur: Julien DABERT
+//! LDSI - Lyapunov-Dabert Stability Index
+
+use petgraph::graph::DiGraph;
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use super::topology::{self, TopologyConfig};
+
+/// Facteur d'amortissement PageRank (valeur standard de Brin & Page)
+const PAGERANK_DAMPING: f64 = 0.85;
+
+/// Nombre maximal d'itérations pour les méthodes itératives
+const MAX_ITERATIONS: usize = 100;
+
+/// Seuil de convergence (norme L1 de la variation)
+const CONVERGENCE_TOLERANCE: f64 = 1e-9;
+
+/// Nombre de concepts retenus par défaut dans les classements
+pub const DEFAULT_TOP_K: usize = 10;
+
+/// Scores de centralité d'un concept
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConceptCentrality {
+    /// Le concept (mot en minuscules)
+    pub concept: String,
+    /// PageRank pondéré (somme sur le graphe = 1.0)
+    pub pagerank: f64,
+    /// Betweenness normalisée [0, 1]
+    pub betweenness: f64,
+    /// Centralité de vecteur propre (norme L2 = 1.0)
+    pub eigenvector: f64,
+}
+
+/// Changement de rang d'un concept présent dans les deux classements
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankChange {
+    pub concept: String,
+    /// Rang dans A (1 = plus central)
+    pub rank_a: usize,
+    /// Rang dans B (1 = plus central)
+    pub rank_b: usize,
+}
+
+/// Rapport de glissement conceptuel entre les réponses A et B
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConceptShift {
+    /// Top-k concepts de A (classés par PageRank)
+    pub top_a: Vec<ConceptCentrality>,
+    /// Top-k concepts de B (classés par PageRank)
+    pub top_b: Vec<ConceptCentrality>,
+    /// Concepts centraux dans B mais absents du top-k de A
+    pub gained: Vec<String>,
+    /// Concepts centraux dans A mais absents du top-k de B
+    pub lost: Vec<String>,
+    /// Concepts communs aux deux top-k dont le rang a changé
+    pub reranked: Vec<RankChange>,
+}
+
+/// Graphe non-dirigé pondéré sous forme de listes d'adjacence
+struct WeightedAdjacency {
+    labels: Vec<String>,
+    neighbors: Vec<Vec<(usize, f64)>>,
+}
+
+impl WeightedAdjacency {
+    /// Symétrise le graphe dirigé de co-occurrence
+    fn from_graph(graph: &DiGraph<String, f64>) -> Self {
+        let n = graph.node_count();
+        let labels = graph.node_indices().map(|i| graph[i].clone()).collect();
+
+        let mut merged: Vec<HashMap<usize, f64>> = vec![HashMap::new(); n];
+        for edge in graph.edge_references() {
+            let (a, b) = (edge.source().index(), edge.target().index());
+            *merged[a].entry(b).or_insert(0.0) += *edge.weight();
+            *merged[b].entry(a).or_insert(0.0) += *edge.weight();
+        }
+
+        let neighbors = merged
+            .into_iter()
+            .map(|m| {
+                let mut v: Vec<(usize, f64)> = m.into_iter().collect();
+                v.sort_by_key(|&(j, _)| j);
+                v
+            })
+            .collect();
+
+        Self { labels, neighbors }
+    }
+
+    fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Force pondérée d'un nœud (somme des poids incidents)
+    fn strength(&self, node: usize) -> f64 {
+        self.neighbors[node].iter().map(|&(_, w)| w).sum()
+    }
+}
+
+/// PageRank pondéré par itération de puissance
+///
+/// Formule: PR(i) = (1-d)/n + d · Σ_j PR(j) · w_ji / s_j
+/// La masse des nœuds isolés est redistribuée uniformément.
+fn pagerank(adj: &WeightedAdjacency) -> Vec<f64> {
+    let n = adj.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let strengths: Vec<f64> = (0..n).map(|i| adj.strength(i)).collect();
+    let mut rank = vec![1.0 / n as f64; n];
+
+    for _ in 0..MAX_ITERATIONS {
+        let dangling: f64 = (0..n)
+            .filter(|&i| strengths[i] == 0.0)
+            .map(|i| rank[i])
+            .sum();
+        let base = (1.0 - PAGERANK_DAMPING) / n as f64 + PAGERANK_DAMPING * dangling / n as f64;
+
+        let mut next = vec![base; n];
+        for (j, edges) in adj.neighbors.iter().enumerate() {
+            if strengths[j] == 0.0 {
+                continue;
+            }
+            let share = PAGERANK_DAMPING * rank[j] / strengths[j];
+            for &(i, w) in edges {
+                next[i] += share * w;
+            }
+        }
+
+        let delta: f64 = next.iter().zip(&rank).map(|(a, b)| (a - b).abs()).sum();
+        rank = next;
+        if delta < CONVERGENCE_TOLERANCE {
+            break;
+        }
+    }
+
+    rank
+}
+
+/// État de la file de priorité pour Dijkstra (min-heap sur la distance)
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct HeapState {
+    dist: f64,
+    node: usize,
+}
+
+impl Eq for HeapState {}
+
+impl Ord for HeapState {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .dist
+            .partial_cmp(&self.dist)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+impl PartialOrd for HeapState {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Betweenness pondérée (algorithme de Brandes, 2001)
+///
+/// La longueur d'une arête est l'inverse de son poids : deux concepts
+/// fortement co-occurrents sont "proches". Normalisée par (n-1)(n-2)/2.
+fn betweenness(adj: &WeightedAdjacency) -> Vec<f64> {
+    let n = adj.len();
+    let mut centrality = vec![0.0; n];
+    if n < 3 {
+        return centrality;
+    }
+
+    for source in 0..n {
+        let mut stack: Vec<usize> = Vec::with_capacity(n);
+        let mut preds: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut sigma = vec![0.0_f64; n];
+        let mut dist = vec![f64::INFINITY; n];
+        let mut settled = vec![false; n];
+
+        sigma[source] = 1.0;
+        dist[source] = 0.0;
+        let mut heap = BinaryHeap::new();
+        heap.push(HeapState {
+            dist: 0.0,
+            node: source,
+        });
+
+        while let Some(HeapState { dist: d, node: v }) = heap.pop() {
+            if settled[v] {
+                continue;
+            }
+            settled[v] = true;
+            stack.push(v);
+
+            for &(w, weight) in &adj.neighbors[v] {
+                let candidate = d + 1.0 / weight;
+                if candidate < dist[w] - f64::EPSILON {
+                    dist[w] = candidate;
+                    sigma[w] = sigma[v];
+                    preds[w].clear();
+                    preds[w].push(v);
+                    heap.push(HeapState {
+                        dist: candidate,
+                        node: w,
+                    });
+                } else if (candidate - dist[w]).abs() <= f64::EPSILON {
+                    sigma[w] += sigma[v];
+                    preds[w].push(v);
+                }
+            }
+        }
+
+        // Accumulation des dépendances en ordre inverse de distance
+        let mut delta = vec![0.0_f64; n];
+        while let Some(w) = stack.pop() {
+            for &v in &preds[w] {
+                delta[v] += sigma[v] / sigma[w] * (1.0 + delta[w]);
+            }
+            if w != source {
+                centrality[w] += delta[w];
+            }
+        }
+    }
+
+    // Graphe non-dirigé : chaque paire est comptée deux fois
+    let norm = ((n - 1) * (n - 2)) as f64;
+    centrality.iter().map(|c| c / norm).collect()
+}
+
+/// Centralité de vecteur propre par itération de puissance
+///
+/// Itère sur (A + I) plutôt que A pour éviter l'oscillation sur les
+/// graphes bipartis ; les vecteurs propres sont identiques.
+fn eigenvector(adj: &WeightedAdjacency) -> Vec<f64> {
+    let n = adj.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mut x = vec![1.0 / (n as f64).sqrt(); n];
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut next = x.clone();
+        for (i, edges) in adj.neighbors.iter().enumerate() {
+            for &(j, w) in edges {
+                next[i] += w * x[j];
+            }
+        }
+
+        let norm = next.iter().map(|v| v * v).sum::<f64>().sqrt();
+        if norm == 0.0 {
+            return vec![0.0; n];
+        }
+        next.iter_mut().for_each(|v| *v /= norm);
+
+        let delta: f64 = next.iter().zip(&x).map(|(a, b)| (a - b).abs()).sum();
+        x = next;
+        if delta < CONVERGENCE_TOLERANCE {
+            break;
+        }
+    }
+
+    x
+}
+
+/// Calcule les trois centralités pour tous les concepts d'un texte
+///
+/// Le graphe est construit avec `config` (fenêtre, noyau, frontières, squelette).
+///
+/// # Returns
+/// Concepts triés par PageRank décroissant (égalités départagées par ordre alphabétique)
+pub fn compute_centrality(text: &str, config: &TopologyConfig) -> Vec<ConceptCentrality> {
+    let graph = topology::text_graph(text, config);
+    let adj = WeightedAdjacency::from_graph(&graph);
+
+    let pr = pagerank(&adj);
+    let bc = betweenness(&adj);
+    let ev = eigenvector(&adj);
+
+    let mut concepts: Vec<ConceptCentrality> = adj
+        .labels
+        .into_iter()
+        .enumerate()
+        .map(|(i, concept)| ConceptCentrality {
+            concept,
+            pagerank: pr[i],
+            betweenness: bc[i],
+            eigenvector: ev[i],
+        })
+        .collect();
+
+    concepts.sort_by(|a, b| {
+        b.pagerank
+            .partial_cmp(&a.pagerank)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.concept.cmp(&b.concept))
+    });
+    concepts
+}
+
+/// Retourne les k concepts les plus centraux d'un texte
+pub fn top_concepts(text: &str, k: usize, config: &TopologyConfig) -> Vec<ConceptCentrality> {
+    let mut concepts = compute_centrality(text, config);
+    concepts.truncate(k);
+    concepts
+}
+
+/// Compare les concepts structurants de A et B
+///
+/// # Arguments
+/// * `text_a` - Réponse standard (contrôle)
+/// * `text_b` - Réponse fracturée
+/// * `k` - Nombre de concepts retenus par texte
+/// * `config` - Construction des graphes de A et B
+pub fn concept_shift(
+    text_a: &str,
+    text_b: &str,
+    k: usize,
+    config: &TopologyConfig,
+) -> ConceptShift {
+    let top_a = top_concepts(text_a, k, config);
+    let top_b = top_concepts(text_b, k, config);
+
+    let ranks_a: HashMap<&str, usize> = top_a
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (c.concept.as_str(), i + 1))
+        .collect();
+    let ranks_b: HashMap<&str, usize> = top_b
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (c.concept.as_str(), i + 1))
+        .collect();
+
+    let gained = top_b
+        .iter()
+        .filter(|c| !ranks_a.contains_key(c.concept.as_str()))
+        .map(|c| c.concept.clone())
+        .collect();
+    let lost = top_a
+        .iter()
+        .filter(|c| !ranks_b.contains_key(c.concept.as_str()))
+        .map(|c| c.concept.clone())
+        .collect();
+    let reranked = top_a
+        .iter()
+        .filter_map(|c| {
+            let rank_a = ranks_a[c.concept.as_str()];
+            ranks_b
+                .get(c.concept.as_str())
+                .filter(|&&rank_b| rank_b != rank_a)
+                .map(|&rank_b| RankChange {
+                    concept: c.concept.clone(),
+                    rank_a,
+                    rank_b,
+                })
+        })
+        .collect();
+
+    ConceptShift {
+        top_a,
+        top_b,
+        gained,
+        lost,
+        reranked,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_text() {
+        assert!(compute_centrality("", &TopologyConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn test_pagerank_sums_to_one() {
+        let concepts = compute_centrality(
+            "Le chat mange la souris. La souris fuit le chat.",
+            &TopologyConfig::default(),
+        );
+        let total: f64 = concepts.iter().map(|c| c.pagerank).sum();
+        assert!(
+            (total - 1.0).abs() < 1e-6,
+            "Somme PageRank = 1, got {}",
+            total
+        );
+    }
+
+    #[test]
+    fn test_hub_is_most_central() {
+        // "noyau" revient entre chaque groupe de remplissage : c'est le pivot
+        let filler = "aa bb cc dd ee ff gg hh ii jj kk ll mm nn oo pp";
+        let text = format!(
+            "noyau {f} noyau {f} noyau alpha beta gamma delta epsilon zeta eta theta iota kappa lambda mu nu xi omicron noyau",
+            f = filler
+        );
+        let concepts = compute_centrality(&text, &TopologyConfig::default());
+
+        assert_eq!(concepts[0].concept, "noyau");
+        let max_bc = concepts
+            .iter()
+            .map(|c| c.betweenness)
+            .fold(0.0_f64, f64::max);
+        let hub = concepts.iter().find(|c| c.concept == "noyau").unwrap();
+        assert_eq!(
+            hub.betweenness, max_bc,
+            "Le pivot devrait maximiser la betweenness"
+        );
+    }
+
+    #[test]
+    fn test_betweenness_path() {
+        // Chaîne a - b - c : b est sur le seul chemin entre a et c
+        let mut graph: DiGraph<String, f64> = DiGraph::new();
+        let a = graph.add_node("a".into());
+        let b = graph.add_node("b".into());
+        let c = graph.add_node("c".into());
+        graph.add_edge(a, b, 1.0);
+        graph.add_edge(b, c, 1.0);
+
+        let bc = betweenness(&WeightedAdjacency::from_graph(&graph));
+        assert_eq!(bc, vec![0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_concept_shift() {
+        let a = "Le chat dort sur le canapé. Le chat ronronne.";
+        let b = "La gravité courbe l'espace-temps. La gravité attire la matière.";
+        let shift = concept_shift(a, b, 3, &TopologyConfig::default());
+
+        assert_eq!(shift.top_a.len(), 3);
+        assert_eq!(shift.gained.len(), 3, "Aucun concept commun");
+        assert_eq!(shift.lost.len(), 3);
+        assert!(shift.reranked.is_empty());
+
+        let same = concept_shift(a, a, 3, &TopologyConfig::default());
+        assert!(same.gained.is_empty() && same.lost.is_empty() && same.reranked.is_empty());
+    }
+}
@@ -0,0 +1,409 @@
+//! Module Null Model - Small-World Normalisé par Graphes Aléatoires
+//!
+//! Le `small_world_index` de Topology (C/L brut) n'a pas de référence :
+//! il sature sur les textes courts et n'est pas comparable entre textes
+//! de tailles différentes. Ce module compare le graphe observé à des
+//! graphes nuls de même taille pour calculer les indices standard :
+//!
+//! - σ = (C/C_r) / (L/L_r)     (Humphries & Gurney, 2008)
+//! - ω = L_r/L − C/C_l         (Telesford et al., 2011)
+//!
+//! C_r, L_r sont moyennés sur plusieurs répliques aléatoires seedées ;
+//! C_l est le clustering d'un anneau régulier de même degré moyen.
+//! Toutes les mesures portent sur le graphe non-dirigé non-pondéré et
+//! réutilisent les routines de Topology (CSR, comptage "forward" des
+//! triangles, sources BFS tirées par graine).
+//!
+//! Auteur: Julien DABERT
+//! LDSI - Lyapunov-Dabert Stability Index
+
+use petgraph::graph::DiGraph;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use super::rng::SeededRng;
+use super::topology::{self, Csr, TopologyConfig};
+
+/// Nombre de tentatives d'échange par arête (Maslov & Sneppen)
+const SWAPS_PER_EDGE: usize = 10;
+
+/// Modèle de graphe nul
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum NullModel {
+    /// Recâblage par échanges d'arêtes : séquence de degrés conservée
+    DegreePreserving,
+    /// Erdős–Rényi G(n, m) : même nombre de nœuds et d'arêtes
+    ErdosRenyi,
+    /// Tokens mélangés puis graphe de co-occurrence reconstruit
+    TokenShuffle,
+}
+
+/// Configuration du calcul small-world normalisé
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NullModelConfig {
+    /// Modèle de graphe nul
+    pub model: NullModel,
+    /// Graine du générateur (reproductibilité)
+    pub seed: u64,
+    /// Nombre de graphes nuls moyennés
+    pub replicates: usize,
+}
+
+impl Default for NullModelConfig {
+    fn default() -> Self {
+        Self {
+            model: NullModel::DegreePreserving,
+            seed: 42,
+            replicates: 20,
+        }
+    }
+}
+
+/// Résultat small-world normalisé
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmallWorldResult {
+    /// σ = (C/C_r) / (L/L_r) — > 1 indique un graphe small-world
+    pub sigma: f64,
+    /// ω = L_r/L − C/C_l — ≈ 0 small-world, → -1 régulier, → +1 aléatoire
+    pub omega: f64,
+    /// Clustering observé C
+    pub clustering: f64,
+    /// Longueur moyenne des chemins observée L
+    pub path_length: f64,
+    /// Clustering moyen des graphes nuls C_r
+    pub clustering_random: f64,
+    /// Longueur moyenne des chemins des graphes nuls L_r
+    pub path_length_random: f64,
+    /// Clustering de l'anneau régulier équivalent C_l
+    pub clustering_lattice: f64,
+    /// Modèle nul utilisé
+    pub model: NullModel,
+    /// Graine utilisée
+    pub seed: u64,
+    /// Nombre de répliques moyennées
+    pub replicates: usize,
+}
+
+/// Liste triée des arêtes (u < v) — l'ordre fixe garantit la reproductibilité
+fn edge_list(adj: &Csr) -> Vec<(usize, usize)> {
+    (0..adj.len())
+        .flat_map(|u| {
+            adj.neighbors(u)
+                .iter()
+                .filter(move |&&v| u < v)
+                .map(move |&v| (u, v))
+        })
+        .collect()
+}
+
+/// Recâblage de Maslov–Sneppen : (a,b),(c,d) → (a,d),(c,b)
+///
+/// Conserve exactement le degré de chaque nœud.
+fn degree_preserving_rewire(adj: &Csr, rng: &mut SeededRng) -> Csr {
+    let mut edges = edge_list(adj);
+    if edges.len() < 2 {
+        return Csr::symmetric(adj.len(), &edges);
+    }
+    let mut present: HashSet<(usize, usize)> = edges.iter().copied().collect();
+    let key = |u: usize, v: usize| (u.min(v), u.max(v));
+
+    for _ in 0..edges.len() * SWAPS_PER_EDGE {
+        let i = rng.below(edges.len());
+        let j = rng.below(edges.len());
+        if i == j {
+            continue;
+        }
+        let (a, b) = edges[i];
+        let (c, d) = if rng.below(2) == 0 {
+            edges[j]
+        } else {
+            (edges[j].1, edges[j].0)
+        };
+
+        // Refuse boucles et arêtes multiples
+        if a == d || c == b || present.contains(&key(a, d)) || present.contains(&key(c, b)) {
+            continue;
+        }
+
+        present.remove(&key(a, b));
+        present.remove(&key(c, d));
+        edges[i] = key(a, d);
+        edges[j] = key(c, b);
+        present.insert(edges[i]);
+        present.insert(edges[j]);
+    }
+
+    Csr::symmetric(adj.len(), &edges)
+}
+
+/// Graphe Erdős–Rényi G(n, m) : m arêtes tirées uniformément sans remise
+fn erdos_renyi(n: usize, m: usize, rng: &mut SeededRng) -> Csr {
+    let max_edges = n * n.saturating_sub(1) / 2;
+    let m = m.min(max_edges);
+    let mut present: HashSet<(usize, usize)> = HashSet::with_capacity(m);
+    let mut edges = Vec::with_capacity(m);
+
+    while edges.len() < m {
+        let u = rng.below(n);
+        let v = rng.below(n);
+        if u != v && present.insert((u.min(v), u.max(v))) {
+            edges.push((u, v));
+        }
+    }
+    Csr::symmetric(n, &edges)
+}
+
+/// Graphe de co-occurrence reconstruit sur les tokens mélangés
+///
+/// Les longueurs de segments sont conservées et le squelette est appliqué,
+/// comme pour le graphe observé.
+fn token_shuffle(
+    segments: &[Vec<String>],
+    config: &TopologyConfig,
+    rng: &mut SeededRng,
+) -> DiGraph<String, f64> {
+    let mut tokens: Vec<String> = segments.concat();
+    rng.shuffle(&mut tokens);
+    let mut rest = tokens.as_slice();
+    let shuffled: Vec<Vec<String>> = segments
+        .iter()
+        .map(|segment| {
+            let (head, tail) = rest.split_at(segment.len());
+            rest = tail;
+            head.to_vec()
+        })
+        .collect();
+    let graph = topology::build_cooccurrence_graph(&shuffled, config);
+    topology::extract_backbone(&graph, config.backbone)
+}
+
+/// Anneau régulier : chaque nœud relié à ses k/2 voisins de chaque côté
+fn ring_lattice(n: usize, mean_degree: f64) -> Csr {
+    if n < 2 {
+        return Csr::symmetric(n, &[]);
+    }
+    let half = ((mean_degree / 2.0).round() as usize).clamp(1, ((n - 1) / 2).max(1));
+
+    let edges: Vec<(usize, usize)> = (0..n)
+        .flat_map(|u| (1..=half).map(move |offset| (u, (u + offset) % n)))
+        .collect();
+    Csr::symmetric(n, &edges)
+}
+
+/// Arcs d'un graphe nul orienté de même taille (recensement des motifs)
+///
+/// - DegreePreserving : échanges (a→b),(c→d) → (a→d),(c→b), degrés entrants
+///   et sortants conservés
+/// - ErdosRenyi : m arcs tirés uniformément parmi n(n-1)
+/// - TokenShuffle : graphe reconstruit sur les tokens mélangés, longueurs
+///   de segments conservées
+pub(crate) fn null_arcs(
+    arcs: &[(usize, usize)],
+    n: usize,
+    segments: &[Vec<String>],
+    config: &TopologyConfig,
+    model: NullModel,
+    rng: &mut SeededRng,
+) -> Vec<(usize, usize)> {
+    match model {
+        NullModel::DegreePreserving => {
+            let mut arcs = arcs.to_vec();
+            let mut present: HashSet<(usize, usize)> = arcs.iter().copied().collect();
+            if arcs.len() < 2 {
+                return arcs;
+            }
+            for _ in 0..arcs.len() * SWAPS_PER_EDGE {
+                let i = rng.below(arcs.len());
+                let j = rng.below(arcs.len());
+                let ((a, b), (c, d)) = (arcs[i], arcs[j]);
+                // Refuse boucles et arcs multiples
+                if i == j
+                    || a == d
+                    || c == b
+                    || present.contains(&(a, d))
+                    || present.contains(&(c, b))
+                {
+                    continue;
+                }
+                present.remove(&(a, b));
+                present.remove(&(c, d));
+                present.insert((a, d));
+                present.insert((c, b));
+                arcs[i] = (a, d);
+                arcs[j] = (c, b);
+            }
+            arcs
+        }
+        NullModel::ErdosRenyi => {
+            let m = arcs.len().min(n * n.saturating_sub(1));
+            let mut present: HashSet<(usize, usize)> = HashSet::with_capacity(m);
+            let mut random = Vec::with_capacity(m);
+            while random.len() < m {
+                let (u, v) = (rng.below(n), rng.below(n));
+                if u != v && present.insert((u, v)) {
+                    random.push((u, v));
+                }
+            }
+            random
+        }
+        NullModel::TokenShuffle => topology::arcs(&token_shuffle(segments, config, rng)),
+    }
+}
+
+/// Calcule σ et ω d'un texte contre un modèle nul seedé
+///
+/// # Arguments
+/// * `text` - Texte à analyser
+/// * `topology_config` - Construction du graphe (fenêtre, noyau, frontières, squelette)
+/// * `config` - Modèle nul, graine et nombre de répliques
+///
+/// # Returns
+/// Structure SmallWorldResult (σ = ω = 0.0 si le graphe est trop petit)
+pub fn small_world(
+    text: &str,
+    topology_config: &TopologyConfig,
+    config: &NullModelConfig,
+) -> SmallWorldResult {
+    let segments = topology::tokenize_segments(text, topology_config.boundary);
+    let adj = Csr::undirected(&topology::text_graph(text, topology_config));
+    let n = adj.len();
+    let m = edge_list(&adj).len();
+
+    let clustering = topology::average_clustering(&adj);
+    let path_length = topology::average_path_length(&adj, topology_config);
+
+    let mut rng = SeededRng::new(config.seed);
+    let replicates = config.replicates.max(1);
+    let (mut c_sum, mut l_sum) = (0.0, 0.0);
+    for _ in 0..replicates {
+        let null = match config.model {
+            NullModel::DegreePreserving => degree_preserving_rewire(&adj, &mut rng),
+            NullModel::ErdosRenyi => erdos_renyi(n, m, &mut rng),
+            NullModel::TokenShuffle => {
+                Csr::undirected(&token_shuffle(&segments, topology_config, &mut rng))
+            }
+        };
+        c_sum += topology::average_clustering(&null);
+        l_sum += topology::average_path_length(&null, topology_config);
+    }
+    let clustering_random = c_sum / replicates as f64;
+    let path_length_random = l_sum / replicates as f64;
+
+    let mean_degree = if n > 0 {
+        2.0 * m as f64 / n as f64
+    } else {
+        0.0
+    };
+    let clustering_lattice = topology::average_clustering(&ring_lattice(n, mean_degree));
+
+    let sigma = if clustering_random > 0.0 && path_length > 0.0 && path_length_random > 0.0 {
+        (clustering / clustering_random) / (path_length / path_length_random)
+    } else {
+        0.0
+    };
+    let omega = if path_length > 0.0 && clustering_lattice > 0.0 {
+        path_length_random / path_length - clustering / clustering_lattice
+    } else {
+        0.0
+    };
+
+    SmallWorldResult {
+        sigma,
+        omega,
+        clustering,
+        path_length,
+        clustering_random,
+        path_length_random,
+        clustering_lattice,
+        model: config.model,
+        seed: config.seed,
+        replicates,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "La gravité courbe l'espace-temps autour des masses. \
+                        Les planètes suivent les géodésiques de l'espace-temps courbé. \
+                        La lumière elle-même suit ces géodésiques près des étoiles massives. \
+                        Einstein a prédit la déviation de la lumière par la gravité.";
+
+    fn degrees(adj: &Csr) -> Vec<usize> {
+        (0..adj.len()).map(|v| adj.degree(v)).collect()
+    }
+
+    #[test]
+    fn test_rewiring_preserves_degrees() {
+        let adj = Csr::undirected(&topology::text_graph(TEXT, &TopologyConfig::default()));
+        let mut rng = SeededRng::new(7);
+        let rewired = degree_preserving_rewire(&adj, &mut rng);
+
+        assert_eq!(degrees(&adj), degrees(&rewired));
+        assert_ne!(
+            edge_list(&adj),
+            edge_list(&rewired),
+            "Le recâblage devrait modifier des arêtes"
+        );
+    }
+
+    #[test]
+    fn test_erdos_renyi_size() {
+        let mut rng = SeededRng::new(1);
+        let adj = erdos_renyi(30, 60, &mut rng);
+        assert_eq!(adj.len(), 30);
+        assert_eq!(edge_list(&adj).len(), 60);
+    }
+
+    #[test]
+    fn test_seed_reproducibility() {
+        for model in [
+            NullModel::DegreePreserving,
+            NullModel::ErdosRenyi,
+            NullModel::TokenShuffle,
+        ] {
+            let config = NullModelConfig {
+                model,
+                seed: 1234,
+                replicates: 5,
+            };
+            let r1 = small_world(TEXT, &TopologyConfig::default(), &config);
+            let r2 = small_world(TEXT, &TopologyConfig::default(), &config);
+            assert_eq!(r1.sigma, r2.sigma, "{:?} non reproductible", model);
+            assert_eq!(r1.omega, r2.omega);
+            assert_eq!(r1.seed, 1234);
+            assert_eq!(r1.replicates, 5);
+            assert!(r1.sigma.is_finite() && r1.omega.is_finite());
+        }
+    }
+
+    #[test]
+    fn test_lattice_clustering() {
+        // Anneau k=4 : C = 3(k-2) / 4(k-1) = 0.5
+        let lattice = ring_lattice(20, 4.0);
+        assert!((topology::average_clustering(&lattice) - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_topology_config_applied() {
+        let narrow = TopologyConfig {
+            window: 2,
+            ..TopologyConfig::default()
+        };
+        let config = NullModelConfig::default();
+        let wide = small_world(TEXT, &TopologyConfig::default(), &config);
+        let chain = small_world(TEXT, &narrow, &config);
+        assert!(
+            chain.path_length > wide.path_length,
+            "Une fenêtre de 2 devrait allonger les chemins"
+        );
+    }
+
+    #[test]
+    fn test_empty_text() {
+        let result = small_world("", &TopologyConfig::default(), &NullModelConfig::default());
+        assert_eq!(result.sigma, 0.0);
+        assert_eq!(result.omega, 0.0);
+    }
+}
@@ -0,0 +1,38 @@
+//! Module Rng - Générateur Pseudo-Aléatoire Seedé
+//!
+//! Les tirages aléatoires de LDSI (modèles nuls, échantillonnage des
+//! chemins) doivent être reproductibles à l'identique : pas de dépendance
+//! à l'horloge ni au générateur du système.
+//!
+//! Auteur: Julien DABERT
+//! LDSI - Lyapunov-Dabert Stability Index
+
+/// Générateur pseudo-aléatoire SplitMix64 (déterministe, pas crypto)
+pub(crate) struct SeededRng(u64);
+
+impl SeededRng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Entier uniforme dans [0, n)
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Mélange de Fisher–Yates
+    pub(crate) fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
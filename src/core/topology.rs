@@ -5,15 +5,23 @@
 //!
 //! Les arêtes sont pondérées par décroissance inverse de la distance :
 //! weight = 1.0 / (distance + 1), capturant les associations long-range
-//! sans noyer le graphe dans le bruit.
+//! sans noyer le graphe dans le bruit. La fenêtre, le noyau, les
+//! frontières textuelles et le squelette (backbone) sont réglables via
+//! `TopologyConfig`.
 //!
 //! Auteur: Julien DABERT
 //! LDSI - Lyapunov-Dabert Stability Index
 
-use petgraph::algo::connected_components;
+use petgraph::algo::tarjan_scc;
 use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::EdgeRef;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet, VecDeque};
 
+use super::null_model::{self, NullModel, NullModelConfig};
+use super::rng::SeededRng;
+
 /// Résultat détaillé de l'analyse topologique
 #[derive(Debug, Clone)]
 pub struct TopologyResult {
@@ -34,88 +42,580 @@ pub struct TopologyResult {
     /// Longueur moyenne des chemins (approximation)
     pub avg_path_length: f64,
     /// Indicateur Small-World (clustering / path_length)
+    /// Non normalisé : voir `null_model::small_world` pour σ et ω
     pub small_world_index: f64,
     /// Degré moyen des nœuds
     pub avg_degree: f64,
+    /// Mesures orientées (ordre des mots)
+    pub directed: DirectedMetrics,
+    /// Distributions de degré et de force, ajustement loi de puissance
+    pub degrees: DegreeDistribution,
+    /// Homologie persistante H0/H1 de la filtration par poids
+    pub persistence: PersistenceResult,
+    /// Métriques du graphe brut quand un squelette est extrait
+    /// (les champs ci-dessus portent alors sur le squelette)
+    pub raw: Option<Box<TopologyResult>>,
+}
+
+/// Mesures sensibles à l'ordre des mots
+///
+/// Un texte qui reprend les tournures de A garde ses arcs dans le même sens ;
+/// un texte brouillé multiplie les arcs réciproques et les cycles.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DirectedMetrics {
+    /// Part des arcs u → v dont l'arc v → u existe aussi
+    pub reciprocity: f64,
+    /// Corrélation de Pearson entre degrés entrant et sortant
+    pub degree_correlation: f64,
+    /// Clustering orienté moyen (Fagiolo)
+    pub clustering: f64,
+    /// Nombre de composantes fortement connexes
+    pub scc_count: usize,
+    /// Taille de la plus grande composante fortement connexe
+    pub largest_scc_size: usize,
+    /// Ratio plus grande CFC / total nodes
+    pub largest_scc_ratio: f64,
+    /// Hiérarchie de flux : part des arcs hors de tout cycle (1 = DAG)
+    pub flow_hierarchy: f64,
+}
+
+/// Distributions de degré (non-dirigé) et de force (somme des poids)
+///
+/// Un texte naturel produit une queue lourde ; la récitation et le
+/// word salad s'en écartent chacun à leur manière.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DegreeDistribution {
+    /// Nombre de nœuds par degré (index = degré)
+    pub degree_histogram: Vec<usize>,
+    /// Degré maximal
+    pub max_degree: usize,
+    /// Force moyenne (somme des poids entrants et sortants)
+    pub mean_strength: f64,
+    /// Force maximale
+    pub max_strength: f64,
+    /// Ajustement de la queue des degrés
+    pub degree_fit: PowerLawFit,
+    /// Ajustement de la queue des forces
+    pub strength_fit: PowerLawFit,
+}
+
+/// Ajustement par maximum de vraisemblance d'une loi de puissance
+/// (Clauset, Shalizi & Newman 2009), comparée à une log-normale
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PowerLawFit {
+    /// Exposant α de p(x) ∝ x^-α
+    pub alpha: f64,
+    /// Borne inférieure de la queue, choisie en minimisant KS
+    pub x_min: f64,
+    /// Statistique de Kolmogorov–Smirnov sur la queue
+    pub ks: f64,
+    /// Nombre d'observations x ≥ x_min
+    pub tail_size: usize,
+    /// Paramètre μ de la log-normale ajustée sur la queue
+    pub lognormal_mu: f64,
+    /// Paramètre σ de la log-normale ajustée sur la queue
+    pub lognormal_sigma: f64,
+    /// Rapport de log-vraisemblance R (> 0 favorise la loi de puissance)
+    pub likelihood_ratio: f64,
+    /// p-value du test de Vuong sur le signe de R
+    pub p_value: f64,
+}
+
+/// Modèle de queue retenu par le test de vraisemblance
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TailModel {
+    PowerLaw,
+    LogNormal,
+    /// Pas assez d'observations ou p-value ≥ 0.1
+    Undecided,
+}
+
+impl PowerLawFit {
+    /// Modèle favorisé au seuil p < 0.1 (convention CSN)
+    pub fn preferred(&self) -> TailModel {
+        if self.tail_size == 0 || self.p_value >= 0.1 {
+            TailModel::Undecided
+        } else if self.likelihood_ratio > 0.0 {
+            TailModel::PowerLaw
+        } else {
+            TailModel::LogNormal
+        }
+    }
+}
+
+/// Homologie persistante du complexe de cliques filtré par poids décroissant
+///
+/// Le paramètre de filtration est t = 1 - ln(1 + w) / ln(1 + w_max) ∈ [0, 1) :
+/// les nœuds sont présents dès t = 0, les arêtes lourdes entrent en premier
+/// et chaque triangle entre avec sa plus légère arête. L'échelle logarithmique
+/// évite que quelques paires de mots-outils très lourdes écrasent la filtration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PersistenceResult {
+    /// Intervalles de H0 (composantes)
+    pub h0: Vec<PersistenceInterval>,
+    /// Intervalles de H1 (cycles non remplis par des triangles)
+    pub h1: Vec<PersistenceInterval>,
+    /// Seuils t auxquels les courbes de Betti sont échantillonnées
+    pub thresholds: Vec<f64>,
+    /// β0(t) : nombre de composantes vivantes
+    pub betti0: Vec<usize>,
+    /// β1(t) : nombre de cycles vivants
+    pub betti1: Vec<usize>,
+    /// Somme des durées de vie finies de H0
+    pub total_persistence_h0: f64,
+    /// Somme des durées de vie de H1 (les cycles essentiels meurent à t = 1)
+    pub total_persistence_h1: f64,
+    /// Nombre de nœuds retenus dans la filtration
+    pub vertices: usize,
+    /// Vrai si le plafond de nœuds a tronqué le graphe
+    pub truncated: bool,
+}
+
+/// Intervalle de persistance [naissance, mort)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PersistenceInterval {
+    pub birth: f64,
+    /// None pour une classe essentielle (jamais tuée)
+    pub death: Option<f64>,
 }
 
-/// Distance maximale de co-occurrence (tokens).
+/// Nombre de seuils des courbes de Betti (pas de 0.05 sur [0, 1])
+const BETTI_STEPS: usize = 21;
+
+/// Taille minimale de queue pour tenter un ajustement
+const MIN_TAIL: usize = 10;
+
+/// Distance maximale de co-occurrence par défaut (tokens).
 /// Au-delà, le poids décroît jusqu'à être négligeable.
-/// Chaque arête reçoit un poids = 1.0 / (distance + 1).
 const MAX_WINDOW: usize = 15;
 
+/// Noyau de décroissance du poids d'une arête selon la distance d
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum DecayKernel {
+    /// 1 / (d + 1) — historique
+    Inverse,
+    /// exp(-d / scale)
+    Exponential { scale: f64 },
+    /// exp(-d² / 2σ²)
+    Gaussian { sigma: f64 },
+    /// 1 — toutes les co-occurrences de la fenêtre se valent
+    Flat,
+}
+
+impl DecayKernel {
+    /// Poids d'une co-occurrence à distance `distance` (≥ 1)
+    pub fn weight(&self, distance: usize) -> f64 {
+        let d = distance as f64;
+        match *self {
+            DecayKernel::Inverse => 1.0 / (d + 1.0),
+            DecayKernel::Exponential { scale } => (-d / scale).exp(),
+            DecayKernel::Gaussian { sigma } => (-(d * d) / (2.0 * sigma * sigma)).exp(),
+            DecayKernel::Flat => 1.0,
+        }
+    }
+}
+
+/// Frontières que la fenêtre de co-occurrence ne traverse pas
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum BoundaryPolicy {
+    /// La fenêtre glisse sur tout le texte — historique
+    Ignore,
+    /// La fenêtre s'arrête aux fins de phrase (. ! ? …) et de paragraphe
+    Sentence,
+    /// La fenêtre s'arrête aux lignes vides
+    Paragraph,
+}
+
+/// Extraction du squelette (backbone) avant le calcul des métriques
+///
+/// Avec une fenêtre de 15 tokens, presque toute paire de mots proches
+/// reçoit une arête : le squelette écarte les arêtes de bruit.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum Backbone {
+    /// Graphe complet — historique
+    #[default]
+    None,
+    /// Filtre de disparité (Serrano et al. 2009) au seuil de significativité α
+    Disparity { alpha: f64 },
+    /// Seuil global sur le poids cumulé
+    Threshold { min_weight: f64 },
+    /// k arêtes les plus lourdes par nœud (sortantes de la source ou entrantes de la cible)
+    TopK { k: usize },
+}
+
+/// Configuration de la construction du graphe de co-occurrence
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct TopologyConfig {
+    /// Taille de la fenêtre glissante (tokens)
+    pub window: usize,
+    /// Noyau de pondération par la distance
+    pub kernel: DecayKernel,
+    /// Frontières textuelles respectées par la fenêtre
+    pub boundary: BoundaryPolicy,
+    /// Poids cumulé minimal pour conserver une arête
+    pub min_edge_weight: f64,
+    /// Nombre maximal de sources BFS pour la longueur moyenne des chemins
+    pub path_sample_size: usize,
+    /// Graine du tirage des sources BFS
+    pub path_sample_seed: u64,
+    /// Squelette extrait avant le calcul des métriques
+    pub backbone: Backbone,
+    /// Nombre maximal de nœuds (les plus forts) de la filtration
+    /// d'homologie persistante ; 0 désactive le calcul
+    pub persistence_max_vertices: usize,
+}
+
+impl Default for TopologyConfig {
+    fn default() -> Self {
+        Self {
+            window: MAX_WINDOW,
+            kernel: DecayKernel::Inverse,
+            boundary: BoundaryPolicy::Ignore,
+            min_edge_weight: 0.0,
+            path_sample_size: 50,
+            path_sample_seed: 42,
+            backbone: Backbone::None,
+            persistence_max_vertices: 100,
+        }
+    }
+}
+
+/// Configuration de graphe refusée
+#[derive(Debug, Clone, PartialEq)]
+pub enum TopologyConfigError {
+    /// Fenêtre trop courte pour relier deux tokens
+    Window(usize),
+    /// Largeur de noyau nulle, négative ou non finie
+    KernelWidth(f64),
+}
+
+impl std::fmt::Display for TopologyConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TopologyConfigError::Window(w) => {
+                write!(f, "Fenêtre de co-occurrence invalide: {} (minimum 2)", w)
+            }
+            TopologyConfigError::KernelWidth(w) => {
+                write!(f, "Largeur de noyau invalide: {} (doit être > 0)", w)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TopologyConfigError {}
+
+impl TopologyConfig {
+    /// Refuse les configurations qui produiraient un graphe dégénéré
+    pub fn validate(&self) -> Result<(), TopologyConfigError> {
+        if self.window < 2 {
+            return Err(TopologyConfigError::Window(self.window));
+        }
+        match self.kernel {
+            DecayKernel::Exponential { scale: width } | DecayKernel::Gaussian { sigma: width }
+                if !(width.is_finite() && width > 0.0) =>
+            {
+                Err(TopologyConfigError::KernelWidth(width))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 /// Tokenize simplement (même logique que entropy pour cohérence)
-fn tokenize(text: &str) -> Vec<String> {
+pub(crate) fn tokenize(text: &str) -> Vec<String> {
     text.split(|c: char| !c.is_alphabetic())
         .filter(|s| !s.is_empty() && s.len() > 1)
         .map(|s| s.to_lowercase())
         .collect()
 }
 
-/// Ajoute ou incrémente une arête pondérée entre deux nœuds
-fn add_or_increment_edge(
-    graph: &mut DiGraph<String, f64>,
-    from: NodeIndex,
-    to: NodeIndex,
-    weight: f64,
-) {
-    if from == to {
-        return;
-    }
-    match graph.find_edge(from, to) {
-        Some(edge) => {
-            if let Some(w) = graph.edge_weight_mut(edge) {
-                *w += weight;
+/// Découpe le texte en paragraphes (séparés par une ou plusieurs lignes vides)
+fn split_paragraphs(text: &str) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut current = String::new();
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(std::mem::take(&mut current));
             }
+        } else {
+            current.push_str(line);
+            current.push('\n');
         }
-        None => {
-            graph.add_edge(from, to, weight);
-        }
     }
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+    paragraphs
 }
 
-/// Crée les arêtes de co-occurrence pour une fenêtre de tokens
-/// avec pondération par décroissance inverse de la distance.
-fn add_window_edges(
-    graph: &mut DiGraph<String, f64>,
-    window: &[String],
-    node_indices: &HashMap<String, NodeIndex>,
-) {
-    for i in 0..window.len() {
-        for j in (i + 1)..window.len() {
-            let distance = j - i;
-            let weight = 1.0 / (distance as f64 + 1.0);
-            let from = node_indices[&window[i]];
-            let to = node_indices[&window[j]];
-            add_or_increment_edge(graph, from, to, weight);
-        }
-    }
+/// Découpe le texte en segments tokenisés selon la politique de frontières
+///
+/// Heuristique sans NLP : les abréviations ("M. Dupont") coupent la phrase.
+pub(crate) fn tokenize_segments(text: &str, boundary: BoundaryPolicy) -> Vec<Vec<String>> {
+    let segments: Vec<String> = match boundary {
+        BoundaryPolicy::Ignore => vec![text.to_string()],
+        BoundaryPolicy::Paragraph => split_paragraphs(text),
+        BoundaryPolicy::Sentence => split_paragraphs(text)
+            .iter()
+            .flat_map(|p| {
+                p.split(['.', '!', '?', '…'])
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .collect(),
+    };
+
+    segments
+        .iter()
+        .map(|s| tokenize(s))
+        .filter(|tokens| !tokens.is_empty())
+        .collect()
 }
 
 /// Construit un graphe dirigé de co-occurrence
 ///
 /// - Nœuds = mots uniques (lemmatisés en minuscules)
-/// - Arêtes = co-occurrence dans une fenêtre glissante, pondérées par 1/(d+1)
-fn build_cooccurrence_graph(tokens: &[String]) -> DiGraph<String, f64> {
+/// - Arêtes = co-occurrence dans une fenêtre glissante, pondérées par le noyau
+/// - Chaque segment a ses propres fenêtres : aucune arête ne traverse une frontière
+///
+/// Les poids sont cumulés dans une table de hachage (O(1) par paire) puis
+/// insérés une seule fois, dans l'ordre de première apparition.
+pub(crate) fn build_cooccurrence_graph(
+    segments: &[Vec<String>],
+    config: &TopologyConfig,
+) -> DiGraph<String, f64> {
     let mut graph: DiGraph<String, f64> = DiGraph::new();
-    let mut node_indices: HashMap<String, NodeIndex> = HashMap::new();
+    let mut node_indices: HashMap<&str, NodeIndex> = HashMap::new();
 
-    // Créer les nœuds
-    for token in tokens {
-        node_indices
-            .entry(token.clone())
-            .or_insert_with(|| graph.add_node(token.clone()));
-    }
+    // Créer les nœuds et traduire les segments en indices
+    let indexed: Vec<Vec<NodeIndex>> = segments
+        .iter()
+        .map(|tokens| {
+            tokens
+                .iter()
+                .map(|token| {
+                    *node_indices
+                        .entry(token.as_str())
+                        .or_insert_with(|| graph.add_node(token.clone()))
+                })
+                .collect()
+        })
+        .collect();
+
+    // Poids du noyau précalculés par distance
+    let kernel_weights: Vec<f64> = (0..config.window.max(1))
+        .map(|d| config.kernel.weight(d))
+        .collect();
+
+    // Accumulateur d'arêtes : (from, to) -> position dans `edges`
+    let mut slots: HashMap<(NodeIndex, NodeIndex), usize> = HashMap::new();
+    let mut edges: Vec<(NodeIndex, NodeIndex, f64)> = Vec::new();
 
     // Créer les arêtes par fenêtre glissante avec décroissance
-    if tokens.len() >= 2 {
-        let window_size = MAX_WINDOW.min(tokens.len());
+    for tokens in &indexed {
+        let window_size = config.window.min(tokens.len());
+        if window_size < 2 {
+            continue;
+        }
         for window in tokens.windows(window_size) {
-            add_window_edges(&mut graph, window, &node_indices);
+            for i in 0..window.len() {
+                for j in (i + 1)..window.len() {
+                    let (from, to) = (window[i], window[j]);
+                    if from == to {
+                        continue;
+                    }
+                    let weight = kernel_weights[j - i];
+                    match slots.entry((from, to)) {
+                        Entry::Occupied(slot) => edges[*slot.get()].2 += weight,
+                        Entry::Vacant(slot) => {
+                            slot.insert(edges.len());
+                            edges.push((from, to, weight));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    for (from, to, weight) in edges {
+        if weight >= config.min_edge_weight {
+            graph.add_edge(from, to, weight);
+        }
+    }
+
+    graph
+}
+
+/// Construit le graphe de co-occurrence d'un texte brut, squelette appliqué
+pub(crate) fn text_graph(text: &str, config: &TopologyConfig) -> DiGraph<String, f64> {
+    let graph = build_cooccurrence_graph(&tokenize_segments(text, config.boundary), config);
+    extract_backbone(&graph, config.backbone)
+}
+
+/// Extrait le squelette d'un graphe pondéré
+///
+/// Tous les nœuds sont conservés (mêmes indices) ; seules les arêtes sont
+/// filtrées, si bien que la fragmentation révélée par le squelette reste
+/// visible dans les composantes et le LCC.
+///
+/// Le filtre de disparité est appliqué des deux côtés de l'arc u → v :
+/// α = (1 - w/s)^(k-1) sur les arcs sortants de u et sur les arcs entrants
+/// de v ; l'arc est conservé s'il est significatif pour l'un des deux.
+pub fn extract_backbone(graph: &DiGraph<String, f64>, backbone: Backbone) -> DiGraph<String, f64> {
+    let n = graph.node_count();
+    let keep: Vec<bool> = match backbone {
+        Backbone::None => return graph.clone(),
+        Backbone::Threshold { min_weight } => graph
+            .edge_references()
+            .map(|e| *e.weight() >= min_weight)
+            .collect(),
+        Backbone::Disparity { alpha } => {
+            let (mut out_strength, mut in_strength) = (vec![0.0f64; n], vec![0.0f64; n]);
+            let (mut out_degree, mut in_degree) = (vec![0usize; n], vec![0usize; n]);
+            for e in graph.edge_references() {
+                out_strength[e.source().index()] += *e.weight();
+                out_degree[e.source().index()] += 1;
+                in_strength[e.target().index()] += *e.weight();
+                in_degree[e.target().index()] += 1;
+            }
+            let significance = |weight: f64, strength: f64, degree: usize| {
+                (1.0 - weight / strength).powi(degree as i32 - 1)
+            };
+            graph
+                .edge_references()
+                .map(|e| {
+                    let (u, v, w) = (e.source().index(), e.target().index(), *e.weight());
+                    significance(w, out_strength[u], out_degree[u]) < alpha
+                        || significance(w, in_strength[v], in_degree[v]) < alpha
+                })
+                .collect()
+        }
+        Backbone::TopK { k } => {
+            let mut keep = vec![false; graph.edge_count()];
+            let mut outgoing: Vec<Vec<usize>> = vec![Vec::new(); n];
+            let mut incoming: Vec<Vec<usize>> = vec![Vec::new(); n];
+            for e in graph.edge_references() {
+                outgoing[e.source().index()].push(e.id().index());
+                incoming[e.target().index()].push(e.id().index());
+            }
+            let weights: Vec<f64> = graph.edge_references().map(|e| *e.weight()).collect();
+            for edges in outgoing.iter_mut().chain(incoming.iter_mut()) {
+                // Poids décroissant, ordre d'insertion en cas d'égalité
+                edges.sort_by(|&a, &b| weights[b].total_cmp(&weights[a]).then(a.cmp(&b)));
+                for &edge in edges.iter().take(k) {
+                    keep[edge] = true;
+                }
+            }
+            keep
         }
+    };
+
+    let mut filtered = DiGraph::with_capacity(n, keep.iter().filter(|&&k| k).count());
+    for node in graph.node_weights() {
+        filtered.add_node(node.clone());
+    }
+    for (e, _) in graph.edge_references().zip(&keep).filter(|(_, k)| **k) {
+        filtered.add_edge(e.source(), e.target(), *e.weight());
     }
+    filtered
+}
 
+/// Arcs (source, cible) du graphe, par indice de nœud
+pub(crate) fn arcs(graph: &DiGraph<String, f64>) -> Vec<(usize, usize)> {
     graph
+        .edge_references()
+        .map(|e| (e.source().index(), e.target().index()))
+        .collect()
+}
+
+/// Adjacence compacte CSR (Compressed Sparse Row)
+///
+/// Les voisins du nœud v sont `targets[offsets[v]..offsets[v + 1]]`,
+/// triés et sans doublon.
+pub(crate) struct Csr {
+    offsets: Vec<usize>,
+    targets: Vec<usize>,
+}
+
+impl Csr {
+    /// Construit le CSR à partir d'une liste de paires (u, v)
+    pub(crate) fn from_pairs(n: usize, pairs: &[(usize, usize)]) -> Self {
+        let mut offsets = vec![0usize; n + 1];
+        for &(u, _) in pairs {
+            offsets[u + 1] += 1;
+        }
+        for v in 0..n {
+            offsets[v + 1] += offsets[v];
+        }
+
+        let mut cursor = offsets.clone();
+        let mut targets = vec![0usize; pairs.len()];
+        for &(u, v) in pairs {
+            targets[cursor[u]] = v;
+            cursor[u] += 1;
+        }
+
+        // Tri et déduplication de chaque ligne, puis compactage
+        let mut compact_offsets = Vec::with_capacity(n + 1);
+        let mut compact_targets = Vec::with_capacity(targets.len());
+        compact_offsets.push(0);
+        for v in 0..n {
+            let row = &mut targets[offsets[v]..offsets[v + 1]];
+            row.sort_unstable();
+            let mut last = None;
+            for &t in row.iter() {
+                if last != Some(t) {
+                    compact_targets.push(t);
+                    last = Some(t);
+                }
+            }
+            compact_offsets.push(compact_targets.len());
+        }
+
+        Self {
+            offsets: compact_offsets,
+            targets: compact_targets,
+        }
+    }
+
+    /// Voisins sortants (sens de lecture du texte)
+    pub(crate) fn outgoing(graph: &DiGraph<String, f64>) -> Self {
+        Self::from_pairs(graph.node_count(), &arcs(graph))
+    }
+
+    /// Voisins non-dirigés (arêtes symétrisées, boucles exclues)
+    pub(crate) fn undirected(graph: &DiGraph<String, f64>) -> Self {
+        Self::symmetric(graph.node_count(), &arcs(graph))
+    }
+
+    /// Voisins non-dirigés d'une liste d'arcs (boucles exclues)
+    pub(crate) fn symmetric(n: usize, arcs: &[(usize, usize)]) -> Self {
+        let pairs: Vec<(usize, usize)> = arcs
+            .iter()
+            .filter(|(a, b)| a != b)
+            .flat_map(|&(a, b)| [(a, b), (b, a)])
+            .collect();
+        Self::from_pairs(n, &pairs)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub(crate) fn neighbors(&self, node: usize) -> &[usize] {
+        &self.targets[self.offsets[node]..self.offsets[node + 1]]
+    }
+
+    pub(crate) fn degree(&self, node: usize) -> usize {
+        self.offsets[node + 1] - self.offsets[node]
+    }
+
+    /// Présence de l'arc u → v (lignes triées : recherche dichotomique)
+    pub(crate) fn contains(&self, u: usize, v: usize) -> bool {
+        self.neighbors(u).binary_search(&v).is_ok()
+    }
 }
 
 /// Calcule la densité du graphe
@@ -127,134 +627,1024 @@ fn compute_density(node_count: usize, edge_count: usize) -> f64 {
     edge_count as f64 / max_edges as f64
 }
 
-/// Calcule le coefficient de clustering local d'un nœud
-fn local_clustering(graph: &DiGraph<String, f64>, node: NodeIndex) -> f64 {
-    let neighbors: HashSet<NodeIndex> = graph.neighbors_undirected(node).collect();
+/// Énumère chaque triangle non-dirigé exactement une fois en O(m·√m)
+///
+/// Algorithme "forward" : chaque arête est orientée vers le nœud de rang
+/// (degré, indice) supérieur, puis chaque triangle est trouvé exactement
+/// une fois par intersection des listes orientées.
+fn for_each_triangle(adj: &Csr, mut visit: impl FnMut(usize, usize, usize)) {
+    let n = adj.len();
+    let rank = |v: usize| (adj.degree(v), v);
+
+    let forward: Vec<Vec<usize>> = (0..n)
+        .map(|v| {
+            adj.neighbors(v)
+                .iter()
+                .copied()
+                .filter(|&w| rank(w) > rank(v))
+                .collect()
+        })
+        .collect();
+
+    for v in 0..n {
+        for &w in &forward[v] {
+            // Intersection de deux listes triées par indice
+            let (a, b) = (&forward[v], &forward[w]);
+            let (mut i, mut j) = (0, 0);
+            while i < a.len() && j < b.len() {
+                match a[i].cmp(&b[j]) {
+                    std::cmp::Ordering::Less => i += 1,
+                    std::cmp::Ordering::Greater => j += 1,
+                    std::cmp::Ordering::Equal => {
+                        visit(v, w, a[i]);
+                        i += 1;
+                        j += 1;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Compte les triangles incidents à chaque nœud
+pub(crate) fn triangles_per_node(adj: &Csr) -> Vec<usize> {
+    let mut triangles = vec![0usize; adj.len()];
+    for_each_triangle(adj, |a, b, c| {
+        triangles[a] += 1;
+        triangles[b] += 1;
+        triangles[c] += 1;
+    });
+    triangles
+}
+
+/// Calcule le coefficient de clustering moyen
+///
+/// C(v) = 2·T(v) / (k(k-1)) sur le graphe non-dirigé, 0 si k < 2.
+pub(crate) fn average_clustering(adj: &Csr) -> f64 {
+    let n = adj.len();
+    if n == 0 {
+        return 0.0;
+    }
+
+    let triangles = triangles_per_node(adj);
+    let sum: f64 = (0..n)
+        .map(|v| {
+            let k = adj.degree(v);
+            if k < 2 {
+                0.0
+            } else {
+                (2 * triangles[v]) as f64 / (k * (k - 1)) as f64
+            }
+        })
+        .sum();
+    sum / n as f64
+}
+
+/// Sélectionne les nœuds sources pour l'estimation des chemins
+///
+/// Tous les nœuds si n ≤ sample_size, sinon un tirage uniforme seedé
+/// sans remise (Fisher–Yates partiel), indépendant de l'ordre d'insertion.
+fn sample_sources(n: usize, sample_size: usize, seed: u64) -> Vec<usize> {
+    let mut nodes: Vec<usize> = (0..n).collect();
+    if n <= sample_size {
+        return nodes;
+    }
+
+    let mut rng = SeededRng::new(seed);
+    for i in 0..sample_size {
+        let j = i + rng.below(n - i);
+        nodes.swap(i, j);
+    }
+    nodes.truncate(sample_size);
+    nodes.sort_unstable();
+    nodes
+}
+
+/// Calcule la longueur moyenne des plus courts chemins (BFS, échantillonné)
+pub(crate) fn average_path_length(adj: &Csr, config: &TopologyConfig) -> f64 {
+    let n = adj.len();
+    if n < 2 {
+        return 0.0;
+    }
+
+    let mut total_length = 0usize;
+    let mut path_count = 0usize;
+    let mut dist = vec![usize::MAX; n];
+    let mut queue: VecDeque<usize> = VecDeque::new();
+
+    for source in sample_sources(n, config.path_sample_size, config.path_sample_seed) {
+        dist.iter_mut().for_each(|d| *d = usize::MAX);
+        dist[source] = 0;
+        queue.push_back(source);
+
+        while let Some(current) = queue.pop_front() {
+            let new_dist = dist[current] + 1;
+            for &neighbor in adj.neighbors(current) {
+                if dist[neighbor] == usize::MAX {
+                    dist[neighbor] = new_dist;
+                    queue.push_back(neighbor);
+                    total_length += new_dist;
+                    path_count += 1;
+                }
+            }
+        }
+    }
+
+    if path_count > 0 {
+        total_length as f64 / path_count as f64
+    } else {
+        0.0
+    }
+}
+
+/// Nombre de composantes connexes et taille de la plus grande (LCC)
+fn connected_components(adj: &Csr) -> (usize, usize) {
+    let n = adj.len();
+    let mut visited = vec![false; n];
+    let mut queue: VecDeque<usize> = VecDeque::new();
+    let (mut components, mut max_size) = (0, 0);
+
+    for start in 0..n {
+        if visited[start] {
+            continue;
+        }
+        components += 1;
+        visited[start] = true;
+        queue.push_back(start);
+        let mut size = 0;
+
+        while let Some(current) = queue.pop_front() {
+            size += 1;
+            for &neighbor in adj.neighbors(current) {
+                if !visited[neighbor] {
+                    visited[neighbor] = true;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        max_size = max_size.max(size);
+    }
+
+    (components, max_size)
+}
+
+/// Mesures orientées du graphe (arc u → v = u précède v dans le texte)
+///
+/// Calculées sur le graphe binaire : les poids de co-occurrence sont ignorés.
+pub(crate) fn directed_metrics(graph: &DiGraph<String, f64>) -> DirectedMetrics {
+    let n = graph.node_count();
+    let m = graph.edge_count();
+    if n == 0 || m == 0 {
+        return DirectedMetrics {
+            scc_count: n,
+            largest_scc_size: n.min(1),
+            largest_scc_ratio: if n > 0 { 1.0 / n as f64 } else { 0.0 },
+            ..Default::default()
+        };
+    }
+
+    let out = Csr::outgoing(graph);
+    let mut in_degree = vec![0usize; n];
+    let mut reciprocal = vec![0usize; n];
+    for (u, count) in reciprocal.iter_mut().enumerate() {
+        for &v in out.neighbors(u) {
+            in_degree[v] += 1;
+            if out.contains(v, u) {
+                *count += 1;
+            }
+        }
+    }
+
+    // Réciprocité : part des arcs dont l'arc inverse existe
+    let reciprocity = reciprocal.iter().sum::<usize>() as f64 / m as f64;
+
+    // Corrélation de Pearson entre degrés entrant et sortant
+    let degree_correlation = {
+        // Degrés entrant et sortant ont la même moyenne m / n
+        let mean_degree = m as f64 / n as f64;
+        let (mut cov, mut var_in, mut var_out) = (0.0, 0.0, 0.0);
+        for (v, &degree) in in_degree.iter().enumerate() {
+            let d_in = degree as f64 - mean_degree;
+            let d_out = out.degree(v) as f64 - mean_degree;
+            cov += d_in * d_out;
+            var_in += d_in * d_in;
+            var_out += d_out * d_out;
+        }
+        if var_in > 0.0 && var_out > 0.0 {
+            cov / (var_in * var_out).sqrt()
+        } else {
+            0.0
+        }
+    };
+
+    // Clustering orienté de Fagiolo (2007) :
+    // C(i) = [(A + Aᵀ)³]ᵢᵢ / 2·[d_tot(d_tot - 1) - 2·d↔]
+    // Chaque triangle non-dirigé (i, j, k) contribue 2·s_ij·s_jk·s_ki
+    // à chacun de ses sommets, avec s_uv = a_uv + a_vu ∈ {1, 2}.
+    let clustering = {
+        let strength =
+            |u: usize, v: usize| out.contains(u, v) as usize + out.contains(v, u) as usize;
+        let mut cycles = vec![0usize; n];
+        for_each_triangle(&Csr::undirected(graph), |a, b, c| {
+            let weight = 2 * strength(a, b) * strength(b, c) * strength(c, a);
+            cycles[a] += weight;
+            cycles[b] += weight;
+            cycles[c] += weight;
+        });
+        let sum: f64 = (0..n)
+            .map(|v| {
+                let total = in_degree[v] + out.degree(v);
+                let denominator = 2 * (total * total.saturating_sub(1) - 2 * reciprocal[v]);
+                if denominator > 0 {
+                    cycles[v] as f64 / denominator as f64
+                } else {
+                    0.0
+                }
+            })
+            .sum();
+        sum / n as f64
+    };
+
+    // Composantes fortement connexes (Tarjan)
+    let mut scc_of = vec![0usize; n];
+    let sccs = tarjan_scc(graph);
+    for (id, component) in sccs.iter().enumerate() {
+        for node in component {
+            scc_of[node.index()] = id;
+        }
+    }
+    let largest_scc_size = sccs.iter().map(Vec::len).max().unwrap_or(0);
+
+    // Hiérarchie de flux (Luo & Magee) : part des arcs hors de tout cycle,
+    // c'est-à-dire reliant deux composantes fortement connexes distinctes
+    let acyclic_edges = graph
+        .edge_references()
+        .filter(|e| scc_of[e.source().index()] != scc_of[e.target().index()])
+        .count();
+
+    DirectedMetrics {
+        reciprocity,
+        degree_correlation,
+        clustering,
+        scc_count: sccs.len(),
+        largest_scc_size,
+        largest_scc_ratio: largest_scc_size as f64 / n as f64,
+        flow_hierarchy: acyclic_edges as f64 / m as f64,
+    }
+}
+
+/// ln erfc(x) pour x ≥ 0, précis dans la queue (Numerical Recipes, erfcc)
+fn ln_erfc_positive(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.5 * x);
+    let poly = -1.265_512_23
+        + t * (1.000_023_68
+            + t * (0.374_091_96
+                + t * (0.096_784_18
+                    + t * (-0.186_288_06
+                        + t * (0.278_868_07
+                            + t * (-1.135_203_98
+                                + t * (1.488_515_87 + t * (-0.822_152_23 + t * 0.170_872_77))))))));
+    t.ln() - x * x + poly
+}
+
+/// Fonction d'erreur complémentaire (erreur relative < 1.2e-7)
+fn erfc(x: f64) -> f64 {
+    if x >= 0.0 {
+        ln_erfc_positive(x).exp()
+    } else {
+        2.0 - ln_erfc_positive(-x).exp()
+    }
+}
+
+/// ln P(Z ≥ z) pour Z ~ N(0, 1)
+fn ln_normal_survival(z: f64) -> f64 {
+    let x = z / std::f64::consts::SQRT_2;
+    if x >= 0.0 {
+        ln_erfc_positive(x) - std::f64::consts::LN_2
+    } else {
+        (1.0 - 0.5 * erfc(-x)).ln()
+    }
+}
+
+/// Log-vraisemblance par observation d'une log-normale tronquée à `scale`
+fn truncated_lognormal_ll(log_x: f64, mu: f64, sigma: f64, log_scale: f64) -> f64 {
+    -log_x
+        - (sigma * (2.0 * std::f64::consts::PI).sqrt()).ln()
+        - (log_x - mu).powi(2) / (2.0 * sigma * sigma)
+        - ln_normal_survival((log_scale - mu) / sigma)
+}
+
+/// Maximum de vraisemblance de la log-normale tronquée à gauche
+///
+/// Nelder–Mead sur (μ, ln σ), départ à l'estimation non tronquée.
+fn fit_truncated_lognormal(logs: &[f64], log_scale: f64) -> (f64, f64) {
+    let n = logs.len() as f64;
+    let mu0 = logs.iter().sum::<f64>() / n;
+    let sigma0 = (logs.iter().map(|l| (l - mu0).powi(2)).sum::<f64>() / n).sqrt();
+    if sigma0 <= 0.0 {
+        return (mu0, 0.0);
+    }
+
+    let cost = |p: [f64; 2]| -> f64 {
+        let sigma = p[1].exp();
+        let ll: f64 = logs
+            .iter()
+            .map(|&l| truncated_lognormal_ll(l, p[0], sigma, log_scale))
+            .sum();
+        if ll.is_finite() { -ll } else { f64::INFINITY }
+    };
+
+    let start = [mu0, sigma0.ln()];
+    let mut simplex = [
+        start,
+        [start[0] + sigma0, start[1]],
+        [start[0], start[1] + 0.5],
+    ];
+    let mut values = simplex.map(cost);
+
+    for _ in 0..200 {
+        // Tri : meilleur en 0, pire en 2
+        let mut order = [0, 1, 2];
+        order.sort_by(|&i, &j| values[i].total_cmp(&values[j]));
+        simplex = order.map(|i| simplex[i]);
+        values = order.map(|i| values[i]);
+        if (values[2] - values[0]).abs() < 1e-10 {
+            break;
+        }
+
+        let centroid = [
+            (simplex[0][0] + simplex[1][0]) / 2.0,
+            (simplex[0][1] + simplex[1][1]) / 2.0,
+        ];
+        let toward = |k: f64| {
+            [
+                centroid[0] + k * (simplex[2][0] - centroid[0]),
+                centroid[1] + k * (simplex[2][1] - centroid[1]),
+            ]
+        };
+
+        let reflected = toward(-1.0);
+        let reflected_value = cost(reflected);
+        if reflected_value < values[0] {
+            let expanded = toward(-2.0);
+            let expanded_value = cost(expanded);
+            (simplex[2], values[2]) = if expanded_value < reflected_value {
+                (expanded, expanded_value)
+            } else {
+                (reflected, reflected_value)
+            };
+        } else if reflected_value < values[1] {
+            (simplex[2], values[2]) = (reflected, reflected_value);
+        } else {
+            let contracted = toward(0.5);
+            let contracted_value = cost(contracted);
+            if contracted_value < values[2] {
+                (simplex[2], values[2]) = (contracted, contracted_value);
+            } else {
+                // Rétrécissement vers le meilleur sommet
+                for k in 1..3 {
+                    simplex[k] = [
+                        (simplex[0][0] + simplex[k][0]) / 2.0,
+                        (simplex[0][1] + simplex[k][1]) / 2.0,
+                    ];
+                    values[k] = cost(simplex[k]);
+                }
+            }
+        }
+    }
+
+    let best = (0..3)
+        .min_by(|&i, &j| values[i].total_cmp(&values[j]))
+        .unwrap_or(0);
+    (simplex[best][0], simplex[best][1].exp())
+}
+
+/// Ajuste une loi de puissance sur la queue d'un échantillon positif
+///
+/// Pour chaque x_min candidat, α est estimé par maximum de vraisemblance
+/// et x_min retenu minimise la distance KS (CSN §3.3). Les données discrètes
+/// utilisent l'approximation continue décalée de ½ (CSN éq. 3.7).
+/// La log-normale est ajustée sur la même queue puis tronquée à x_min ;
+/// R et la p-value suivent le test de Vuong (CSN §5.1).
+fn fit_power_law(values: &[f64], discrete: bool) -> PowerLawFit {
+    let mut data: Vec<f64> = values.iter().copied().filter(|&x| x > 0.0).collect();
+    data.sort_by(|a, b| a.total_cmp(b));
+    if data.len() < MIN_TAIL {
+        return PowerLawFit::default();
+    }
+    let offset = if discrete { 0.5 } else { 0.0 };
+
+    // (ks, alpha, index de début de queue)
+    let mut best: Option<(f64, f64, usize)> = None;
+    let mut start = 0;
+    while data.len() - start >= MIN_TAIL {
+        let tail = &data[start..];
+        let scale = tail[0] - offset;
+        let log_sum: f64 = tail.iter().map(|&x| (x / scale).ln()).sum();
+        if log_sum > 0.0 {
+            let n = tail.len() as f64;
+            let alpha = 1.0 + n / log_sum;
+
+            // KS entre CDF empirique et CDF du modèle, aux sauts de l'empirique
+            let mut ks: f64 = 0.0;
+            let mut i = 0;
+            while i < tail.len() {
+                let x = tail[i];
+                let below = i as f64 / n;
+                while i < tail.len() && tail[i] == x {
+                    i += 1;
+                }
+                let empirical = i as f64 / n;
+                let model = 1.0 - ((x + offset) / scale).powf(1.0 - alpha);
+                let model_before = if discrete {
+                    1.0 - ((x - offset) / scale).powf(1.0 - alpha)
+                } else {
+                    model
+                };
+                ks = ks
+                    .max((empirical - model).abs())
+                    .max((below - model_before).abs());
+            }
+
+            if best.is_none_or(|(best_ks, _, _)| ks < best_ks) {
+                best = Some((ks, alpha, start));
+            }
+        }
+
+        // Candidat suivant : prochaine valeur distincte
+        let current = data[start];
+        while start < data.len() && data[start] == current {
+            start += 1;
+        }
+    }
+
+    let Some((ks, alpha, start)) = best else {
+        return PowerLawFit::default();
+    };
+    let tail = &data[start..];
+    let n = tail.len() as f64;
+    let scale = tail[0] - offset;
+
+    // Log-normale tronquée à la même borne que la loi de puissance
+    let logs: Vec<f64> = tail.iter().map(|x| x.ln()).collect();
+    let (mu, sigma) = fit_truncated_lognormal(&logs, scale.ln());
+
+    let (likelihood_ratio, p_value) = if sigma > 0.0 {
+        let differences: Vec<f64> = tail
+            .iter()
+            .zip(&logs)
+            .map(|(&x, &l)| {
+                let power_law = (alpha - 1.0).ln() - scale.ln() - alpha * (x / scale).ln();
+                power_law - truncated_lognormal_ll(l, mu, sigma, scale.ln())
+            })
+            .collect();
+        let ratio: f64 = differences.iter().sum();
+        let mean = ratio / n;
+        let spread = (differences.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / n).sqrt();
+        let p_value = if spread > 0.0 {
+            erfc(ratio.abs() / ((2.0 * n).sqrt() * spread))
+        } else {
+            1.0
+        };
+        (ratio, p_value)
+    } else {
+        (0.0, 1.0)
+    };
+
+    PowerLawFit {
+        alpha,
+        x_min: tail[0],
+        ks,
+        tail_size: tail.len(),
+        lognormal_mu: mu,
+        lognormal_sigma: sigma,
+        likelihood_ratio,
+        p_value,
+    }
+}
+
+/// Distributions de degré et de force du graphe de co-occurrence
+pub(crate) fn degree_distribution(
+    graph: &DiGraph<String, f64>,
+    undirected: &Csr,
+) -> DegreeDistribution {
+    let n = graph.node_count();
+    if n == 0 {
+        return DegreeDistribution::default();
+    }
+
+    let degrees: Vec<usize> = (0..n).map(|v| undirected.degree(v)).collect();
+    let max_degree = degrees.iter().copied().max().unwrap_or(0);
+    let mut degree_histogram = vec![0usize; max_degree + 1];
+    for &k in &degrees {
+        degree_histogram[k] += 1;
+    }
+
+    let mut strengths = vec![0.0f64; n];
+    for edge in graph.edge_references() {
+        strengths[edge.source().index()] += *edge.weight();
+        strengths[edge.target().index()] += *edge.weight();
+    }
+
+    let degree_values: Vec<f64> = degrees.iter().map(|&k| k as f64).collect();
+    DegreeDistribution {
+        degree_histogram,
+        max_degree,
+        mean_strength: strengths.iter().sum::<f64>() / n as f64,
+        max_strength: strengths.iter().copied().fold(0.0, f64::max),
+        degree_fit: fit_power_law(&degree_values, true),
+        strength_fit: fit_power_law(&strengths, false),
+    }
+}
+
+/// Noms des 16 types de triades orientées (Holland & Leinhardt, code MAN)
+pub const TRIAD_TYPES: [&str; 16] = [
+    "003", "012", "102", "021D", "021U", "021C", "111D", "111U", "030T", "030C", "201", "120D",
+    "120U", "120C", "210", "300",
+];
+
+/// Motifs non-dirigés induits à 3 et 4 nœuds (connexes)
+pub const MOTIF_TYPES: [&str; 8] = [
+    "wedge",
+    "triangle",
+    "path4",
+    "star4",
+    "cycle4",
+    "tailed_triangle",
+    "diamond",
+    "clique4",
+];
+
+/// Type de triade (1..=16) selon le code à 6 bits des arcs présents
+/// (Batagelj & Mrvar 2001)
+const TRICODES: [usize; 64] = [
+    1, 2, 2, 3, 2, 4, 6, 8, 2, 6, 5, 7, 3, 8, 7, 11, 2, 6, 4, 8, 5, 9, 9, 13, 6, 10, 9, 14, 7, 14,
+    12, 15, 2, 5, 6, 7, 6, 9, 10, 14, 4, 9, 9, 12, 8, 13, 14, 15, 3, 7, 8, 11, 7, 12, 14, 15, 8,
+    14, 13, 15, 11, 15, 15, 16,
+];
+
+/// Effectif d'un motif comparé au modèle nul
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MotifCount {
+    /// Nom du motif (voir `TRIAD_TYPES`, `MOTIF_TYPES`)
+    pub name: String,
+    /// Effectif observé
+    pub count: f64,
+    /// Effectif moyen sur les graphes nuls
+    pub null_mean: f64,
+    /// Écart-type sur les graphes nuls
+    pub null_std: f64,
+    /// z = (observé - moyenne) / écart-type (0 si écart-type nul)
+    pub z_score: f64,
+}
+
+/// Profil de motifs d'un texte : recensement des triades et motifs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MotifProfile {
+    /// Recensement des 16 triades orientées
+    pub triads: Vec<MotifCount>,
+    /// Motifs non-dirigés à 3 et 4 nœuds
+    pub motifs: Vec<MotifCount>,
+    /// Modèle nul utilisé
+    pub model: NullModel,
+    /// Graine utilisée
+    pub seed: u64,
+    /// Nombre de répliques
+    pub replicates: usize,
+}
+
+impl MotifProfile {
+    /// Vecteur de caractéristiques : profil de significativité (Milo et al. 2004)
+    ///
+    /// z-scores des triades puis des motifs, chaque bloc normalisé à la
+    /// norme unité pour rester comparable entre textes de tailles différentes.
+    pub fn feature_vector(&self) -> Vec<f64> {
+        let normalized = |counts: &[MotifCount]| {
+            let norm = counts.iter().map(|c| c.z_score.powi(2)).sum::<f64>().sqrt();
+            counts
+                .iter()
+                .map(|c| if norm > 0.0 { c.z_score / norm } else { 0.0 })
+                .collect::<Vec<f64>>()
+        };
+        let mut features = normalized(&self.triads);
+        features.extend(normalized(&self.motifs));
+        features
+    }
+}
+
+/// Recensement des triades orientées en O(m·Δ) (Batagelj & Mrvar 2001)
+pub(crate) fn triad_census(out: &Csr, undirected: &Csr) -> [u64; 16] {
+    let n = out.len();
+    let mut census = [0u64; 16];
+    let link = |a: usize, b: usize| out.contains(a, b) as usize;
+    let tricode = |v: usize, u: usize, w: usize| {
+        link(v, u)
+            + 2 * link(u, v)
+            + 4 * link(v, w)
+            + 8 * link(w, v)
+            + 16 * link(u, w)
+            + 32 * link(w, u)
+    };
+
+    let mut seen = vec![false; n];
+    for v in 0..n {
+        for &u in undirected.neighbors(v).iter().filter(|&&u| u > v) {
+            // S = N(u) ∪ N(v) \ {u, v}
+            let mut union: Vec<usize> = Vec::new();
+            for &w in undirected
+                .neighbors(u)
+                .iter()
+                .chain(undirected.neighbors(v))
+            {
+                if w != u && w != v && !seen[w] {
+                    seen[w] = true;
+                    union.push(w);
+                }
+            }
+
+            // Triades dont le troisième nœud n'est relié ni à u ni à v
+            let dyad = if link(v, u) == 1 && link(u, v) == 1 {
+                2
+            } else {
+                1
+            };
+            census[dyad] += (n - union.len() - 2) as u64;
+
+            for &w in &union {
+                seen[w] = false;
+                if u < w || (v < w && w < u && !undirected.contains(v, w)) {
+                    census[TRICODES[tricode(v, u, w)] - 1] += 1;
+                }
+            }
+        }
+    }
+
+    let n = n as u64;
+    let total = if n >= 3 { n * (n - 1) * (n - 2) / 6 } else { 0 };
+    census[0] = total - census[1..].iter().sum::<u64>();
+    census
+}
+
+/// Motifs non-dirigés induits à 3 et 4 nœuds, dans l'ordre de `MOTIF_TYPES`
+///
+/// Les sous-graphes non induits se comptent par combinatoire sur les degrés,
+/// les triangles et les voisins communs ; les effectifs induits s'en
+/// déduisent par inversion (chaque motif dense contient un nombre fixe de
+/// copies des motifs plus creux).
+pub(crate) fn motif_census(adj: &Csr) -> [u64; 8] {
+    let n = adj.len();
+    let choose2 = |k: u64| k * k.saturating_sub(1) / 2;
+    let degree = |v: usize| adj.degree(v) as u64;
+
+    let triangles_at = triangles_per_node(adj);
+    let triangles = triangles_at.iter().sum::<usize>() as u64 / 3;
+
+    // K4 : chaque clique est trouvée depuis son triangle de plus bas rang
+    let rank = |v: usize| (adj.degree(v), v);
+    let forward: Vec<Vec<usize>> = (0..n)
+        .map(|v| {
+            adj.neighbors(v)
+                .iter()
+                .copied()
+                .filter(|&w| rank(w) > rank(v))
+                .collect()
+        })
+        .collect();
+    let mut cliques = 0u64;
+    for_each_triangle(adj, |a, b, c| {
+        cliques += forward[c]
+            .iter()
+            .filter(|y| forward[a].binary_search(y).is_ok() && forward[b].binary_search(y).is_ok())
+            .count() as u64;
+    });
+
+    // Voisins communs de toutes les paires à distance ≤ 2 (compteur dense)
+    let (mut cycles_twice, mut diamonds) = (0u64, 0u64);
+    let mut common = vec![0u64; n];
+    let mut touched: Vec<usize> = Vec::new();
+    for u in 0..n {
+        for &v in adj.neighbors(u) {
+            for &w in adj.neighbors(v).iter().filter(|&&w| w > u) {
+                if common[w] == 0 {
+                    touched.push(w);
+                }
+                common[w] += 1;
+            }
+        }
+        for &w in &touched {
+            cycles_twice += choose2(common[w]);
+            if adj.contains(u, w) {
+                diamonds += choose2(common[w]);
+            }
+            common[w] = 0;
+        }
+        touched.clear();
+    }
+
+    let wedges: u64 = (0..n).map(|v| choose2(degree(v))).sum();
+    let stars: u64 = (0..n)
+        .map(|v| {
+            let k = degree(v);
+            k * k.saturating_sub(1) * k.saturating_sub(2) / 6
+        })
+        .sum();
+    let tailed: u64 = (0..n)
+        .map(|v| triangles_at[v] as u64 * degree(v).saturating_sub(2))
+        .sum();
+    let paths: u64 = (0..n)
+        .flat_map(|u| adj.neighbors(u).iter().map(move |&v| (u, v)))
+        .filter(|&(u, v)| u < v)
+        .map(|(u, v)| degree(u).saturating_sub(1) * degree(v).saturating_sub(1))
+        .sum::<u64>()
+        - 3 * triangles;
+
+    // Inversion non-induit → induit, du motif le plus dense au plus creux
+    let clique4 = cliques;
+    let diamond = diamonds - 6 * clique4;
+    let cycle4 = cycles_twice / 2 - diamond - 3 * clique4;
+    let tailed_triangle = tailed - 4 * diamond - 12 * clique4;
+    let star4 = stars - tailed_triangle - 2 * diamond - 4 * clique4;
+    let path4 = paths - 2 * tailed_triangle - 4 * cycle4 - 6 * diamond - 12 * clique4;
+
+    [
+        wedges - 3 * triangles,
+        triangles,
+        path4,
+        star4,
+        cycle4,
+        tailed_triangle,
+        diamond,
+        clique4,
+    ]
+}
+
+/// Recensement des triades et motifs, avec z-scores contre un modèle nul
+///
+/// Chaque réplique est un graphe orienté aléatoire (voir
+/// `null_model::null_arcs`) ; les motifs non-dirigés sont comptés sur sa
+/// projection symétrique. Coût O(m·Δ) par graphe : réservé aux analyses
+/// explicites, pas appelé par `analyze_topology`.
+///
+/// # Arguments
+/// * `text` - Texte à analyser
+/// * `config` - Construction du graphe (squelette compris)
+/// * `null_config` - Modèle nul, graine et nombre de répliques
+pub fn motif_profile(
+    text: &str,
+    config: &TopologyConfig,
+    null_config: &NullModelConfig,
+) -> MotifProfile {
+    let segments = tokenize_segments(text, config.boundary);
+    let graph = extract_backbone(
+        &build_cooccurrence_graph(&segments, config),
+        config.backbone,
+    );
+    let n = graph.node_count();
+    let observed_arcs = arcs(&graph);
+
+    let census = |arcs: &[(usize, usize)]| {
+        let out = Csr::from_pairs(n, arcs);
+        let undirected = Csr::symmetric(n, arcs);
+        (triad_census(&out, &undirected), motif_census(&undirected))
+    };
+    let (triads, motifs) = census(&observed_arcs);
 
-    let k = neighbors.len();
-    if k < 2 {
-        return 0.0;
+    let mut rng = SeededRng::new(null_config.seed);
+    let replicates = null_config.replicates.max(1);
+    let mut null_triads: Vec<[u64; 16]> = Vec::with_capacity(replicates);
+    let mut null_motifs: Vec<[u64; 8]> = Vec::with_capacity(replicates);
+    for _ in 0..replicates {
+        let null = null_model::null_arcs(
+            &observed_arcs,
+            n,
+            &segments,
+            config,
+            null_config.model,
+            &mut rng,
+        );
+        let (t, m) = census(&null);
+        null_triads.push(t);
+        null_motifs.push(m);
     }
 
-    let mut triangles = 0;
-    for &n1 in &neighbors {
-        for &n2 in &neighbors {
-            if n1 != n2 && (graph.contains_edge(n1, n2) || graph.contains_edge(n2, n1)) {
-                triangles += 1;
-            }
-        }
+    fn score<const K: usize>(
+        names: &[&str; K],
+        observed: &[u64; K],
+        null: &[[u64; K]],
+    ) -> Vec<MotifCount> {
+        (0..K)
+            .map(|i| {
+                let r = null.len() as f64;
+                let mean = null.iter().map(|c| c[i] as f64).sum::<f64>() / r;
+                let std = (null
+                    .iter()
+                    .map(|c| (c[i] as f64 - mean).powi(2))
+                    .sum::<f64>()
+                    / r)
+                    .sqrt();
+                let count = observed[i] as f64;
+                MotifCount {
+                    name: names[i].to_string(),
+                    count,
+                    null_mean: mean,
+                    null_std: std,
+                    z_score: if std > 0.0 { (count - mean) / std } else { 0.0 },
+                }
+            })
+            .collect()
     }
 
-    // Chaque triangle est compté 2 fois
-    triangles as f64 / (k * (k - 1)) as f64
+    MotifProfile {
+        triads: score(&TRIAD_TYPES, &triads, &null_triads),
+        motifs: score(&MOTIF_TYPES, &motifs, &null_motifs),
+        model: null_config.model,
+        seed: null_config.seed,
+        replicates,
+    }
 }
 
-/// Calcule le coefficient de clustering moyen
-fn average_clustering(graph: &DiGraph<String, f64>) -> f64 {
-    let nodes: Vec<NodeIndex> = graph.node_indices().collect();
-    if nodes.is_empty() {
-        return 0.0;
+/// Différence symétrique de deux colonnes triées (addition sur Z/2)
+fn symmetric_difference(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let mut result = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => {
+                result.push(a[i]);
+                i += 1;
+            }
+            std::cmp::Ordering::Greater => {
+                result.push(b[j]);
+                j += 1;
+            }
+            std::cmp::Ordering::Equal => {
+                i += 1;
+                j += 1;
+            }
+        }
     }
-
-    let sum: f64 = nodes.iter().map(|&n| local_clustering(graph, n)).sum();
-    sum / nodes.len() as f64
+    result.extend_from_slice(&a[i..]);
+    result.extend_from_slice(&b[j..]);
+    result
 }
 
-/// Calcule la longueur moyenne des plus courts chemins (BFS, échantillonné)
-fn average_path_length(graph: &DiGraph<String, f64>) -> f64 {
-    let nodes: Vec<NodeIndex> = graph.node_indices().collect();
-    if nodes.len() < 2 {
-        return 0.0;
+/// Homologie persistante H0/H1 du graphe pondéré (réduction matricielle sur Z/2)
+///
+/// Seuls les `max_vertices` nœuds de plus forte force sont conservés, pour
+/// borner le nombre de triangles. Les poids des arcs u → v et v → u sont
+/// additionnés. H0 par union-find, H1 par réduction standard des colonnes
+/// de bord des triangles ; les intervalles de durée nulle sont omis.
+pub(crate) fn persistent_homology(
+    graph: &DiGraph<String, f64>,
+    max_vertices: usize,
+) -> PersistenceResult {
+    let n = graph.node_count();
+    if n == 0 || max_vertices == 0 {
+        return PersistenceResult::default();
     }
 
-    // Échantillonnage pour performance (max 50 nœuds sources)
-    let sample_size = nodes.len().min(50);
-    let mut total_length = 0usize;
-    let mut path_count = 0usize;
-
-    for &source in nodes.iter().take(sample_size) {
-        // BFS depuis source
-        let mut visited: HashMap<NodeIndex, usize> = HashMap::new();
-        let mut queue: VecDeque<NodeIndex> = VecDeque::new();
-
-        visited.insert(source, 0);
-        queue.push_back(source);
-
-        while let Some(current) = queue.pop_front() {
-            let current_dist = visited[&current];
+    // Nœuds retenus : les plus forts, indices renumérotés
+    let mut strengths = vec![0.0f64; n];
+    for e in graph.edge_references() {
+        strengths[e.source().index()] += *e.weight();
+        strengths[e.target().index()] += *e.weight();
+    }
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| strengths[b].total_cmp(&strengths[a]).then(a.cmp(&b)));
+    order.truncate(max_vertices);
+    let mut local = vec![usize::MAX; n];
+    for (i, &v) in order.iter().enumerate() {
+        local[v] = i;
+    }
+    let k = order.len();
 
-            for neighbor in graph.neighbors(current) {
-                if let std::collections::hash_map::Entry::Vacant(e) = visited.entry(neighbor) {
-                    let new_dist = current_dist + 1;
-                    e.insert(new_dist);
-                    queue.push_back(neighbor);
-                    total_length += new_dist;
-                    path_count += 1;
-                }
-            }
+    // Arêtes non-dirigées pondérées, triées par filtration croissante
+    let mut weights: HashMap<(usize, usize), f64> = HashMap::new();
+    for e in graph.edge_references() {
+        let (a, b) = (local[e.source().index()], local[e.target().index()]);
+        if a != usize::MAX && b != usize::MAX && a != b {
+            *weights.entry((a.min(b), a.max(b))).or_insert(0.0) += *e.weight();
         }
     }
-
-    if path_count > 0 {
-        total_length as f64 / path_count as f64
-    } else {
-        0.0
+    let max_weight = weights.values().copied().fold(0.0, f64::max);
+    // Poids tous nuls (noyau nul en bord de fenêtre) : filtration indéfinie
+    if max_weight <= 0.0 {
+        return PersistenceResult::default();
     }
-}
+    let mut edges: Vec<((usize, usize), f64)> = weights
+        .into_iter()
+        .map(|(pair, w)| (pair, 1.0 - w.ln_1p() / max_weight.ln_1p()))
+        .collect();
+    edges.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+    let edge_rank: HashMap<(usize, usize), usize> = edges
+        .iter()
+        .enumerate()
+        .map(|(i, (pair, _))| (*pair, i))
+        .collect();
 
-/// Parcourt une composante connexe par BFS et retourne sa taille
-fn bfs_component_size(
-    graph: &DiGraph<String, f64>,
-    start: NodeIndex,
-    visited: &mut HashSet<NodeIndex>,
-) -> usize {
-    let mut component_size = 0;
-    let mut queue: VecDeque<NodeIndex> = VecDeque::new();
-    queue.push_back(start);
-
-    while let Some(current) = queue.pop_front() {
-        if visited.insert(current) {
-            component_size += 1;
-            for neighbor in graph.neighbors_undirected(current) {
-                if !visited.contains(&neighbor) {
-                    queue.push_back(neighbor);
-                }
+    // H0 : union-find, chaque fusion tue une composante née à t = 0
+    fn find(parent: &mut [usize], mut v: usize) -> usize {
+        while parent[v] != v {
+            parent[v] = parent[parent[v]];
+            v = parent[v];
+        }
+        v
+    }
+    let mut parent: Vec<usize> = (0..k).collect();
+    let mut merging = vec![false; edges.len()];
+    let mut h0 = Vec::new();
+    for (i, &((a, b), t)) in edges.iter().enumerate() {
+        let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+        if ra != rb {
+            parent[ra.max(rb)] = ra.min(rb);
+            merging[i] = true;
+            if t > 0.0 {
+                h0.push(PersistenceInterval {
+                    birth: 0.0,
+                    death: Some(t),
+                });
             }
         }
     }
-
-    component_size
-}
-
-/// Trouve la taille de la plus grande composante connexe
-fn largest_connected_component(graph: &DiGraph<String, f64>) -> usize {
-    if graph.node_count() == 0 {
-        return 0;
+    for v in 0..k {
+        if find(&mut parent, v) == v {
+            h0.push(PersistenceInterval {
+                birth: 0.0,
+                death: None,
+            });
+        }
     }
 
-    let mut visited: HashSet<NodeIndex> = HashSet::new();
-    let mut max_size = 0;
+    // Triangles : bord = 3 arêtes, filtration = arête la plus tardive
+    let pairs: Vec<(usize, usize)> = edges.iter().map(|&(pair, _)| pair).collect();
+    let mut columns: Vec<Vec<usize>> = Vec::new();
+    for_each_triangle(&Csr::symmetric(k, &pairs), |a, b, c| {
+        let rank = |x: usize, y: usize| edge_rank[&(x.min(y), x.max(y))];
+        let mut boundary = vec![rank(a, b), rank(b, c), rank(a, c)];
+        boundary.sort_unstable();
+        columns.push(boundary);
+    });
+    columns.sort_by(|a, b| a[2].cmp(&b[2]).then_with(|| a.cmp(b)));
+    let triangle_t: Vec<f64> = columns.iter().map(|c| edges[c[2]].1).collect();
 
-    for node in graph.node_indices() {
-        if !visited.contains(&node) {
-            let size = bfs_component_size(graph, node, &mut visited);
-            max_size = max_size.max(size);
+    // H1 : réduction standard, pivot = arête de rang maximal de la colonne
+    let mut pivot_column: HashMap<usize, usize> = HashMap::new();
+    let mut killed_at: Vec<Option<f64>> = vec![None; edges.len()];
+    for j in 0..columns.len() {
+        let mut column = std::mem::take(&mut columns[j]);
+        while let Some(&other) = column.last().and_then(|low| pivot_column.get(low)) {
+            column = symmetric_difference(&column, &columns[other]);
+        }
+        if let Some(&low) = column.last() {
+            pivot_column.insert(low, j);
+            killed_at[low] = Some(triangle_t[j]);
         }
+        columns[j] = column;
     }
 
-    max_size
-}
-
-/// Calcule le degré moyen des nœuds
-fn average_degree(graph: &DiGraph<String, f64>) -> f64 {
-    let node_count = graph.node_count();
-    if node_count == 0 {
-        return 0.0;
+    // Arêtes positives (hors fusions H0) : naissance d'un cycle
+    let mut h1 = Vec::new();
+    for (i, &(_, birth)) in edges.iter().enumerate() {
+        if merging[i] {
+            continue;
+        }
+        match killed_at[i] {
+            Some(death) if death > birth => h1.push(PersistenceInterval {
+                birth,
+                death: Some(death),
+            }),
+            Some(_) => {}
+            None => h1.push(PersistenceInterval { birth, death: None }),
+        }
     }
 
-    let total_degree: usize = graph.node_indices().map(|n| graph.edges(n).count()).sum();
+    let thresholds: Vec<f64> = (0..BETTI_STEPS)
+        .map(|i| i as f64 / (BETTI_STEPS - 1) as f64)
+        .collect();
+    let alive = |intervals: &[PersistenceInterval], t: f64| {
+        intervals
+            .iter()
+            .filter(|iv| iv.birth <= t && iv.death.is_none_or(|d| d > t))
+            .count()
+    };
 
-    total_degree as f64 / node_count as f64
+    PersistenceResult {
+        betti0: thresholds.iter().map(|&t| alive(&h0, t)).collect(),
+        betti1: thresholds.iter().map(|&t| alive(&h1, t)).collect(),
+        total_persistence_h0: h0
+            .iter()
+            .filter_map(|iv| iv.death.map(|d| d - iv.birth))
+            .fold(0.0, |acc, x| acc + x),
+        total_persistence_h1: h1
+            .iter()
+            .map(|iv| iv.death.unwrap_or(1.0) - iv.birth)
+            .fold(0.0, |acc, x| acc + x),
+        thresholds,
+        h0,
+        h1,
+        vertices: k,
+        truncated: k < n,
+    }
 }
 
 /// Analyse topologique complète d'un texte
@@ -265,10 +1655,30 @@ fn average_degree(graph: &DiGraph<String, f64>) -> f64 {
 /// # Returns
 /// Structure TopologyResult avec toutes les métriques de graphe
 pub fn analyze_topology(text: &str) -> TopologyResult {
-    let tokens = tokenize(text);
+    analyze_topology_with(text, &TopologyConfig::default())
+}
+
+/// Analyse topologique avec une configuration de graphe explicite
+///
+/// # Arguments
+/// * `text` - Texte à analyser
+/// * `config` - Fenêtre, noyau, frontières et seuil d'arête
+pub fn analyze_topology_with(text: &str, config: &TopologyConfig) -> TopologyResult {
+    analyze_text_graph(text, config).0
+}
 
-    if tokens.is_empty() {
-        return TopologyResult {
+/// Analyse topologique qui rend aussi le graphe analysé (squelette)
+///
+/// Permet de comparer les graphes de A et B (`compare_graphs`) sans
+/// les reconstruire.
+pub(crate) fn analyze_text_graph(
+    text: &str,
+    config: &TopologyConfig,
+) -> (TopologyResult, DiGraph<String, f64>) {
+    let segments = tokenize_segments(text, config.boundary);
+
+    if segments.is_empty() {
+        let empty = TopologyResult {
             node_count: 0,
             edge_count: 0,
             density: 0.0,
@@ -279,23 +1689,40 @@ pub fn analyze_topology(text: &str) -> TopologyResult {
             avg_path_length: 0.0,
             small_world_index: 0.0,
             avg_degree: 0.0,
+            directed: DirectedMetrics::default(),
+            degrees: DegreeDistribution::default(),
+            persistence: PersistenceResult::default(),
+            raw: None,
         };
+        return (empty, DiGraph::new());
+    }
+
+    let graph = build_cooccurrence_graph(&segments, config);
+    if config.backbone == Backbone::None {
+        return (graph_metrics(&graph, config), graph);
     }
 
-    let graph = build_cooccurrence_graph(&tokens);
+    let backbone = extract_backbone(&graph, config.backbone);
+    let mut result = graph_metrics(&backbone, config);
+    result.raw = Some(Box::new(graph_metrics(&graph, config)));
+    (result, backbone)
+}
+
+/// Métriques d'un graphe de co-occurrence déjà construit
+fn graph_metrics(graph: &DiGraph<String, f64>, config: &TopologyConfig) -> TopologyResult {
+    let undirected = Csr::undirected(graph);
 
     let node_count = graph.node_count();
     let edge_count = graph.edge_count();
     let density = compute_density(node_count, edge_count);
-    let components = connected_components(&graph);
-    let lcc_size = largest_connected_component(&graph);
+    let (components, lcc_size) = connected_components(&undirected);
     let lcc_ratio = if node_count > 0 {
         lcc_size as f64 / node_count as f64
     } else {
         0.0
     };
-    let clustering_coefficient = average_clustering(&graph);
-    let avg_path_length = average_path_length(&graph);
+    let clustering_coefficient = average_clustering(&undirected);
+    let avg_path_length = average_path_length(&Csr::outgoing(graph), config);
 
     // Small-World Index: C / L (clustering élevé, path court)
     let small_world_index = if avg_path_length > 0.0 {
@@ -304,7 +1731,11 @@ pub fn analyze_topology(text: &str) -> TopologyResult {
         0.0
     };
 
-    let avg_degree = average_degree(&graph);
+    // Degré sortant moyen = m / n
+    let avg_degree = edge_count as f64 / node_count as f64;
+    let directed = directed_metrics(graph);
+    let degrees = degree_distribution(graph, &undirected);
+    let persistence = persistent_homology(graph, config.persistence_max_vertices);
 
     TopologyResult {
         node_count,
@@ -317,18 +1748,19 @@ pub fn analyze_topology(text: &str) -> TopologyResult {
         avg_path_length,
         small_world_index,
         avg_degree,
+        directed,
+        degrees,
+        persistence,
+        raw: None,
     }
 }
 
-/// Calcule le delta topologique entre deux textes
+/// Calcule le delta topologique entre les analyses de deux textes
 ///
 /// Retourne un score de conservation de structure:
 /// - Positif = structure améliorée ou maintenue
 /// - Négatif = structure dégradée (potentiel délire)
-pub fn topology_delta(text_a: &str, text_b: &str) -> f64 {
-    let topo_a = analyze_topology(text_a);
-    let topo_b = analyze_topology(text_b);
-
+pub fn topology_delta(topo_a: &TopologyResult, topo_b: &TopologyResult) -> f64 {
     // Facteurs de qualité structurelle
     let lcc_score = topo_b.lcc_ratio - topo_a.lcc_ratio;
     let clustering_score = topo_b.clustering_coefficient - topo_a.clustering_coefficient;
@@ -344,6 +1776,208 @@ pub fn topology_delta(text_a: &str, text_b: &str) -> f64 {
     (lcc_score * 0.5) + (clustering_score * 0.3) + fragmentation_penalty
 }
 
+/// Nombre d'itérations du raffinement Weisfeiler–Lehman
+const WL_ITERATIONS: usize = 3;
+
+/// Comparaison structurelle directe entre les graphes de A et B
+///
+/// Contrairement à `topology_delta` qui soustrait deux résumés scalaires,
+/// ces mesures vérifient si A et B partagent effectivement de la structure.
+/// Les nœuds sont identifiés par leur mot : deux graphes sont alignés
+/// sur le vocabulaire, sans recherche d'appariement.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphComparison {
+    /// Jaccard des vocabulaires |V_A ∩ V_B| / |V_A ∪ V_B|
+    pub node_jaccard: f64,
+    /// Jaccard des arêtes restreint au vocabulaire commun
+    pub edge_jaccard: f64,
+    /// Cosinus entre les vecteurs de poids d'arêtes (non-dirigés)
+    pub edge_cosine: f64,
+    /// Similarité normalisée du noyau de sous-arbres Weisfeiler–Lehman [0, 1]
+    pub wl_similarity: f64,
+    /// Distance d'édition (insertions/suppressions de nœuds et d'arêtes)
+    pub edit_distance: usize,
+    /// Distance d'édition normalisée par la taille cumulée des graphes [0, 1]
+    pub edit_distance_normalized: f64,
+}
+
+/// Arêtes non-dirigées indexées par paire de mots triée, poids symétrisés
+fn undirected_edges(graph: &DiGraph<String, f64>) -> HashMap<(String, String), f64> {
+    let mut edges: HashMap<(String, String), f64> = HashMap::new();
+    for edge in graph.edge_references() {
+        let a = &graph[edge.source()];
+        let b = &graph[edge.target()];
+        let key = if a <= b {
+            (a.clone(), b.clone())
+        } else {
+            (b.clone(), a.clone())
+        };
+        *edges.entry(key).or_insert(0.0) += *edge.weight();
+    }
+    edges
+}
+
+/// Ratio de Jaccard, 1.0 par convention si les deux ensembles sont vides
+fn jaccard(intersection: usize, union: usize) -> f64 {
+    if union == 0 {
+        1.0
+    } else {
+        intersection as f64 / union as f64
+    }
+}
+
+/// Histogrammes de labels Weisfeiler–Lehman des deux graphes
+///
+/// Label initial = le mot. À chaque itération, le label devient
+/// (label, multiset trié des labels voisins), compressé dans un
+/// dictionnaire partagé pour que les labels soient comparables.
+fn wl_histograms(
+    graph_a: &DiGraph<String, f64>,
+    graph_b: &DiGraph<String, f64>,
+) -> (HashMap<usize, usize>, HashMap<usize, usize>) {
+    let mut dictionary: HashMap<String, usize> = HashMap::new();
+    let mut hist_a: HashMap<usize, usize> = HashMap::new();
+    let mut hist_b: HashMap<usize, usize> = HashMap::new();
+
+    let mut compress = |signature: String| -> usize {
+        let next = dictionary.len();
+        *dictionary.entry(signature).or_insert(next)
+    };
+
+    let mut labels_a: Vec<usize> = graph_a
+        .node_indices()
+        .map(|n| compress(graph_a[n].clone()))
+        .collect();
+    let mut labels_b: Vec<usize> = graph_b
+        .node_indices()
+        .map(|n| compress(graph_b[n].clone()))
+        .collect();
+
+    for iteration in 0..=WL_ITERATIONS {
+        for &l in &labels_a {
+            *hist_a.entry(l).or_insert(0) += 1;
+        }
+        for &l in &labels_b {
+            *hist_b.entry(l).or_insert(0) += 1;
+        }
+        if iteration == WL_ITERATIONS {
+            break;
+        }
+
+        for (graph, labels) in [(graph_a, &mut labels_a), (graph_b, &mut labels_b)] {
+            let relabeled: Vec<usize> = graph
+                .node_indices()
+                .map(|n| {
+                    let neighbors: HashSet<NodeIndex> = graph.neighbors_undirected(n).collect();
+                    let mut neighbor_labels: Vec<usize> =
+                        neighbors.iter().map(|m| labels[m.index()]).collect();
+                    neighbor_labels.sort_unstable();
+                    compress(format!(
+                        "{}|{}:{:?}",
+                        iteration,
+                        labels[n.index()],
+                        neighbor_labels
+                    ))
+                })
+                .collect();
+            *labels = relabeled;
+        }
+    }
+
+    (hist_a, hist_b)
+}
+
+/// Produit scalaire de deux histogrammes creux
+fn histogram_dot(a: &HashMap<usize, usize>, b: &HashMap<usize, usize>) -> f64 {
+    a.iter()
+        .filter_map(|(label, &ca)| b.get(label).map(|&cb| (ca * cb) as f64))
+        .sum()
+}
+
+/// Compare directement les graphes de co-occurrence de deux textes
+///
+/// # Arguments
+/// * `graph_a` - Graphe de la réponse standard (contrôle)
+/// * `graph_b` - Graphe de la réponse fracturée
+///
+/// Les deux graphes sont ceux de `analyze_text_graph` (ou `text_graph`),
+/// construits avec la même configuration.
+///
+/// # Returns
+/// Structure GraphComparison (similarités dans [0, 1], 1.0 = structures identiques)
+pub fn compare_graphs(
+    graph_a: &DiGraph<String, f64>,
+    graph_b: &DiGraph<String, f64>,
+) -> GraphComparison {
+    // Jaccard des nœuds
+    let vocab_a: HashSet<&String> = graph_a.node_weights().collect();
+    let vocab_b: HashSet<&String> = graph_b.node_weights().collect();
+    let shared: HashSet<&String> = vocab_a.intersection(&vocab_b).copied().collect();
+    let node_union = vocab_a.union(&vocab_b).count();
+    let node_jaccard = jaccard(shared.len(), node_union);
+
+    // Jaccard des arêtes sur le vocabulaire commun
+    let edges_a = undirected_edges(graph_a);
+    let edges_b = undirected_edges(graph_b);
+    let in_shared = |key: &&(String, String)| shared.contains(&key.0) && shared.contains(&key.1);
+    let shared_a: HashSet<&(String, String)> = edges_a.keys().filter(in_shared).collect();
+    let shared_b: HashSet<&(String, String)> = edges_b.keys().filter(in_shared).collect();
+    let edge_jaccard = jaccard(
+        shared_a.intersection(&shared_b).count(),
+        shared_a.union(&shared_b).count(),
+    );
+
+    // Cosinus pondéré des arêtes
+    let dot: f64 = edges_a
+        .iter()
+        .filter_map(|(key, wa)| edges_b.get(key).map(|wb| wa * wb))
+        .sum();
+    let norm_a = edges_a.values().map(|w| w * w).sum::<f64>().sqrt();
+    let norm_b = edges_b.values().map(|w| w * w).sum::<f64>().sqrt();
+    let edge_cosine = if norm_a > 0.0 && norm_b > 0.0 {
+        dot / (norm_a * norm_b)
+    } else if norm_a == 0.0 && norm_b == 0.0 {
+        1.0
+    } else {
+        0.0
+    };
+
+    // Noyau Weisfeiler–Lehman normalisé
+    let (hist_a, hist_b) = wl_histograms(graph_a, graph_b);
+    let k_ab = histogram_dot(&hist_a, &hist_b);
+    let k_aa = histogram_dot(&hist_a, &hist_a);
+    let k_bb = histogram_dot(&hist_b, &hist_b);
+    let wl_similarity = if k_aa > 0.0 && k_bb > 0.0 {
+        k_ab / (k_aa * k_bb).sqrt()
+    } else if k_aa == 0.0 && k_bb == 0.0 {
+        1.0
+    } else {
+        0.0
+    };
+
+    // Distance d'édition : avec des nœuds étiquetés par mot, l'appariement
+    // est imposé et le coût se réduit aux différences symétriques.
+    let node_edits = node_union - shared.len();
+    let common_edges = edges_a.keys().filter(|k| edges_b.contains_key(*k)).count();
+    let edge_edits = edges_a.len() + edges_b.len() - 2 * common_edges;
+    let edit_distance = node_edits + edge_edits;
+    let total_size = vocab_a.len() + vocab_b.len() + edges_a.len() + edges_b.len();
+    let edit_distance_normalized = if total_size > 0 {
+        edit_distance as f64 / total_size as f64
+    } else {
+        0.0
+    };
+
+    GraphComparison {
+        node_jaccard,
+        edge_jaccard,
+        edge_cosine,
+        wl_similarity,
+        edit_distance,
+        edit_distance_normalized,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -381,7 +2015,7 @@ mod tests {
     fn test_topology_delta() {
         let standard = "Le chat dort.";
         let enriched = "Le félin somnole paisiblement sur le coussin moelleux du salon.";
-        let delta = topology_delta(standard, enriched);
+        let delta = topology_delta(&analyze_topology(standard), &analyze_topology(enriched));
 
         // Sans baseline, le delta est centré sur 0.
         // Un texte enrichi a un graphe plus large, clustering légèrement différent.
@@ -400,7 +2034,7 @@ mod tests {
             .into_iter()
             .map(String::from)
             .collect();
-        let graph = build_cooccurrence_graph(&tokens);
+        let graph = build_cooccurrence_graph(&[tokens], &TopologyConfig::default());
 
         // Arête alpha→beta (distance 1, weight = 1/(1+1) = 0.5)
         let alpha = graph
@@ -432,4 +2066,516 @@ mod tests {
             w_far
         );
     }
+
+    fn compare_texts(text_a: &str, text_b: &str) -> GraphComparison {
+        let config = TopologyConfig::default();
+        compare_graphs(&text_graph(text_a, &config), &text_graph(text_b, &config))
+    }
+
+    #[test]
+    fn test_compare_identical_graphs() {
+        let text = "Le chat mange la souris. La souris fuit le chat.";
+        let cmp = compare_texts(text, text);
+
+        assert_eq!(cmp.node_jaccard, 1.0);
+        assert_eq!(cmp.edge_jaccard, 1.0);
+        assert!((cmp.edge_cosine - 1.0).abs() < 1e-9);
+        assert!((cmp.wl_similarity - 1.0).abs() < 1e-9);
+        assert_eq!(cmp.edit_distance, 0);
+    }
+
+    #[test]
+    fn test_compare_disjoint_graphs() {
+        let cmp = compare_texts("alpha beta gamma", "delta epsilon zeta");
+
+        assert_eq!(cmp.node_jaccard, 0.0);
+        assert_eq!(cmp.edge_cosine, 0.0);
+        assert_eq!(cmp.wl_similarity, 0.0);
+        assert_eq!(cmp.edit_distance_normalized, 1.0);
+    }
+
+    #[test]
+    fn test_compare_reordered_vocabulary() {
+        // Même vocabulaire, voisinages différents : le WL doit le voir
+        let a =
+            "alpha beta gamma delta epsilon zeta eta theta iota kappa lambda mu nu xi omicron pi";
+        let b =
+            "pi omicron xi nu mu lambda kappa iota theta eta zeta epsilon delta gamma beta alpha";
+        let c =
+            "alpha pi beta omicron gamma xi delta nu epsilon mu zeta lambda eta kappa theta iota";
+        let same_order = compare_texts(a, b);
+        let shuffled = compare_texts(a, c);
+
+        assert_eq!(shuffled.node_jaccard, 1.0);
+        assert!(
+            shuffled.wl_similarity < same_order.wl_similarity,
+            "Voisinages brouillés ({}) < voisinages conservés ({})",
+            shuffled.wl_similarity,
+            same_order.wl_similarity
+        );
+        assert!(shuffled.edit_distance > 0);
+    }
+
+    #[test]
+    fn test_sentence_boundary() {
+        let text = "Le chat dort. La gravité courbe l'espace.";
+        let ignore = analyze_topology(text);
+        let sentence = analyze_topology_with(
+            text,
+            &TopologyConfig {
+                boundary: BoundaryPolicy::Sentence,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(ignore.components, 1);
+        assert_eq!(sentence.components, 2, "Deux phrases = deux composantes");
+        assert_eq!(sentence.node_count, ignore.node_count);
+    }
+
+    #[test]
+    fn test_paragraph_boundary() {
+        let segments = tokenize_segments(
+            "Alpha beta. Gamma delta.\n\n  \nEpsilon zeta.",
+            BoundaryPolicy::Paragraph,
+        );
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0], vec!["alpha", "beta", "gamma", "delta"]);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(TopologyConfig::default().validate().is_ok());
+        let window = TopologyConfig {
+            window: 1,
+            ..TopologyConfig::default()
+        };
+        assert_eq!(window.validate(), Err(TopologyConfigError::Window(1)));
+        for kernel in [
+            DecayKernel::Exponential { scale: 0.0 },
+            DecayKernel::Gaussian { sigma: -1.0 },
+        ] {
+            let config = TopologyConfig {
+                kernel,
+                ..TopologyConfig::default()
+            };
+            assert!(matches!(
+                config.validate(),
+                Err(TopologyConfigError::KernelWidth(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_decay_kernels() {
+        assert_eq!(DecayKernel::Inverse.weight(1), 0.5);
+        assert_eq!(DecayKernel::Flat.weight(10), 1.0);
+        let exp = DecayKernel::Exponential { scale: 2.0 };
+        assert!(exp.weight(1) > exp.weight(4));
+        let gauss = DecayKernel::Gaussian { sigma: 3.0 };
+        assert!((gauss.weight(3) - (-0.5_f64).exp()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_min_edge_weight_and_window() {
+        let text = "alpha beta gamma delta epsilon zeta eta theta";
+        let full = analyze_topology(text);
+        let pruned = analyze_topology_with(
+            text,
+            &TopologyConfig {
+                min_edge_weight: 0.4,
+                ..Default::default()
+            },
+        );
+        let narrow = analyze_topology_with(
+            text,
+            &TopologyConfig {
+                window: 2,
+                ..Default::default()
+            },
+        );
+
+        assert!(pruned.edge_count < full.edge_count);
+        assert_eq!(narrow.edge_count, 7, "Fenêtre 2 = chaîne de bigrammes");
+    }
+
+    #[test]
+    fn test_triangles_csr() {
+        // Deux triangles partageant l'arête 1-2, plus une feuille 4
+        let pairs: Vec<(usize, usize)> = [(0, 1), (1, 2), (2, 0), (1, 3), (3, 2), (3, 4)]
+            .iter()
+            .flat_map(|&(a, b)| [(a, b), (b, a)])
+            .collect();
+        let adj = Csr::from_pairs(5, &pairs);
+
+        assert_eq!(triangles_per_node(&adj), vec![1, 2, 2, 1, 0]);
+        // C = (1 + 2/3 + 2/3 + 1/3 + 0) / 5
+        assert!((average_clustering(&adj) - 8.0 / 15.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_directed_chain_is_acyclic() {
+        let d = analyze_topology("alpha beta gamma").directed;
+
+        assert_eq!(d.reciprocity, 0.0);
+        assert_eq!(d.flow_hierarchy, 1.0, "Ordre strict = DAG");
+        assert_eq!(d.scc_count, 3);
+        assert_eq!(d.largest_scc_size, 1);
+        // Triangle transitif : la moitié des cycles orientés possibles
+        assert!((d.clustering - 0.5).abs() < 1e-12);
+        // Source (out 2, in 0) et puits (out 0, in 2) : anti-corrélation
+        assert!((d.degree_correlation + 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_directed_reciprocal_order() {
+        let d = analyze_topology("alpha beta alpha beta").directed;
+        assert_eq!(d.reciprocity, 1.0);
+        assert_eq!(d.scc_count, 1);
+        assert_eq!(d.largest_scc_ratio, 1.0);
+        assert_eq!(d.flow_hierarchy, 0.0);
+
+        // Un ordre brouillé crée des cycles que l'ordre d'origine n'a pas
+        let ordered = analyze_topology_with(
+            "le chat mange la souris grise",
+            &TopologyConfig {
+                window: 3,
+                ..Default::default()
+            },
+        );
+        let scrambled = analyze_topology_with(
+            "le chat mange la souris grise. souris la mange chat le",
+            &TopologyConfig {
+                window: 3,
+                ..Default::default()
+            },
+        );
+        assert!(scrambled.directed.reciprocity > ordered.directed.reciprocity);
+        assert!(scrambled.directed.flow_hierarchy < ordered.directed.flow_hierarchy);
+    }
+
+    #[test]
+    fn test_erfc() {
+        assert!((erfc(0.0) - 1.0).abs() < 1e-7);
+        assert!((erfc(1.0) - 0.157_299_207).abs() < 1e-7);
+        assert!((erfc(-1.0) - 1.842_700_793).abs() < 1e-7);
+        // Queue profonde : pas de sous-dépassement en échelle log
+        assert!((ln_normal_survival(10.0) - (-53.231_285)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_power_law_fit_recovers_exponent() {
+        // Pareto continue α = 2.5, x_min = 1 (inversion de la CDF)
+        let mut rng = SeededRng::new(7);
+        let sample: Vec<f64> = (0..3000)
+            .map(|_| {
+                let u = (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+                (1.0 - u).powf(-1.0 / 1.5)
+            })
+            .collect();
+        let fit = fit_power_law(&sample, false);
+
+        assert!((fit.alpha - 2.5).abs() < 0.15, "alpha = {}", fit.alpha);
+        assert!(fit.ks < 0.05);
+        assert_ne!(fit.preferred(), TailModel::LogNormal);
+    }
+
+    #[test]
+    fn test_degree_distribution() {
+        let text = "Le chat mange la souris. La souris fuit le chat. Le chien dort.";
+        let result = analyze_topology(text);
+        let degrees = &result.degrees;
+
+        assert_eq!(
+            degrees.degree_histogram.iter().sum::<usize>(),
+            result.node_count
+        );
+        assert_eq!(degrees.degree_histogram.len(), degrees.max_degree + 1);
+        assert!(degrees.max_strength >= degrees.mean_strength);
+        // Trop peu de nœuds pour ajuster une queue
+        assert_eq!(degrees.degree_fit.tail_size, 0);
+        assert_eq!(degrees.degree_fit.preferred(), TailModel::Undecided);
+    }
+
+    #[test]
+    fn test_backbone_disparity() {
+        // "hub" : une arête lourde parmi neuf arêtes légères
+        let mut graph: DiGraph<String, f64> = DiGraph::new();
+        let hub = graph.add_node("hub".into());
+        let heavy = graph.add_node("heavy".into());
+        graph.add_edge(hub, heavy, 20.0);
+        for i in 0..9 {
+            let leaf = graph.add_node(format!("leaf{}", i));
+            graph.add_edge(hub, leaf, 1.0);
+        }
+
+        let backbone = extract_backbone(&graph, Backbone::Disparity { alpha: 0.05 });
+        assert_eq!(backbone.node_count(), graph.node_count(), "Nœuds conservés");
+        assert_eq!(backbone.edge_count(), 1);
+        assert!(backbone.find_edge(hub, heavy).is_some());
+
+        let all = extract_backbone(&graph, Backbone::Disparity { alpha: 1.01 });
+        assert_eq!(all.edge_count(), graph.edge_count());
+    }
+
+    #[test]
+    fn test_backbone_threshold_and_topk() {
+        let text = "le chat mange la souris. la souris fuit le chat qui mange";
+        let threshold = analyze_topology_with(
+            text,
+            &TopologyConfig {
+                backbone: Backbone::Threshold { min_weight: 0.4 },
+                ..Default::default()
+            },
+        );
+        let pruned = analyze_topology_with(
+            text,
+            &TopologyConfig {
+                min_edge_weight: 0.4,
+                ..Default::default()
+            },
+        );
+        assert_eq!(threshold.edge_count, pruned.edge_count);
+        assert!(pruned.raw.is_none());
+
+        let raw = threshold.raw.as_ref().expect("Métriques brutes conservées");
+        assert_eq!(raw.edge_count, analyze_topology(text).edge_count);
+        assert_eq!(raw.node_count, threshold.node_count);
+
+        let top1 = extract_backbone(
+            &build_cooccurrence_graph(&[tokenize("alpha beta gamma")], &TopologyConfig::default()),
+            Backbone::TopK { k: 1 },
+        );
+        // alpha→beta (0.5) domine alpha→gamma (0.33) ; beta→gamma est le seul entrant lourd de gamma
+        assert_eq!(top1.edge_count(), 2);
+    }
+
+    /// Graphe orienté pseudo-aléatoire de test (arcs sans boucle ni doublon)
+    fn random_arcs(n: usize, m: usize, seed: u64) -> Vec<(usize, usize)> {
+        let mut rng = SeededRng::new(seed);
+        let mut present = HashSet::new();
+        while present.len() < m {
+            let (u, v) = (rng.below(n), rng.below(n));
+            if u != v {
+                present.insert((u, v));
+            }
+        }
+        let mut arcs: Vec<_> = present.into_iter().collect();
+        arcs.sort_unstable();
+        arcs
+    }
+
+    #[test]
+    fn test_triad_types() {
+        let triad = |arcs: &[(usize, usize)]| {
+            let census = triad_census(&Csr::from_pairs(3, arcs), &Csr::symmetric(3, arcs));
+            TRIAD_TYPES[census.iter().position(|&c| c == 1).unwrap()]
+        };
+        assert_eq!(triad(&[]), "003");
+        assert_eq!(triad(&[(0, 1)]), "012");
+        assert_eq!(triad(&[(0, 1), (1, 0)]), "102");
+        assert_eq!(triad(&[(0, 1), (0, 2)]), "021D");
+        assert_eq!(triad(&[(1, 0), (2, 0)]), "021U");
+        assert_eq!(triad(&[(0, 1), (1, 2)]), "021C");
+        assert_eq!(triad(&[(0, 1), (1, 2), (0, 2)]), "030T");
+        assert_eq!(triad(&[(0, 1), (1, 2), (2, 0)]), "030C");
+        let all: Vec<_> = (0..3)
+            .flat_map(|a| (0..3).map(move |b| (a, b)))
+            .filter(|(a, b)| a != b)
+            .collect();
+        assert_eq!(triad(&all), "300");
+    }
+
+    #[test]
+    fn test_triad_census_matches_brute_force() {
+        let n = 12;
+        let arcs = random_arcs(n, 40, 3);
+        let out = Csr::from_pairs(n, &arcs);
+        let census = triad_census(&out, &Csr::symmetric(n, &arcs));
+
+        let link = |a, b| out.contains(a, b) as usize;
+        let mut brute = [0u64; 16];
+        for v in 0..n {
+            for u in (v + 1)..n {
+                for w in (u + 1)..n {
+                    let code = link(v, u)
+                        + 2 * link(u, v)
+                        + 4 * link(v, w)
+                        + 8 * link(w, v)
+                        + 16 * link(u, w)
+                        + 32 * link(w, u);
+                    brute[TRICODES[code] - 1] += 1;
+                }
+            }
+        }
+        assert_eq!(census, brute);
+        assert_eq!(census.iter().sum::<u64>(), 220, "C(12, 3) triades");
+    }
+
+    #[test]
+    fn test_motif_census_matches_brute_force() {
+        let n = 10;
+        let adj = Csr::symmetric(n, &random_arcs(n, 24, 11));
+        let census = motif_census(&adj);
+
+        let mut brute = [0u64; 8];
+        for a in 0..n {
+            for b in (a + 1)..n {
+                for c in (b + 1)..n {
+                    let edges = [(a, b), (b, c), (a, c)]
+                        .iter()
+                        .filter(|&&(x, y)| adj.contains(x, y))
+                        .count();
+                    match edges {
+                        2 => brute[0] += 1,
+                        3 => brute[1] += 1,
+                        _ => {}
+                    }
+                    for d in (c + 1)..n {
+                        let nodes = [a, b, c, d];
+                        let mut degrees = [0usize; 4];
+                        let mut edges = 0;
+                        for i in 0..4 {
+                            for j in (i + 1)..4 {
+                                if adj.contains(nodes[i], nodes[j]) {
+                                    degrees[i] += 1;
+                                    degrees[j] += 1;
+                                    edges += 1;
+                                }
+                            }
+                        }
+                        degrees.sort_unstable();
+                        let motif = match (edges, degrees) {
+                            (3, [1, 1, 2, 2]) => Some(2),
+                            (3, [1, 1, 1, 3]) => Some(3),
+                            (4, [2, 2, 2, 2]) => Some(4),
+                            (4, _) => Some(5),
+                            (5, _) => Some(6),
+                            (6, _) => Some(7),
+                            _ => None,
+                        };
+                        if let Some(i) = motif {
+                            brute[i] += 1;
+                        }
+                    }
+                }
+            }
+        }
+        assert_eq!(census, brute);
+    }
+
+    #[test]
+    fn test_motif_profile() {
+        let text = "La gravité courbe l'espace-temps autour des masses. \
+                    Les planètes suivent les géodésiques de l'espace-temps courbé. \
+                    La lumière elle-même suit ces géodésiques près des étoiles massives.";
+        let null_config = NullModelConfig {
+            replicates: 5,
+            ..Default::default()
+        };
+        let profile = motif_profile(text, &TopologyConfig::default(), &null_config);
+        let again = motif_profile(text, &TopologyConfig::default(), &null_config);
+
+        assert_eq!(profile.triads.len(), 16);
+        assert_eq!(profile.motifs.len(), 8);
+        assert_eq!(profile.feature_vector(), again.feature_vector(), "Seedé");
+
+        let features = profile.feature_vector();
+        assert_eq!(features.len(), 24);
+        let norm: f64 = features[..16].iter().map(|z| z * z).sum::<f64>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-9 || norm == 0.0);
+
+        let n = analyze_topology(text).node_count as f64;
+        let triads: f64 = profile.triads.iter().map(|t| t.count).sum();
+        assert_eq!(triads, n * (n - 1.0) * (n - 2.0) / 6.0);
+    }
+
+    /// Graphe pondéré de test à partir d'arêtes (u, v, poids)
+    fn weighted_graph(n: usize, edges: &[(usize, usize, f64)]) -> DiGraph<String, f64> {
+        let mut graph = DiGraph::new();
+        let nodes: Vec<_> = (0..n).map(|i| graph.add_node(format!("n{}", i))).collect();
+        for &(u, v, w) in edges {
+            graph.add_edge(nodes[u], nodes[v], w);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_persistence_square_cycle() {
+        // Carré 0-1-2-3 : un cycle jamais rempli
+        let square = [(0, 1, 8.0), (1, 2, 6.0), (2, 3, 4.0), (3, 0, 2.0)];
+        let result = persistent_homology(&weighted_graph(4, &square), 100);
+
+        assert_eq!(result.h1.len(), 1);
+        assert_eq!(result.h1[0].death, None, "Cycle essentiel");
+        assert_eq!(result.h0.iter().filter(|iv| iv.death.is_none()).count(), 1);
+        // L'arête la plus lourde entre à t = 0
+        assert_eq!(result.betti0.first(), Some(&3));
+        assert_eq!(result.betti0.last(), Some(&1));
+        assert_eq!(result.betti1.last(), Some(&1));
+        assert!(!result.truncated);
+
+        // La diagonale, plus légère, remplit le carré par deux triangles
+        let mut filled = square.to_vec();
+        filled.push((0, 2, 1.0));
+        let result = persistent_homology(&weighted_graph(4, &filled), 100);
+        assert_eq!(result.h1.len(), 1);
+        let cycle = result.h1[0];
+        let death = cycle.death.expect("Cycle tué par la diagonale");
+        assert!(death > cycle.birth);
+        assert!((result.total_persistence_h1 - (death - cycle.birth)).abs() < 1e-12);
+        assert_eq!(result.betti1.last(), Some(&0));
+    }
+
+    #[test]
+    fn test_persistence_zero_weights() {
+        let flat = weighted_graph(3, &[(0, 1, 0.0), (1, 2, 0.0), (2, 0, 0.0)]);
+        let result = persistent_homology(&flat, 100);
+        assert!(result.h0.is_empty() && result.h1.is_empty());
+        assert!(result.betti0.is_empty());
+    }
+
+    #[test]
+    fn test_persistence_triangle_and_cap() {
+        // Un triangle se remplit dès sa dernière arête : aucun cycle
+        let triangle = weighted_graph(3, &[(0, 1, 3.0), (1, 2, 2.0), (2, 0, 1.0)]);
+        let result = persistent_homology(&triangle, 100);
+        assert!(result.h1.is_empty());
+        assert_eq!(result.total_persistence_h1, 0.0);
+
+        let capped = analyze_topology_with(
+            "le chat mange la souris grise dans le jardin fleuri",
+            &TopologyConfig {
+                persistence_max_vertices: 4,
+                ..Default::default()
+            },
+        );
+        assert_eq!(capped.persistence.vertices, 4);
+        assert!(capped.persistence.truncated);
+        assert_eq!(capped.persistence.betti0.len(), BETTI_STEPS);
+
+        let disabled = analyze_topology_with(
+            "le chat mange la souris",
+            &TopologyConfig {
+                persistence_max_vertices: 0,
+                ..Default::default()
+            },
+        );
+        assert!(disabled.persistence.h0.is_empty());
+    }
+
+    #[test]
+    fn test_path_sampling_seeded() {
+        let all = sample_sources(10, 50, 42);
+        assert_eq!(all, (0..10).collect::<Vec<_>>(), "Petit graphe = exact");
+
+        let a = sample_sources(1000, 50, 7);
+        let b = sample_sources(1000, 50, 7);
+        let c = sample_sources(1000, 50, 8);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(a.len(), 50);
+        assert!(a.windows(2).all(|w| w[0] < w[1]), "Sans remise");
+        assert!(*a.last().unwrap() >= 50, "Pas limité aux premiers indices");
+    }
 }
@@ -11,13 +11,37 @@ mod core;
 mod probe;
 mod server;
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use std::fs;
 use std::time::Instant;
 
 use audit::AuditLogger;
-use core::{LdsiCoefficients, LdsiResult, LdsiVerdict, compute_ldsi};
-use probe::{ApiType, Injector, LlmConfig, clean_default};
+use core::centrality::{ConceptCentrality, ConceptShift};
+use core::ncd::compute_ncd;
+use core::null_model::{NullModel, NullModelConfig};
+use core::topology::{
+    Backbone, BoundaryPolicy, DecayKernel, DegreeDistribution, DirectedMetrics, MotifProfile,
+    PersistenceResult, TopologyConfig,
+};
+use core::{LdsiCoefficients, LdsiResult, LdsiVerdict, TopologyTerm, compute_ldsi_with};
+use probe::boilerplate::{
+    BoilerplateKind, BoilerplateReport, apply_refusal, detect_boilerplate,
+    strip_boilerplate as strip_boilerplate_text,
+};
+use probe::cache::{CacheConfig, CacheMode, DEFAULT_CACHE_DIR};
+use probe::cleaner::{CleanerConfig, Language, split_reasoning};
+use probe::conversation::{Conversation, Role};
+use probe::injector::{
+    AuthScheme, Completion, EndpointConfig, InjectorError, RetryStats, StreamDelta, StreamTiming,
+};
+use probe::language::detect_language;
+use probe::markup::{BlockPolicy, MarkupConfig, MarkupReport, parse_markup};
+use probe::numerals::{NumeralConfig, NumeralForm};
+use probe::pipeline::{CleaningPipeline, CleaningTrace};
+use probe::pricing::{CompletionUsage, PriceTable, SessionCost};
+use probe::profile::ProfileSet;
+use probe::sampling::SamplingParams;
+use probe::{ApiType, Injector, LlmConfig, RetryPolicy};
 
 /// LDSI - Lyapunov-Dabert Stability Index
 ///
@@ -45,6 +69,281 @@ struct Cli {
     command: Commands,
 }
 
+/// Options de construction du graphe de co-occurrence
+#[derive(Args)]
+struct GraphArgs {
+    /// Taille de la fenêtre de co-occurrence (tokens)
+    #[arg(long, default_value = "15")]
+    window: usize,
+
+    /// Noyau de décroissance (inverse, exp, gaussian, flat)
+    #[arg(long, default_value = "inverse")]
+    kernel: String,
+
+    /// Largeur des noyaux exp/gaussian (scale ou sigma, en tokens)
+    #[arg(long, default_value = "5.0")]
+    kernel_width: f64,
+
+    /// Frontières respectées par la fenêtre (ignore, sentence, paragraph)
+    #[arg(long, default_value = "ignore")]
+    boundary: String,
+
+    /// Poids cumulé minimal d'une arête conservée
+    #[arg(long, default_value = "0.0")]
+    min_edge_weight: f64,
+
+    /// Nombre maximal de sources BFS pour la longueur des chemins
+    #[arg(long, default_value = "50")]
+    path_samples: usize,
+
+    /// Graine du tirage des sources BFS
+    #[arg(long, default_value = "42")]
+    path_seed: u64,
+
+    /// Squelette extrait avant les métriques (none, disparity, threshold, topk)
+    #[arg(long, default_value = "none")]
+    backbone: String,
+
+    /// Paramètre du squelette (α pour disparity, poids pour threshold, k pour topk)
+    #[arg(long, default_value = "0.05")]
+    backbone_level: f64,
+
+    /// Nœuds retenus pour l'homologie persistante (0 = désactivée)
+    #[arg(long, default_value = "100")]
+    persistence_vertices: usize,
+}
+
+/// Paramètres d'échantillonnage de la génération
+#[derive(Args)]
+struct SamplingArgs {
+    /// Température (0.0 = déterministe)
+    #[arg(long, default_value = "0.7")]
+    temperature: f32,
+
+    /// Nombre max de tokens de réponse
+    #[arg(long, default_value = "2048")]
+    max_tokens: u32,
+
+    /// Graine du tirage (reproductibilité)
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Nucleus sampling, dans ]0, 1]
+    #[arg(long)]
+    top_p: Option<f32>,
+
+    /// Nombre de tokens candidats conservés
+    #[arg(long)]
+    top_k: Option<u32>,
+
+    /// Séquence d'arrêt (répétable)
+    #[arg(long)]
+    stop: Vec<String>,
+
+    /// Pénalité de fréquence, dans [-2, 2]
+    #[arg(long, allow_hyphen_values = true)]
+    frequency_penalty: Option<f32>,
+
+    /// Pénalité de présence, dans [-2, 2]
+    #[arg(long, allow_hyphen_values = true)]
+    presence_penalty: Option<f32>,
+
+    /// Pénalité de répétition (1.0 = neutre)
+    #[arg(long)]
+    repeat_penalty: Option<f32>,
+}
+
+/// Cache disque des réponses (rejeu sans coût ni réseau)
+#[derive(Args)]
+struct CacheArgs {
+    /// Mode du cache: off, read-write, read-only, replay-only
+    #[arg(long = "cache", default_value = "off")]
+    mode: String,
+
+    /// Répertoire du cache
+    #[arg(long, default_value = DEFAULT_CACHE_DIR)]
+    cache_dir: String,
+}
+
+impl CacheArgs {
+    fn to_config(&self) -> CacheConfig {
+        let mode = match self.mode.to_lowercase().as_str() {
+            "off" => CacheMode::Off,
+            "read-write" => CacheMode::ReadWrite,
+            "read-only" => CacheMode::ReadOnly,
+            "replay-only" => CacheMode::ReplayOnly,
+            _ => {
+                eprintln!(
+                    "Mode de cache inconnu: {}. Utiliser: off, read-write, read-only, replay-only",
+                    self.mode
+                );
+                std::process::exit(1);
+            }
+        };
+        CacheConfig {
+            mode,
+            dir: self.cache_dir.clone().into(),
+        }
+    }
+}
+
+/// Endpoint au format OpenAI (passerelles) et profils nommés
+#[derive(Args)]
+struct EndpointArgs {
+    /// Profil d'endpoint nommé (remplace --url, --model et --api-type)
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Fichier de profils TOML ([profiles.<nom>])
+    #[arg(long, default_value = "profiles.toml")]
+    profiles: String,
+
+    /// Chemin de l'endpoint (défaut: /v1/chat/completions)
+    #[arg(long)]
+    path: Option<String>,
+
+    /// En-tête supplémentaire "Nom: valeur" (répétable)
+    #[arg(long = "header")]
+    headers: Vec<String>,
+
+    /// Paramètre de requête "clé=valeur" (répétable, ex: api-version=2024-06-01)
+    #[arg(long = "query")]
+    query: Vec<String>,
+
+    /// Authentification: bearer, none, header:<nom>
+    #[arg(long)]
+    auth: Option<String>,
+}
+
+impl EndpointArgs {
+    /// Applique chemin, en-têtes, paramètres et authentification à `endpoint`
+    fn apply(&self, mut endpoint: EndpointConfig) -> EndpointConfig {
+        if let Some(path) = &self.path {
+            endpoint.path = Some(path.clone());
+        }
+        for header in &self.headers {
+            let Some((name, value)) = header.split_once(':') else {
+                eprintln!(
+                    "En-tête invalide: {}. Format attendu: \"Nom: valeur\"",
+                    header
+                );
+                std::process::exit(1);
+            };
+            endpoint
+                .headers
+                .insert(name.trim().to_string(), value.trim().to_string());
+        }
+        for param in &self.query {
+            let Some((key, value)) = param.split_once('=') else {
+                eprintln!("Paramètre invalide: {}. Format attendu: clé=valeur", param);
+                std::process::exit(1);
+            };
+            endpoint.query.insert(key.to_string(), value.to_string());
+        }
+        if let Some(auth) = &self.auth {
+            endpoint.auth = match auth.to_lowercase().as_str() {
+                "bearer" => AuthScheme::Bearer,
+                "none" => AuthScheme::None,
+                other => match other.strip_prefix("header:") {
+                    Some(name) if !name.is_empty() => AuthScheme::Header(name.to_string()),
+                    _ => {
+                        eprintln!(
+                            "Authentification inconnue: {}. Utiliser: bearer, none, header:<nom>",
+                            auth
+                        );
+                        std::process::exit(1);
+                    }
+                },
+            };
+        }
+        endpoint
+    }
+}
+
+impl SamplingArgs {
+    fn to_params(&self) -> SamplingParams {
+        SamplingParams {
+            seed: self.seed,
+            top_p: self.top_p,
+            top_k: self.top_k,
+            stop: self.stop.clone(),
+            frequency_penalty: self.frequency_penalty,
+            presence_penalty: self.presence_penalty,
+            repeat_penalty: self.repeat_penalty,
+        }
+    }
+}
+
+impl GraphArgs {
+    fn to_config(&self) -> TopologyConfig {
+        let kernel = match self.kernel.to_lowercase().as_str() {
+            "inverse" => DecayKernel::Inverse,
+            "exp" => DecayKernel::Exponential {
+                scale: self.kernel_width,
+            },
+            "gaussian" => DecayKernel::Gaussian {
+                sigma: self.kernel_width,
+            },
+            "flat" => DecayKernel::Flat,
+            _ => {
+                eprintln!(
+                    "Noyau inconnu: {}. Utiliser: inverse, exp, gaussian, flat",
+                    self.kernel
+                );
+                std::process::exit(1);
+            }
+        };
+        let boundary = match self.boundary.to_lowercase().as_str() {
+            "ignore" => BoundaryPolicy::Ignore,
+            "sentence" => BoundaryPolicy::Sentence,
+            "paragraph" => BoundaryPolicy::Paragraph,
+            _ => {
+                eprintln!(
+                    "Frontière inconnue: {}. Utiliser: ignore, sentence, paragraph",
+                    self.boundary
+                );
+                std::process::exit(1);
+            }
+        };
+
+        let backbone = match self.backbone.to_lowercase().as_str() {
+            "none" => Backbone::None,
+            "disparity" => Backbone::Disparity {
+                alpha: self.backbone_level,
+            },
+            "threshold" => Backbone::Threshold {
+                min_weight: self.backbone_level,
+            },
+            "topk" => Backbone::TopK {
+                k: self.backbone_level.round().max(1.0) as usize,
+            },
+            _ => {
+                eprintln!(
+                    "Squelette inconnu: {}. Utiliser: none, disparity, threshold, topk",
+                    self.backbone
+                );
+                std::process::exit(1);
+            }
+        };
+
+        let config = TopologyConfig {
+            window: self.window,
+            kernel,
+            boundary,
+            min_edge_weight: self.min_edge_weight,
+            path_sample_size: self.path_samples,
+            path_sample_seed: self.path_seed,
+            backbone,
+            persistence_max_vertices: self.persistence_vertices,
+        };
+        if let Err(e) = config.validate() {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        config
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Lance le Control Center (interface web locale)
@@ -56,6 +355,17 @@ enum Commands {
         /// Clé API OpenRouter (ou variable env OPENROUTER_API_KEY)
         #[arg(short = 'k', long)]
         openrouter_key: Option<String>,
+
+        /// Table de prix TOML pour le coût des sessions
+        #[arg(long)]
+        prices: Option<String>,
+
+        /// Fichier de profils TOML (passerelles OpenAI-compatibles)
+        #[arg(long)]
+        profiles: Option<String>,
+
+        #[command(flatten)]
+        cache: CacheArgs,
     },
 
     /// Analyse deux textes locaux (fichiers ou stdin)
@@ -72,6 +382,46 @@ enum Commands {
         #[arg(short, long, default_value = "false")]
         clean: bool,
 
+        /// Langue des stop-words: auto, fr, en, both, es, de, it, pt, nl
+        #[arg(long, default_value = "both")]
+        language: String,
+
+        /// Raciniser les mots (Snowball FR/EN) avant entropie et topologie
+        #[arg(long, default_value = "false")]
+        stem: bool,
+
+        /// Normaliser nombres, unités et dates: digits, words
+        #[arg(long)]
+        numerals: Option<String>,
+
+        /// Prétraitement structurel (markdown, code, LaTeX, HTML)
+        #[arg(long, default_value = "false")]
+        markup: bool,
+
+        /// Blocs de code: keep, strip, extract (avec --markup)
+        #[arg(long, default_value = "strip")]
+        code: String,
+
+        /// Formules LaTeX: keep, strip, extract (avec --markup)
+        #[arg(long, default_value = "strip")]
+        math: String,
+
+        /// Tableaux: keep, strip, extract (avec --markup)
+        #[arg(long, default_value = "keep")]
+        tables: String,
+
+        /// Liens: keep, strip, extract (avec --markup)
+        #[arg(long, default_value = "keep")]
+        links: String,
+
+        /// Pipeline de nettoyage TOML ([[stage]]), remplace --clean/--stem
+        #[arg(long)]
+        pipeline: Option<String>,
+
+        /// Retirer refus, avertissements et formules toutes faites avant calcul
+        #[arg(long, default_value = "false")]
+        strip_boilerplate: bool,
+
         /// Fichier de sortie JSON pour l'audit
         #[arg(short, long)]
         output: Option<String>,
@@ -87,11 +437,18 @@ enum Commands {
         /// Coefficient gamma (Topologie)
         #[arg(long, default_value = "0.20")]
         gamma: f64,
+
+        /// Terme topologique (delta, wl, ged)
+        #[arg(long, default_value = "delta")]
+        topology_term: String,
+
+        #[command(flatten)]
+        graph: GraphArgs,
     },
 
     /// Injection live sur un LLM via API
     Inject {
-        /// URL de l'API (ex: http://localhost:11434)
+        /// URL de l'API (ex: http://localhost:11434 ; Gemini et Mistral : URL officielle par défaut)
         #[arg(short, long, default_value = "http://localhost:11434")]
         url: String,
 
@@ -99,25 +456,65 @@ enum Commands {
         #[arg(short, long, default_value = "llama3")]
         model: String,
 
-        /// Type d'API (ollama, openai, anthropic, openrouter)
+        /// Type d'API (ollama, openai, anthropic, openrouter, openai-compatible, gemini, mistral)
         #[arg(short = 't', long, default_value = "ollama")]
         api_type: String,
 
-        /// Clé API (pour OpenAI/Anthropic/OpenRouter)
+        /// Clé API (pour OpenAI/Anthropic/OpenRouter/Gemini/Mistral)
         #[arg(short, long)]
         api_key: Option<String>,
 
-        /// Prompt standard (A)
+        /// Prompt standard (A), ajouté en dernier message utilisateur
         #[arg(long)]
-        prompt_a: String,
+        prompt_a: Option<String>,
 
-        /// Prompt fracturé (B)
+        /// Prompt fracturé (B), ajouté en dernier message utilisateur
         #[arg(long)]
-        prompt_b: String,
+        prompt_b: Option<String>,
+
+        /// Prompt système de A (texte ou fichier)
+        #[arg(long)]
+        system_a: Option<String>,
+
+        /// Prompt système de B (texte ou fichier)
+        #[arg(long)]
+        system_b: Option<String>,
+
+        /// Conversation de A (fichier JSON ou TOML : system, messages)
+        #[arg(long)]
+        conversation_a: Option<String>,
+
+        /// Conversation de B (fichier JSON ou TOML : system, messages)
+        #[arg(long)]
+        conversation_b: Option<String>,
 
         /// Fichier de sortie JSON
         #[arg(short, long, default_value = "ldsi_audit.json")]
         output: String,
+
+        /// Nombre max de tentatives par requête (429, 5xx, timeouts)
+        #[arg(long, default_value = "3")]
+        max_attempts: u32,
+
+        /// Réception en streaming : affichage en direct et profil de latence
+        #[arg(long)]
+        stream: bool,
+
+        /// Table de prix TOML ([models."<id>"] input/output en USD par million de tokens)
+        #[arg(long)]
+        prices: Option<String>,
+
+        #[command(flatten)]
+        sampling: SamplingArgs,
+
+        #[command(flatten)]
+        endpoint: Box<EndpointArgs>,
+
+        #[command(flatten)]
+        cache: CacheArgs,
+
+        #[command(flatten)]
+        graph: GraphArgs,
     },
 
     /// Calcule uniquement le NCD entre deux textes
@@ -139,6 +536,40 @@ enum Commands {
     Topology {
         /// Texte ou fichier à analyser
         text: String,
+
+        /// Second texte ou fichier (mesures orientées comparées, glissement conceptuel avec --central)
+        text_b: Option<String>,
+
+        /// Affiche les concepts centraux (PageRank, betweenness, vecteur propre)
+        #[arg(long, default_value = "false")]
+        central: bool,
+
+        /// Nombre de concepts centraux affichés
+        #[arg(long, default_value = "10")]
+        top_k: usize,
+
+        /// Calcule σ et ω contre un modèle nul
+        #[arg(long, default_value = "false")]
+        small_world: bool,
+
+        /// Recensement des triades et motifs avec z-scores contre le modèle nul
+        #[arg(long, default_value = "false")]
+        motifs: bool,
+
+        /// Modèle nul (degree, er, shuffle)
+        #[arg(long, default_value = "degree")]
+        null_model: String,
+
+        /// Graine du modèle nul
+        #[arg(long, default_value = "42")]
+        seed: u64,
+
+        /// Nombre de graphes nuls moyennés
+        #[arg(long, default_value = "20")]
+        replicates: usize,
+
+        #[command(flatten)]
+        graph: GraphArgs,
     },
 
     /// Affiche les informations de version et crédits
@@ -156,6 +587,290 @@ fn load_text(path_or_text: &str) -> String {
     }
 }
 
+fn parse_language(language: &str) -> Language {
+    match language.to_lowercase().as_str() {
+        "auto" => Language::Auto,
+        "fr" | "french" => Language::French,
+        "en" | "english" => Language::English,
+        "both" => Language::Both,
+        "es" | "spanish" => Language::Spanish,
+        "de" | "german" => Language::German,
+        "it" | "italian" => Language::Italian,
+        "pt" | "portuguese" => Language::Portuguese,
+        "nl" | "dutch" => Language::Dutch,
+        _ => {
+            eprintln!(
+                "Langue inconnue: {}. Utiliser: auto, fr, en, both, es, de, it, pt, nl",
+                language
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+fn parse_numeral_form(form: &str) -> NumeralForm {
+    match form.to_lowercase().as_str() {
+        "digits" => NumeralForm::Digits,
+        "words" => NumeralForm::Words,
+        _ => {
+            eprintln!(
+                "Forme de nombres inconnue: {}. Utiliser: digits, words",
+                form
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+fn parse_block_policy(policy: &str) -> BlockPolicy {
+    match policy.to_lowercase().as_str() {
+        "keep" => BlockPolicy::Keep,
+        "strip" => BlockPolicy::Strip,
+        "extract" => BlockPolicy::Extract,
+        _ => {
+            eprintln!(
+                "Politique de bloc inconnue: {}. Utiliser: keep, strip, extract",
+                policy
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+fn print_markup(label: &str, report: &MarkupReport) {
+    println!(
+        "[MARKUP] {}: code={}+{} maths={}+{} tableaux={} liens={} images={} titres={} listes={} html={}/{} | {} caractères retirés",
+        label,
+        report.code_blocks,
+        report.inline_code,
+        report.math_blocks,
+        report.inline_math,
+        report.tables,
+        report.links,
+        report.images,
+        report.headers,
+        report.list_items,
+        report.html_tags,
+        report.html_entities,
+        report.removed_chars
+    );
+}
+
+/// Refus et boilerplate détectés dans une réponse
+fn print_boilerplate(label: &str, report: &BoilerplateReport) {
+    println!(
+        "[BOILERPLATE] {}: refus={} ratio={:.1}% (refus={} IA={} mises en garde={} clôtures={})",
+        label,
+        if report.refusal { "oui" } else { "non" },
+        report.ratio * 100.0,
+        report.count(BoilerplateKind::Refusal),
+        report.count(BoilerplateKind::Disclaimer),
+        report.count(BoilerplateKind::Caveat),
+        report.count(BoilerplateKind::SignOff)
+    );
+}
+
+/// Reprises d'une requête, affichées seulement s'il y en a eu
+fn print_retry(label: &str, stats: &RetryStats) {
+    if stats.attempts > 1 {
+        println!(
+            "[RETRY] {}: {} tentatives, {} ms d'attente ({:?})",
+            label,
+            stats.attempts,
+            stats.total_backoff_ms(),
+            stats.backoff_ms
+        );
+    }
+}
+
+/// Profil de latence d'une réponse streamée
+fn print_timing(label: &str, timing: Option<&StreamTiming>) {
+    let Some(timing) = timing else {
+        return;
+    };
+    let ms = |v: Option<f64>| v.map_or("N/A".to_string(), |v| format!("{:.1} ms", v));
+    println!(
+        "[LATENCE] {}: TTFT {}, total {:.1} ms, {} fragments, inter-token moyen {} (max {})",
+        label,
+        ms(timing.ttft_ms),
+        timing.total_ms,
+        timing.deltas(),
+        ms(timing.mean_inter_token_ms()),
+        ms(timing.max_inter_token_ms())
+    );
+}
+
+/// Table de prix du fichier donné (vide sans fichier : coût non calculé)
+fn load_prices(path: Option<&str>) -> PriceTable {
+    let Some(path) = path else {
+        return PriceTable::default();
+    };
+    PriceTable::load(path).unwrap_or_else(|e| {
+        eprintln!("Table de prix invalide ({}): {}", path, e);
+        std::process::exit(1);
+    })
+}
+
+/// Tokens, raison d'arrêt et coût d'une réponse ; signale les troncatures
+fn print_usage(label: &str, usage: &CompletionUsage) {
+    let tokens = |v: Option<u32>| v.map_or("?".to_string(), |v| v.to_string());
+    println!(
+        "[USAGE] {}: {} tokens prompt, {} générés, fin={} modèle={} latence {:.0} ms, coût {}{}",
+        label,
+        tokens(usage.meta.prompt_tokens),
+        tokens(usage.meta.completion_tokens),
+        usage
+            .meta
+            .finish_reason
+            .as_ref()
+            .map_or("?".to_string(), |r| format!("{:?}", r)),
+        usage.meta.model_id.as_deref().unwrap_or("?"),
+        usage.latency_ms,
+        usage
+            .cost_usd
+            .map_or("N/A".to_string(), |c| format!("${:.6}", c)),
+        if usage.cached { " (cache)" } else { "" }
+    );
+    if usage.truncated {
+        println!(
+            "[TRONQUÉ] {}: réponse coupée à max_tokens, NCD biaisé (augmenter --max-tokens)",
+            label
+        );
+    }
+}
+
+/// Conversation d'un côté du test A/B : fichier, puis prompt système, puis
+/// prompt ajouté en dernier message utilisateur
+fn build_conversation(
+    label: &str,
+    file: Option<&str>,
+    system: Option<&str>,
+    prompt: Option<&str>,
+) -> Conversation {
+    let mut conversation = match file {
+        Some(path) => {
+            let source = fs::read_to_string(path).unwrap_or_else(|e| {
+                eprintln!("Erreur lecture fichier: {}", e);
+                std::process::exit(1);
+            });
+            let parsed = if path.ends_with(".toml") {
+                Conversation::from_toml(&source)
+            } else {
+                Conversation::from_json(&source)
+            };
+            parsed.unwrap_or_else(|e| {
+                eprintln!("Conversation {} invalide ({}): {}", label, path, e);
+                std::process::exit(1);
+            })
+        }
+        None => Conversation::default(),
+    };
+    if let Some(system) = system {
+        conversation = conversation.with_system(&load_text(system));
+    }
+    if let Some(prompt) = prompt {
+        conversation = conversation.with_message(Role::User, prompt);
+    }
+    if conversation.turns().next().is_none() {
+        eprintln!(
+            "Aucun message pour {}: utiliser --prompt-{} ou --conversation-{}",
+            label,
+            label.to_lowercase(),
+            label.to_lowercase()
+        );
+        std::process::exit(1);
+    }
+    conversation
+}
+
+/// Injection streamée : affiche la réponse finale au fil des fragments
+async fn inject_live(
+    injector: &Injector,
+    conversation: &Conversation,
+) -> Result<Completion, InjectorError> {
+    use std::io::Write;
+    let response = injector
+        .inject_streaming(conversation, |delta| {
+            if let StreamDelta::Content(text) = delta {
+                print!("{}", text);
+                let _ = std::io::stdout().flush();
+            }
+        })
+        .await;
+    println!();
+    response
+}
+
+/// Trace du pipeline : caractères et tokens par étape, tokens les plus supprimés
+fn print_trace(label: &str, trace: &CleaningTrace) {
+    println!(
+        "[PIPELINE] {}: {} -> {} caractères, {} tokens supprimés",
+        label,
+        trace.chars_before(),
+        trace.chars_after(),
+        trace.removed_tokens()
+    );
+    for stage in &trace.stages {
+        let top: Vec<String> = stage
+            .removed
+            .iter()
+            .take(5)
+            .map(|t| format!("{}×{}", t.token, t.count))
+            .collect();
+        println!(
+            "           {:<24} {:>6} -> {:<6} car. {:>5} -> {:<5} tokens {}",
+            stage.stage,
+            stage.chars_before,
+            stage.chars_after,
+            stage.tokens_before,
+            stage.tokens_after,
+            top.join(" ")
+        );
+    }
+}
+
+/// NCD des blocs extraits, analysés séparément de la prose
+fn print_extracted(kind: &str, a: &str, b: &str) {
+    if a.is_empty() && b.is_empty() {
+        return;
+    }
+    println!(
+        "[MARKUP] {} extrait: A={} B={} caractères, NCD={:.4}",
+        kind,
+        a.chars().count(),
+        b.chars().count(),
+        compute_ncd(a, b).score
+    );
+}
+
+/// λLD des traces de raisonnement, calculé à part des réponses finales
+fn reasoning_ldsi(
+    reasoning_a: Option<&str>,
+    reasoning_b: Option<&str>,
+    config: &TopologyConfig,
+) -> Option<LdsiResult> {
+    if reasoning_a.is_none() && reasoning_b.is_none() {
+        return None;
+    }
+    let length = |r: Option<&str>| r.map_or(0, |r| r.chars().count());
+    println!(
+        "\n[REASONING] Traces séparées: A={} B={} caractères",
+        length(reasoning_a),
+        length(reasoning_b)
+    );
+    let (Some(a), Some(b)) = (reasoning_a, reasoning_b) else {
+        println!("  λLD raisonnement: N/A (trace absente d'un côté)");
+        return None;
+    };
+    let result = compute_ldsi_with(a, b, None, config);
+    println!(
+        "  λLD raisonnement: {:.4} ({})",
+        result.lambda,
+        result.verdict.description()
+    );
+    Some(result)
+}
+
 fn print_result(result: &LdsiResult) {
     println!("\n{}", "=".repeat(60));
     println!("           LDSI - Lyapunov-Dabert Stability Index");
@@ -190,14 +905,165 @@ fn print_result(result: &LdsiResult) {
     println!("    Clustering A:     {:.4}", result.topology.clustering_a);
     println!("    Clustering B:     {:.4}", result.topology.clustering_b);
 
+    let config = &result.topology.config;
+    println!(
+        "    Graphe:           fenetre={} noyau={:?} frontieres={:?} poids_min={} squelette={:?}",
+        config.window, config.kernel, config.boundary, config.min_edge_weight, config.backbone
+    );
+
+    let cmp = &result.topology.comparison;
+    println!("\n  [COMPARAISON - Graphes A/B]");
+    println!("    Jaccard noeuds:   {:.4}", cmp.node_jaccard);
+    println!("    Jaccard aretes:   {:.4}", cmp.edge_jaccard);
+    println!("    Cosinus aretes:   {:.4}", cmp.edge_cosine);
+    println!("    Similarite WL:    {:.4}", cmp.wl_similarity);
+    println!(
+        "    Distance edition: {} ({:.4})",
+        cmp.edit_distance, cmp.edit_distance_normalized
+    );
+
     println!("\n{}", "-".repeat(60));
     println!(
-        "  COEFFICIENTS: alpha={:.2} beta={:.2} gamma={:.2}",
-        result.coefficients.alpha, result.coefficients.beta, result.coefficients.gamma
+        "  COEFFICIENTS: alpha={:.2} beta={:.2} gamma={:.2} ({:?})",
+        result.coefficients.alpha,
+        result.coefficients.beta,
+        result.coefficients.gamma,
+        result.coefficients.topology_term
     );
     println!("{}", "=".repeat(60));
 }
 
+fn print_concepts(label: &str, concepts: &[ConceptCentrality]) {
+    println!("\n[CENTRALITY] Concepts centraux {}", label);
+    println!(
+        "  {:<4} {:<20} {:>10} {:>12} {:>12}",
+        "#", "Concept", "PageRank", "Betweenness", "Eigenvector"
+    );
+    for (i, c) in concepts.iter().enumerate() {
+        println!(
+            "  {:<4} {:<20} {:>10.6} {:>12.6} {:>12.6}",
+            i + 1,
+            c.concept,
+            c.pagerank,
+            c.betweenness,
+            c.eigenvector
+        );
+    }
+}
+
+fn print_directed(label: &str, directed: &DirectedMetrics) {
+    println!("\n[ORIENTE] Ordre des mots {}", label);
+    println!("  Reciprocite:   {:.6}", directed.reciprocity);
+    println!("  Corr. in/out:  {:.6}", directed.degree_correlation);
+    println!("  Clustering D:  {:.6}", directed.clustering);
+    println!("  CFC:           {}", directed.scc_count);
+    println!(
+        "  Plus grande:   {} ({:.6})",
+        directed.largest_scc_size, directed.largest_scc_ratio
+    );
+    println!("  Hierarchie:    {:.6}", directed.flow_hierarchy);
+}
+
+fn print_degrees(degrees: &DegreeDistribution) {
+    println!("\n[DEGRES] Distribution et ajustement de queue");
+    println!("  Degre max:     {}", degrees.max_degree);
+    println!(
+        "  Force moy/max: {:.6} / {:.6}",
+        degrees.mean_strength, degrees.max_strength
+    );
+    for (label, fit) in [
+        ("Degre", &degrees.degree_fit),
+        ("Force", &degrees.strength_fit),
+    ] {
+        if fit.tail_size == 0 {
+            println!(
+                "  {:<6}         queue trop courte pour un ajustement",
+                label
+            );
+            continue;
+        }
+        println!(
+            "  {:<6} alpha:  {:.4} (x_min {:.4}, n {}, KS {:.4})",
+            label, fit.alpha, fit.x_min, fit.tail_size, fit.ks
+        );
+        println!(
+            "  {:<6} lognorm: mu {:.4}, sigma {:.4} | R {:.4}, p {:.4} -> {:?}",
+            label,
+            fit.lognormal_mu,
+            fit.lognormal_sigma,
+            fit.likelihood_ratio,
+            fit.p_value,
+            fit.preferred()
+        );
+    }
+}
+
+fn print_persistence(persistence: &PersistenceResult) {
+    let curve = |betti: &[usize]| {
+        betti
+            .iter()
+            .map(|b| b.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    println!("\n[PERSISTANCE] Homologie H0/H1 (filtration t = 1 - ln(1+w)/ln(1+w_max))");
+    println!(
+        "  Noeuds:        {}{}",
+        persistence.vertices,
+        if persistence.truncated {
+            " (tronqué)"
+        } else {
+            ""
+        }
+    );
+    println!(
+        "  H0 / H1:       {} / {} intervalles",
+        persistence.h0.len(),
+        persistence.h1.len()
+    );
+    println!(
+        "  Persistance:   H0 {:.6} | H1 {:.6}",
+        persistence.total_persistence_h0, persistence.total_persistence_h1
+    );
+    println!("  Betti0(t):     {}", curve(&persistence.betti0));
+    println!("  Betti1(t):     {}", curve(&persistence.betti1));
+}
+
+fn print_motifs(profile: &MotifProfile) {
+    println!(
+        "\n[MOTIFS] Triades et motifs ({:?}, graine {}, {} répliques)",
+        profile.model, profile.seed, profile.replicates
+    );
+    println!(
+        "  {:<16} {:>12} {:>14} {:>10}",
+        "Motif", "Observé", "Nul (moy)", "z"
+    );
+    for count in profile.triads.iter().chain(&profile.motifs) {
+        println!(
+            "  {:<16} {:>12} {:>14.2} {:>10.2}",
+            count.name, count.count, count.null_mean, count.z_score
+        );
+    }
+    let features: Vec<String> = profile
+        .feature_vector()
+        .iter()
+        .map(|z| format!("{:.2}", z))
+        .collect();
+    println!("  Profil SP:     [{}]", features.join(", "));
+}
+
+fn print_concept_shift(shift: &ConceptShift) {
+    println!("\n[CENTRALITY] Glissement conceptuel A -> B");
+    println!("  Gagnés:        {}", shift.gained.join(", "));
+    println!("  Perdus:        {}", shift.lost.join(", "));
+    for change in &shift.reranked {
+        println!(
+            "  Reclassé:      {} (#{} -> #{})",
+            change.concept, change.rank_a, change.rank_b
+        );
+    }
+}
+
 fn print_verdict_banner(verdict: &LdsiVerdict) {
     let banner = match verdict {
         LdsiVerdict::Zombie => {
@@ -237,6 +1103,16 @@ fn print_verdict_banner(verdict: &LdsiVerdict) {
  / __/ / /_/ /_/ /
 /_/    \____/\____/
    [FOU] - Chaos detecte
+"#
+        }
+        LdsiVerdict::Refus => {
+            r#"
+    ____  ____________  __  _____
+   / __ \/ ____/ ____/ / / / ___/
+  / /_/ / __/ / /_  / / / /\__ \
+ / _, _/ /___/ __/ / /_/ /___/ /
+/_/ |_/_____/_/    \____//____/
+   [REFUS] - Refus detecte, divergence non notee
 "#
         }
     };
@@ -251,40 +1127,172 @@ async fn main() {
         Commands::Serve {
             port,
             openrouter_key,
+            prices,
+            profiles,
+            cache,
         } => {
             // Chercher la clé API dans l'environnement si non fournie
             let api_key = openrouter_key.or_else(|| std::env::var("OPENROUTER_API_KEY").ok());
+            let prices = load_prices(prices.as_deref());
+            let profiles = match profiles {
+                Some(path) => ProfileSet::load(&path).unwrap_or_else(|e| {
+                    eprintln!("Profils invalides ({}): {}", path, e);
+                    std::process::exit(1);
+                }),
+                None => ProfileSet::default(),
+            };
 
-            server::start_server(port, api_key).await;
+            server::start_server(port, api_key, prices, profiles, cache.to_config()).await;
         }
 
         Commands::Analyze {
             text_a,
             text_b,
             clean,
+            language,
+            stem,
+            numerals,
+            markup,
+            code,
+            math,
+            tables,
+            links,
+            pipeline,
+            strip_boilerplate,
             output,
             alpha,
             beta,
             gamma,
+            topology_term,
+            graph,
         } => {
+            let topology_term = match topology_term.to_lowercase().as_str() {
+                "delta" => TopologyTerm::Delta,
+                "wl" => TopologyTerm::WeisfeilerLehman,
+                "ged" => TopologyTerm::EditDistance,
+                _ => {
+                    eprintln!(
+                        "Terme topologique inconnu: {}. Utiliser: delta, wl, ged",
+                        topology_term
+                    );
+                    std::process::exit(1);
+                }
+            };
+
             let start = Instant::now();
 
-            let mut content_a = load_text(&text_a);
-            let mut content_b = load_text(&text_b);
+            // Traces <think> séparées : seule la réponse finale est notée
+            let (mut content_a, reasoning_a) = split_reasoning(&load_text(&text_a));
+            let (mut content_b, reasoning_b) = split_reasoning(&load_text(&text_b));
+            // Avant nettoyage : stop-words retirés et racines trompent la détection
+            let (language_a, language_b) =
+                (detect_language(&content_a), detect_language(&content_b));
+
+            if markup {
+                let config = MarkupConfig {
+                    code: parse_block_policy(&code),
+                    math: parse_block_policy(&math),
+                    tables: parse_block_policy(&tables),
+                    links: parse_block_policy(&links),
+                    html: true,
+                };
+                let doc_a = parse_markup(&content_a, &config);
+                let doc_b = parse_markup(&content_b, &config);
+                print_markup("A", &doc_a.report);
+                print_markup("B", &doc_b.report);
+                print_extracted("code", &doc_a.code_text(), &doc_b.code_text());
+                print_extracted("maths", &doc_a.math_text(), &doc_b.math_text());
+                content_a = doc_a.prose;
+                content_b = doc_b.prose;
+            }
 
-            if clean {
-                content_a = clean_default(&content_a);
-                content_b = clean_default(&content_b);
-                println!("[CLEAN] Textes nettoyés (stop-words supprimés)");
+            // Refus détectés avant tout retrait : ils imposent le verdict REFUS
+            let boilerplate_a = detect_boilerplate(&content_a);
+            let boilerplate_b = detect_boilerplate(&content_b);
+            print_boilerplate("A", &boilerplate_a);
+            print_boilerplate("B", &boilerplate_b);
+            if strip_boilerplate {
+                content_a = strip_boilerplate_text(&content_a);
+                content_b = strip_boilerplate_text(&content_b);
+                println!("[BOILERPLATE] Retiré avant calcul");
             }
 
-            let coefficients = LdsiCoefficients { alpha, beta, gamma };
-            let result = compute_ldsi(&content_a, &content_b, Some(coefficients));
+            // La racinisation passe par le nettoyeur, stop-words conservés sans --clean
+            let numerals = numerals.as_deref().map(|form| NumeralConfig {
+                target: parse_numeral_form(form),
+                ..Default::default()
+            });
+            let cleaner_config = (pipeline.is_none() && (clean || stem || numerals.is_some()))
+                .then(|| CleanerConfig {
+                    remove_stopwords: clean,
+                    // Nombres normalisés conservés
+                    remove_numbers: numerals.is_none(),
+                    language: parse_language(&language),
+                    stem,
+                    numerals,
+                    ..Default::default()
+                });
+            let cleaning = match (&pipeline, &cleaner_config) {
+                (Some(path), _) => Some(
+                    CleaningPipeline::from_toml(&load_text(path)).unwrap_or_else(|e| {
+                        eprintln!("Pipeline invalide ({}): {}", path, e);
+                        std::process::exit(1);
+                    }),
+                ),
+                (None, Some(config)) => {
+                    if config.language == Language::Auto {
+                        println!(
+                            "[CLEAN] Langues détectées: A={:?} B={:?}",
+                            language_a, language_b
+                        );
+                    }
+                    if clean {
+                        println!("[CLEAN] Textes nettoyés (stop-words supprimés)");
+                    }
+                    if stem {
+                        println!("[CLEAN] Racinisation Snowball appliquée (FR/EN)");
+                    }
+                    if let Some(numerals) = &config.numerals {
+                        println!(
+                            "[CLEAN] Nombres, unités et dates normalisés ({:?})",
+                            numerals.target
+                        );
+                    }
+                    Some(CleaningPipeline::from_config(config))
+                }
+                (None, None) => None,
+            };
+            let cleaning = cleaning.map(|pipeline| {
+                println!("[PIPELINE] Étapes: {}", pipeline.stage_names().join(" -> "));
+                let cleaned_a = pipeline.run(&content_a);
+                let cleaned_b = pipeline.run(&content_b);
+                print_trace("A", &cleaned_a.1);
+                print_trace("B", &cleaned_b.1);
+                content_a = cleaned_a.0.clone();
+                content_b = cleaned_b.0.clone();
+                (cleaned_a, cleaned_b)
+            });
+
+            let coefficients = LdsiCoefficients {
+                alpha,
+                beta,
+                gamma,
+                topology_term,
+            };
+            let topology_config = graph.to_config();
+            let result =
+                compute_ldsi_with(&content_a, &content_b, Some(coefficients), &topology_config);
+            let result = apply_refusal(result, &boilerplate_a, &boilerplate_b);
 
             let duration = start.elapsed().as_millis() as u64;
 
             print_verdict_banner(&result.verdict);
             print_result(&result);
+            let reasoning_result = reasoning_ldsi(
+                reasoning_a.as_deref(),
+                reasoning_b.as_deref(),
+                &topology_config,
+            );
 
             if let Some(out_path) = output {
                 let entry = AuditLogger::create_entry(
@@ -296,6 +1304,25 @@ async fn main() {
                     result,
                     duration,
                 );
+                let entry = AuditLogger::with_languages(entry, language_a, language_b);
+                let mut entry = AuditLogger::with_reasoning(
+                    entry,
+                    reasoning_a.as_deref(),
+                    reasoning_b.as_deref(),
+                    reasoning_result,
+                );
+                if let Some(config) = &cleaner_config {
+                    entry = AuditLogger::with_cleaner(entry, config);
+                }
+                if let Some((cleaned_a, cleaned_b)) = cleaning {
+                    entry = AuditLogger::with_cleaning(entry, cleaned_a, cleaned_b);
+                }
+                entry = AuditLogger::with_boilerplate(
+                    entry,
+                    boilerplate_a,
+                    boilerplate_b,
+                    strip_boilerplate,
+                );
                 AuditLogger::write_single(&entry, &out_path).unwrap();
                 println!("\n[AUDIT] Résultat sauvegardé: {}", out_path);
             }
@@ -308,40 +1335,127 @@ async fn main() {
             api_key,
             prompt_a,
             prompt_b,
+            system_a,
+            system_b,
+            conversation_a,
+            conversation_b,
             output,
+            max_attempts,
+            stream,
+            prices,
+            sampling,
+            endpoint,
+            cache,
+            graph,
         } => {
-            let api = match api_type.to_lowercase().as_str() {
-                "ollama" => ApiType::Ollama,
-                "openai" => ApiType::OpenAI,
-                "anthropic" => ApiType::Anthropic,
-                "openrouter" => ApiType::OpenRouter,
-                _ => {
-                    eprintln!(
-                        "Type API inconnu: {}. Utiliser: ollama, openai, anthropic, openrouter",
-                        api_type
-                    );
-                    std::process::exit(1);
+            let topology_config = graph.to_config();
+            let conversation_a = build_conversation(
+                "A",
+                conversation_a.as_deref(),
+                system_a.as_deref(),
+                prompt_a.as_deref(),
+            );
+            let conversation_b = build_conversation(
+                "B",
+                conversation_b.as_deref(),
+                system_b.as_deref(),
+                prompt_b.as_deref(),
+            );
+
+            // Un profil fixe l'endpoint ; sinon --url, --model et --api-type
+            let base = match &endpoint.profile {
+                Some(name) => {
+                    let profile = ProfileSet::load(&endpoint.profiles)
+                        .and_then(|set| set.config(name))
+                        .unwrap_or_else(|e| {
+                            eprintln!("Profil {} ({}): {}", name, endpoint.profiles, e);
+                            std::process::exit(1);
+                        });
+                    LlmConfig {
+                        api_key: api_key.or(profile.api_key.clone()),
+                        ..profile
+                    }
+                }
+                None => {
+                    let api = match api_type.to_lowercase().as_str() {
+                        "ollama" => ApiType::Ollama,
+                        "openai" => ApiType::OpenAI,
+                        "anthropic" => ApiType::Anthropic,
+                        "openrouter" => ApiType::OpenRouter,
+                        "openai-compatible" => ApiType::OpenAICompatible,
+                        "gemini" => ApiType::Gemini,
+                        "mistral" => ApiType::Mistral,
+                        _ => {
+                            eprintln!(
+                                "Type API inconnu: {}. Utiliser: ollama, openai, anthropic, openrouter, openai-compatible, gemini, mistral",
+                                api_type
+                            );
+                            std::process::exit(1);
+                        }
+                    };
+                    // Gemini et Mistral : URL officielle sauf --url explicite
+                    let explicit_url = url != LlmConfig::default().base_url;
+                    LlmConfig {
+                        base_url: match api {
+                            ApiType::OpenRouter => "https://openrouter.ai/api".to_string(),
+                            ApiType::Gemini if !explicit_url => {
+                                LlmConfig::gemini(&model, "").base_url
+                            }
+                            ApiType::Mistral if !explicit_url => {
+                                LlmConfig::mistral(&model, "").base_url
+                            }
+                            _ => url,
+                        },
+                        model,
+                        api_key,
+                        api_type: api,
+                        ..Default::default()
+                    }
                 }
             };
+            let model = base.model.clone();
 
             let config = LlmConfig {
-                base_url: if api == ApiType::OpenRouter {
-                    "https://openrouter.ai/api".to_string()
-                } else {
-                    url
+                endpoint: endpoint.apply(base.endpoint.clone()),
+                retry: match max_attempts {
+                    0 | 1 => RetryPolicy::none(),
+                    _ => RetryPolicy {
+                        max_attempts,
+                        ..Default::default()
+                    },
                 },
-                model: model.clone(),
-                api_key,
-                api_type: api,
-                ..Default::default()
+                stream,
+                temperature: sampling.temperature,
+                max_tokens: sampling.max_tokens,
+                sampling: sampling.to_params(),
+                cache: cache.to_config(),
+                ..base
             };
+            if let Err(e) = config.sampling.validate(config.api_type) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+            let effective_sampling = config.effective_sampling();
+            let prices = load_prices(prices.as_deref());
 
             let injector = Injector::new(config);
 
             println!("[INJECT] Envoi prompt A (standard)...");
             let start = Instant::now();
 
-            let (response_a, response_b) = match injector.inject_ab(&prompt_a, &prompt_b).await {
+            let responses = if stream {
+                match inject_live(&injector, &conversation_a).await {
+                    Ok(a) => inject_live(&injector, &conversation_b)
+                        .await
+                        .map(|b| (a, b)),
+                    Err(e) => Err(e),
+                }
+            } else {
+                injector
+                    .inject_ab_conversations(&conversation_a, &conversation_b)
+                    .await
+            };
+            let (full_a, full_b) = match responses {
                 Ok(responses) => responses,
                 Err(e) => {
                     eprintln!("Erreur injection: {}", e);
@@ -350,22 +1464,67 @@ async fn main() {
             };
 
             println!("[INJECT] Envoi prompt B (fracturé)... OK");
+            print_retry("A", &full_a.retry);
+            print_retry("B", &full_b.retry);
+            print_timing("A", full_a.timing.as_ref());
+            print_timing("B", full_b.timing.as_ref());
+            let usage_a = prices.usage(&model, &full_a);
+            let usage_b = prices.usage(&model, &full_b);
+            print_usage("A", &usage_a);
+            print_usage("B", &usage_b);
+            let mut session = SessionCost::default();
+            session.add(&usage_a);
+            session.add(&usage_b);
+            if session.unpriced < session.requests {
+                println!(
+                    "[COÛT] Session: ${:.6} ({} tokens prompt, {} générés, {} depuis le cache)",
+                    session.total_usd,
+                    session.prompt_tokens,
+                    session.completion_tokens,
+                    session.cached
+                );
+            }
 
-            let result = compute_ldsi(&response_a, &response_b, None);
+            let (retry_a, retry_b) = (full_a.retry.clone(), full_b.retry.clone());
+            let (timing_a, timing_b) = (full_a.timing.clone(), full_b.timing.clone());
+            let (response_a, response_b) = (full_a.content, full_b.content);
+            let boilerplate_a = detect_boilerplate(&response_a);
+            let boilerplate_b = detect_boilerplate(&response_b);
+            let result = compute_ldsi_with(&response_a, &response_b, None, &topology_config);
+            let result = apply_refusal(result, &boilerplate_a, &boilerplate_b);
             let duration = start.elapsed().as_millis() as u64;
 
             print_verdict_banner(&result.verdict);
+            print_boilerplate("A", &boilerplate_a);
+            print_boilerplate("B", &boilerplate_b);
             print_result(&result);
+            let reasoning_result = reasoning_ldsi(
+                full_a.reasoning.as_deref(),
+                full_b.reasoning.as_deref(),
+                &topology_config,
+            );
 
             let entry = AuditLogger::create_entry(
                 &model,
-                &prompt_a,
-                &prompt_b,
+                conversation_a.last_user().unwrap_or_default(),
+                conversation_b.last_user().unwrap_or_default(),
                 &response_a,
                 &response_b,
                 result,
                 duration,
             );
+            let entry = AuditLogger::with_reasoning(
+                entry,
+                full_a.reasoning.as_deref(),
+                full_b.reasoning.as_deref(),
+                reasoning_result,
+            );
+            let entry = AuditLogger::with_boilerplate(entry, boilerplate_a, boilerplate_b, false);
+            let entry = AuditLogger::with_retry(entry, retry_a, retry_b);
+            let entry = AuditLogger::with_timing(entry, timing_a, timing_b);
+            let entry = AuditLogger::with_conversation(entry, &conversation_a, &conversation_b);
+            let entry = AuditLogger::with_sampling(entry, effective_sampling);
+            let entry = AuditLogger::with_usage(entry, usage_a, usage_b);
 
             AuditLogger::write_single(&entry, &output).unwrap();
             println!("\n[AUDIT] Résultat sauvegardé: {}", output);
@@ -402,9 +1561,22 @@ async fn main() {
             println!("  H(bigrammes):  {:.6} bits", h2);
         }
 
-        Commands::Topology { text } => {
+        Commands::Topology {
+            text,
+            text_b,
+            central,
+            top_k,
+            small_world,
+            motifs,
+            null_model,
+            seed,
+            replicates,
+            graph,
+        } => {
             let content = load_text(&text);
-            let result = core::topology::analyze_topology(&content);
+            let content_b = text_b.as_deref().map(load_text);
+            let config = graph.to_config();
+            let result = core::topology::analyze_topology_with(&content, &config);
 
             println!("\n[TOPOLOGY] Analyse de Graphe");
             println!("  Noeuds:        {}", result.node_count);
@@ -417,6 +1589,107 @@ async fn main() {
             println!("  Avg path len:  {:.6}", result.avg_path_length);
             println!("  Small-world:   {:.6}", result.small_world_index);
             println!("  Avg degree:    {:.6}", result.avg_degree);
+
+            if let Some(raw) = &result.raw {
+                println!("\n[BACKBONE] {:?} (brut -> squelette)", config.backbone);
+                println!(
+                    "  Aretes:        {} -> {}",
+                    raw.edge_count, result.edge_count
+                );
+                println!(
+                    "  Densite:       {:.6} -> {:.6}",
+                    raw.density, result.density
+                );
+                println!(
+                    "  LCC ratio:     {:.6} -> {:.6}",
+                    raw.lcc_ratio, result.lcc_ratio
+                );
+                println!(
+                    "  Clustering:    {:.6} -> {:.6}",
+                    raw.clustering_coefficient, result.clustering_coefficient
+                );
+                println!(
+                    "  Avg path len:  {:.6} -> {:.6}",
+                    raw.avg_path_length, result.avg_path_length
+                );
+                println!(
+                    "  Small-world:   {:.6} -> {:.6}",
+                    raw.small_world_index, result.small_world_index
+                );
+            }
+
+            print_degrees(&result.degrees);
+            if config.persistence_max_vertices > 0 {
+                print_persistence(&result.persistence);
+            }
+
+            match &content_b {
+                Some(content_b) => {
+                    let result_b = core::topology::analyze_topology_with(content_b, &config);
+                    print_directed("A", &result.directed);
+                    print_directed("B", &result_b.directed);
+                }
+                None => print_directed("", &result.directed),
+            }
+
+            let model = match null_model.to_lowercase().as_str() {
+                "degree" => NullModel::DegreePreserving,
+                "er" => NullModel::ErdosRenyi,
+                "shuffle" => NullModel::TokenShuffle,
+                _ => {
+                    eprintln!(
+                        "Modèle nul inconnu: {}. Utiliser: degree, er, shuffle",
+                        null_model
+                    );
+                    std::process::exit(1);
+                }
+            };
+            let null_config = NullModelConfig {
+                model,
+                seed,
+                replicates,
+            };
+
+            if small_world {
+                let sw = core::null_model::small_world(&content, &config, &null_config);
+
+                println!("\n[SMALL-WORLD] Normalisation par modèle nul");
+                println!("  Modèle:        {:?}", sw.model);
+                println!("  Graine:        {}", sw.seed);
+                println!("  Répliques:     {}", sw.replicates);
+                println!(
+                    "  C / C_r:       {:.6} / {:.6}",
+                    sw.clustering, sw.clustering_random
+                );
+                println!(
+                    "  L / L_r:       {:.6} / {:.6}",
+                    sw.path_length, sw.path_length_random
+                );
+                println!("  C_lattice:     {:.6}", sw.clustering_lattice);
+                println!("  Sigma:         {:.6}", sw.sigma);
+                println!("  Omega:         {:.6}", sw.omega);
+            }
+
+            if motifs {
+                let profile = core::topology::motif_profile(&content, &config, &null_config);
+                print_motifs(&profile);
+            }
+
+            if central {
+                match &content_b {
+                    Some(content_b) => {
+                        let shift =
+                            core::centrality::concept_shift(&content, content_b, top_k, &config);
+                        print_concepts("A", &shift.top_a);
+                        print_concepts("B", &shift.top_b);
+                        print_concept_shift(&shift);
+                    }
+                    None => {
+                        let concepts = core::centrality::top_concepts(&content, top_k, &config);
+                        print_concepts("", &concepts);
+                    }
+                }
+            }
         }
 
         Commands::Info => {
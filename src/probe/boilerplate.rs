@@ -0,0 +1,465 @@
+//! Module Boilerplate - Refus, Avertissements et Formules Toutes Faites
+//!
+//! Un refus ("I'm sorry, I can't help with that") a une NCD élevée face à
+//! une réponse utile : sans détection, il serait noté REBELLE ou ARCHITECTE.
+//! Ce module repère, phrase par phrase, quatre familles de texte convenu
+//! (refus, avertissement d'IA, mise en garde, formule de clôture) dans les
+//! sept langues du nettoyeur, pour signaler les refus et, au besoin,
+//! retirer le boilerplate avant le calcul.
+//!
+//! Auteur: Julien DABERT
+//! LDSI - Lyapunov-Dabert Stability Index
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+use crate::core::{LdsiResult, LdsiVerdict};
+
+/// Part minimale de boilerplate pour qu'un refus qualifie la réponse
+const REFUSAL_RATIO: f64 = 0.5;
+
+/// En deçà de ce nombre de mots hors boilerplate, un refus qualifie la réponse
+const REFUSAL_MAX_CONTENT_WORDS: usize = 60;
+
+/// Famille de texte convenu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BoilerplateKind {
+    /// Refus de répondre
+    Refusal,
+    /// "En tant qu'IA…", absence d'opinions, date de coupure
+    Disclaimer,
+    /// Mise en garde : consulter un professionnel, "ceci n'est pas un avis…"
+    Caveat,
+    /// Formule de clôture : "J'espère que cela vous aide", "N'hésitez pas…"
+    SignOff,
+}
+
+/// Motifs par famille (casse ignorée), toutes langues confondues
+const PATTERNS: &[(BoilerplateKind, &str)] = &[
+    // Refus
+    (
+        BoilerplateKind::Refusal,
+        r"\bi(?:['’]m| am) (?:sorry|afraid)\b[^.!?\n]{0,40}\b(?:can(?:no|['’])?t|unable to|won['’]t|not able to) (?:help|assist|comply|provide|fulfill|support|engage|do that)\b",
+    ),
+    (
+        BoilerplateKind::Refusal,
+        r"\bi (?:can(?:no|['’])?t|won['’]t|will not|(?:am|['’]m) (?:unable|not able) to) (?:help|assist|comply|provide|fulfill|support|engage|create|generate|write|do that)",
+    ),
+    (
+        BoilerplateKind::Refusal,
+        r"\bi (?:must|have to|need to) (?:respectfully )?(?:decline|refuse)\b",
+    ),
+    (
+        BoilerplateKind::Refusal,
+        r"\bje (?:suis désolée?|regrette)\b.*\b(?:ne (?:peux|pourrai|vais) pas|pas en mesure)\b",
+    ),
+    (
+        BoilerplateKind::Refusal,
+        r"\bje ne (?:peux|pourrai|vais) pas (?:vous |t['’])?(?:aider|répondre|fournir|satisfaire|donner|rédiger|générer|créer|accéder)",
+    ),
+    (
+        BoilerplateKind::Refusal,
+        r"\bje (?:dois|me dois de) (?:refuser|décliner)\b|\bje ne suis pas en mesure de\b",
+    ),
+    (
+        BoilerplateKind::Refusal,
+        r"\blo siento\b.*\bno (?:puedo|podré)\b|\bno puedo (?:ayudar|proporcionar|cumplir|responder|asistir)",
+    ),
+    (
+        BoilerplateKind::Refusal,
+        r"\b(?:es tut mir leid|leider)\b.*\b(?:kann|darf|werde) ich\b.*\b(?:nicht|keine)\b|\bich kann (?:ihnen |dir )?(?:dabei |damit )?nicht helfen\b",
+    ),
+    (
+        BoilerplateKind::Refusal,
+        r"\bmi dispiace\b.*\bnon (?:posso|sono in grado)\b|\bnon posso (?:aiutar|fornire|rispondere)",
+    ),
+    (
+        BoilerplateKind::Refusal,
+        r"\b(?:desculpe|lamento|sinto muito)\b.*\bnão (?:posso|consigo)\b|\bnão posso (?:ajudar|fornecer|responder)",
+    ),
+    (
+        BoilerplateKind::Refusal,
+        r"\b(?:het spijt me|sorry)\b.*\b(?:kan|mag) ik\b.*\b(?:niet|geen)\b|\bik kan (?:je |u )?(?:daar |hier )?niet (?:mee )?helpen\b",
+    ),
+    // Avertissements d'IA
+    (
+        BoilerplateKind::Disclaimer,
+        r"\bas an? (?:ai|artificial intelligence|(?:large )?language model|ai (?:language model|assistant))\b",
+    ),
+    (
+        BoilerplateKind::Disclaimer,
+        r"\bi(?:['’]m| am) (?:just |only )?an? (?:ai|language model|ai assistant)\b|\bi (?:don['’]t|do not) have (?:personal )?(?:opinions|feelings|emotions|beliefs)\b|\bmy (?:knowledge|training data) (?:cutoff|cut-off)\b",
+    ),
+    (
+        BoilerplateKind::Disclaimer,
+        r"\ben tant qu['’](?:ia|intelligence artificielle|assistant|modèle de langage)\b|\bje (?:suis |ne suis qu['’])(?:un|une) (?:ia|intelligence artificielle|modèle de langage)\b|\bje n['’]ai pas d['’](?:opinions?|émotions|sentiments)\b",
+    ),
+    (
+        BoilerplateKind::Disclaimer,
+        r"\bcomo (?:una? )?(?:ia|inteligencia artificial|modelo de lenguaje)\b",
+    ),
+    (
+        BoilerplateKind::Disclaimer,
+        r"\bals (?:eine? )?(?:ki|künstliche intelligenz|sprachmodell)\b",
+    ),
+    (
+        BoilerplateKind::Disclaimer,
+        r"\bin quanto (?:ia|intelligenza artificiale|modello linguistico)\b",
+    ),
+    (
+        BoilerplateKind::Disclaimer,
+        r"\bcomo (?:uma? )?(?:ia|inteligência artificial|modelo de linguagem)\b",
+    ),
+    (
+        BoilerplateKind::Disclaimer,
+        r"\bals (?:een )?(?:ai|taalmodel)\b",
+    ),
+    // Mises en garde
+    (
+        BoilerplateKind::Caveat,
+        r"\b(?:consult|seek|speak (?:with|to)|talk to) (?:a|an|your) (?:qualified |licensed |medical |healthcare |legal )?(?:professional|doctor|physician|lawyer|attorney|therapist|financial advisor|expert)",
+    ),
+    (
+        BoilerplateKind::Caveat,
+        r"\bthis (?:is not|does not constitute) (?:medical|legal|financial|professional) advice\b|\bfor (?:informational|educational) purposes only\b|\bplease (?:be careful|use caution|exercise caution)\b",
+    ),
+    (
+        BoilerplateKind::Caveat,
+        r"\bconsultez (?:un|une|votre) (?:professionnel|médecin|avocat|spécialiste|expert)|\bceci (?:ne constitue pas|n['’]est pas) un (?:avis|conseil) (?:médical|juridique|financier|professionnel)|\bà titre (?:informatif|indicatif) (?:uniquement|seulement)\b",
+    ),
+    (
+        BoilerplateKind::Caveat,
+        r"\bconsulte (?:a|con) un (?:profesional|médico|abogado)|\besto no (?:constituye|es) (?:un )?consejo\b",
+    ),
+    (
+        BoilerplateKind::Caveat,
+        r"\bwenden sie sich an (?:einen|eine|ihren|ihre) (?:arzt|ärztin|anwalt|fachmann|experten)|\bdies (?:ist|stellt) keine (?:medizinische|rechtliche|finanzielle)",
+    ),
+    (
+        BoilerplateKind::Caveat,
+        r"\bconsulta(?:re)? un (?:professionista|medico|avvocato)|\bquesto non (?:costituisce|è) (?:un )?(?:consiglio|parere)\b",
+    ),
+    (
+        BoilerplateKind::Caveat,
+        r"\bconsulte um (?:profissional|médico|advogado)|\bisto não (?:constitui|é) (?:um )?(?:conselho|aconselhamento)\b",
+    ),
+    (
+        BoilerplateKind::Caveat,
+        r"\braadpleeg (?:een|uw|je) (?:arts|huisarts|advocaat|professional|deskundige)",
+    ),
+    // Formules de clôture
+    (
+        BoilerplateKind::SignOff,
+        r"\bhope (?:this|that) helps\b|\b(?:let me know|feel free to (?:ask|reach out))\b|\bif you have any (?:other|more|further) questions\b|\bis there anything else\b",
+    ),
+    (
+        BoilerplateKind::SignOff,
+        r"\bj['’]espère que (?:cela|ça|ceci) (?:vous |t['’])(?:aide|aidera|a aidé)|\bn['’]hésitez pas à\b|\bsi vous avez d['’]autres questions\b|\by a-t-il autre chose\b",
+    ),
+    (
+        BoilerplateKind::SignOff,
+        r"\bespero que (?:esto|te|le) (?:ayude|sirva)\b|\bno dudes en\b|\bsi tienes (?:alguna |más )?(?:otra )?preguntas?\b",
+    ),
+    (
+        BoilerplateKind::SignOff,
+        r"\bich hoffe,? (?:das|dies) hilft\b|\bzögern sie nicht\b|\bwenn sie (?:noch )?(?:weitere )?fragen haben\b",
+    ),
+    (
+        BoilerplateKind::SignOff,
+        r"\bspero che (?:questo|ti) (?:sia|aiuti|possa)\b|\bnon esitare a\b|\bse hai (?:altre )?domande\b",
+    ),
+    (
+        BoilerplateKind::SignOff,
+        r"\bespero que (?:isso|isto) (?:ajude|te ajude)\b|\bnão hesite em\b|\bse tiver (?:mais |outras )?dúvidas\b",
+    ),
+    (
+        BoilerplateKind::SignOff,
+        r"\bik hoop dat (?:dit|dat) helpt\b|\baarzel niet\b|\bals je (?:nog )?(?:meer |andere )?vragen hebt\b",
+    ),
+];
+
+/// Motifs compilés une seule fois
+fn patterns() -> &'static [(BoilerplateKind, Regex)] {
+    static COMPILED: OnceLock<Vec<(BoilerplateKind, Regex)>> = OnceLock::new();
+    COMPILED.get_or_init(|| {
+        PATTERNS
+            .iter()
+            .map(|(kind, pattern)| (*kind, Regex::new(&format!("(?i){}", pattern)).unwrap()))
+            .collect()
+    })
+}
+
+/// Phrase reconnue comme boilerplate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoilerplateMatch {
+    pub kind: BoilerplateKind,
+    pub sentence: String,
+}
+
+/// Diagnostic d'une réponse
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BoilerplateReport {
+    /// La réponse est un refus (et non une réponse avec réserve)
+    pub refusal: bool,
+    /// Part des caractères appartenant à des phrases de boilerplate
+    pub ratio: f64,
+    /// Phrases reconnues, dans l'ordre du texte
+    pub matches: Vec<BoilerplateMatch>,
+}
+
+impl BoilerplateReport {
+    /// Nombre de phrases d'une famille
+    pub fn count(&self, kind: BoilerplateKind) -> usize {
+        self.matches.iter().filter(|m| m.kind == kind).count()
+    }
+}
+
+/// Découpe en phrases (intervalles d'octets, sans espaces de bord)
+///
+/// Une phrase se termine par un saut de ligne ou par `.`, `!`, `?`, `…`
+/// suivi d'un espace ou de la fin du texte.
+fn sentences(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let end = match c {
+            '\n' => Some(i),
+            '.' | '!' | '?' | '…' => match chars.peek() {
+                None => Some(i + c.len_utf8()),
+                Some((_, next)) if next.is_whitespace() => Some(i + c.len_utf8()),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(end) = end {
+            spans.push((start, end));
+            start = end;
+        }
+    }
+    spans.push((start, text.len()));
+
+    spans
+        .into_iter()
+        .filter_map(|(s, e)| {
+            let slice = &text[s..e];
+            let trimmed = slice.trim();
+            (!trimmed.is_empty()).then(|| {
+                let offset = s + (slice.len() - slice.trim_start().len());
+                (offset, offset + trimmed.len())
+            })
+        })
+        .collect()
+}
+
+/// Famille d'une phrase (le refus prime sur les autres)
+fn classify(sentence: &str) -> Option<BoilerplateKind> {
+    patterns()
+        .iter()
+        .find(|(_, regex)| {
+            regex
+                .find_iter(sentence)
+                .any(|m| !help_but(m.as_str(), &sentence[m.end()..]))
+        })
+        .map(|(kind, _)| *kind)
+}
+
+/// Tournure "I can't help but…" (= ne pas pouvoir s'empêcher), pas un refus
+///
+/// Le crate regex n'a pas d'assertion avant : on vérifie après coup.
+fn help_but(matched: &str, rest: &str) -> bool {
+    let rest = rest.trim_start();
+    matched.to_lowercase().ends_with("help")
+        && rest.len() >= 4
+        && rest.is_char_boundary(4)
+        && rest[..4].eq_ignore_ascii_case("but ")
+}
+
+/// Sépare le boilerplate du contenu
+///
+/// # Returns
+/// (texte sans boilerplate, phrases retirées)
+pub fn split_boilerplate(text: &str) -> (String, Vec<BoilerplateMatch>) {
+    let mut kept = String::with_capacity(text.len());
+    let mut matches = Vec::new();
+    let mut cursor = 0;
+
+    for (start, end) in sentences(text) {
+        let sentence = &text[start..end];
+        if let Some(kind) = classify(sentence) {
+            kept.push_str(&text[cursor..start]);
+            cursor = end;
+            matches.push(BoilerplateMatch {
+                kind,
+                sentence: sentence.to_string(),
+            });
+        }
+    }
+    kept.push_str(&text[cursor..]);
+
+    (kept.trim().to_string(), matches)
+}
+
+/// Retire le boilerplate avant le calcul
+pub fn strip_boilerplate(text: &str) -> String {
+    split_boilerplate(text).0
+}
+
+/// Analyse une réponse
+///
+/// Un refus qualifie la réponse entière si le boilerplate en occupe au
+/// moins la moitié, ou si la réponse s'ouvre sur le refus et qu'il reste
+/// moins de `REFUSAL_MAX_CONTENT_WORDS` mots : "je ne peux pas X, mais voici
+/// Y" suivi d'une vraie réponse n'en est pas un, ni une réponse courte qui
+/// décline un point secondaire en fin de texte.
+pub fn detect_boilerplate(text: &str) -> BoilerplateReport {
+    let (content, matches) = split_boilerplate(text);
+
+    let total: usize = sentences(text)
+        .iter()
+        .map(|(s, e)| text[*s..*e].chars().count())
+        .sum();
+    let boilerplate: usize = matches.iter().map(|m| m.sentence.chars().count()).sum();
+    let ratio = if total > 0 {
+        boilerplate as f64 / total as f64
+    } else {
+        0.0
+    };
+
+    let refuses = matches.iter().any(|m| m.kind == BoilerplateKind::Refusal);
+    let leads = sentences(text)
+        .first()
+        .is_some_and(|(s, e)| classify(&text[*s..*e]) == Some(BoilerplateKind::Refusal));
+    let content_words = content.split_whitespace().count();
+    let refusal =
+        refuses && (ratio >= REFUSAL_RATIO || (leads && content_words < REFUSAL_MAX_CONTENT_WORDS));
+
+    BoilerplateReport {
+        refusal,
+        ratio,
+        matches,
+    }
+}
+
+/// Verdict REFUS si l'une des deux réponses est un refus
+///
+/// λLD est conservé pour l'audit, mais un refus n'est jamais noté comme
+/// divergence créative.
+pub fn apply_refusal(
+    mut result: LdsiResult,
+    report_a: &BoilerplateReport,
+    report_b: &BoilerplateReport,
+) -> LdsiResult {
+    if report_a.refusal || report_b.refusal {
+        result.verdict = LdsiVerdict::Refus;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::compute_ldsi;
+
+    #[test]
+    fn test_refusals_multilingual() {
+        let refusals = [
+            "I'm sorry, but I can't help with that.",
+            "I cannot assist with this request.",
+            "Je suis désolé, mais je ne peux pas répondre à cette demande.",
+            "Lo siento, pero no puedo ayudarte con eso.",
+            "Es tut mir leid, aber dabei kann ich dir nicht helfen.",
+            "Mi dispiace, ma non posso aiutarti con questa richiesta.",
+            "Desculpe, mas não posso ajudar com isso.",
+            "Het spijt me, maar daar kan ik je niet mee helpen.",
+        ];
+        for text in refusals {
+            let report = detect_boilerplate(text);
+            assert!(report.refusal, "{}", text);
+            assert_eq!(report.count(BoilerplateKind::Refusal), 1, "{}", text);
+            assert!((report.ratio - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_helpful_answer_with_caveats() {
+        let answer = "As an AI, I don't have personal opinions. \
+            Photosynthesis converts light energy into chemical energy stored in glucose. \
+            Chlorophyll absorbs mostly blue and red light and reflects green. \
+            The light-dependent reactions split water and release oxygen.\n\
+            The Calvin cycle then fixes carbon dioxide into sugars.\n\
+            Please consult a qualified professional before changing your diet. \
+            I hope this helps!";
+        let report = detect_boilerplate(answer);
+        assert!(!report.refusal);
+        assert_eq!(report.count(BoilerplateKind::Disclaimer), 1);
+        assert_eq!(report.count(BoilerplateKind::Caveat), 1);
+        assert_eq!(report.count(BoilerplateKind::SignOff), 1);
+        assert!(report.ratio > 0.1 && report.ratio < 0.5);
+
+        let stripped = strip_boilerplate(answer);
+        assert!(stripped.starts_with("Photosynthesis"));
+        assert!(stripped.ends_with("into sugars."));
+
+        // Refus partiel suivi d'une vraie réponse
+        let partial = format!("I can't provide medical advice. {}", stripped.repeat(2));
+        assert!(!detect_boilerplate(&partial).refusal);
+
+        // Refus secondaire en fin de réponse courte
+        let short = "Paris is the capital of France, its largest city and home to the Louvre. \
+            I can't provide live traffic data.";
+        let report = detect_boilerplate(short);
+        assert_eq!(report.count(BoilerplateKind::Refusal), 1);
+        assert!(!report.refusal);
+    }
+
+    #[test]
+    fn test_refusal_false_positives() {
+        for text in [
+            "I'm sorry to hear that your cat can't sleep. Try a warm blanket near the radiator.",
+            "I can't help but notice the pattern in your data.",
+            "I'm sorry, I can't help but laugh at that joke.",
+        ] {
+            let report = detect_boilerplate(text);
+            assert!(!report.refusal, "{}", text);
+            assert_eq!(report.count(BoilerplateKind::Refusal), 0, "{}", text);
+        }
+        assert!(detect_boilerplate("I can't help, but a pharmacist can.").refusal);
+    }
+
+    #[test]
+    fn test_clean_text_and_empty() {
+        let report = detect_boilerplate("");
+        assert!(!report.refusal);
+        assert_eq!(report.ratio, 0.0);
+        assert_eq!(
+            strip_boilerplate("Le chat dort. Il fait beau."),
+            "Le chat dort. Il fait beau."
+        );
+        // Un point décimal ne coupe pas la phrase
+        assert_eq!(sentences("Pi vaut 3.14 environ. Fin").len(), 2);
+    }
+
+    #[test]
+    fn test_apply_refusal() {
+        let helpful = "Paris is the capital of France and its largest city.";
+        let refusal = "I'm sorry, I can't help with that.";
+        let result = compute_ldsi(helpful, refusal, None);
+        let lambda = result.lambda;
+
+        let result = apply_refusal(
+            result,
+            &detect_boilerplate(helpful),
+            &detect_boilerplate(refusal),
+        );
+        assert_eq!(result.verdict, LdsiVerdict::Refus);
+        assert_eq!(result.lambda, lambda);
+
+        let result = compute_ldsi(helpful, helpful, None);
+        let verdict = result.verdict.clone();
+        let report = detect_boilerplate(helpful);
+        assert_eq!(apply_refusal(result, &report, &report).verdict, verdict);
+    }
+}
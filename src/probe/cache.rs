@@ -0,0 +1,351 @@
+//! Module Cache - Cache disque des réponses
+//!
+//! Une réponse est rangée sous l'empreinte de tout ce qui la détermine :
+//! provider, hôte et endpoint (chemin, paramètres de requête), modèle,
+//! messages, température, `max_tokens`, paramètres
+//! d'échantillonnage et graine. Rejouer un benchmark contre le cache permet
+//! d'essayer une nouvelle formule sur les mêmes échantillons, sans coût ni
+//! réseau :
+//!
+//! | Mode          | Lecture | Écriture | Réponse absente du cache |
+//! |---------------|---------|----------|--------------------------|
+//! | `off`         | non     | non      | requête                  |
+//! | `read-write`  | oui     | oui      | requête, puis écriture   |
+//! | `read-only`   | oui     | non      | requête                  |
+//! | `replay-only` | oui     | non      | erreur                   |
+//!
+//! Une réponse rejouée garde son usage, sa latence et son profil de
+//! streaming d'origine ; `retry.attempts = 0` signale qu'elle ne vient pas
+//! du réseau. Réponses streamées et non streamées partagent les mêmes
+//! entrées.
+//!
+//! Auteur: Julien DABERT
+//! LDSI - Lyapunov-Dabert Stability Index
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use super::conversation::{Conversation, Message};
+use super::injector::{ApiType, Completion, LlmConfig, RetryStats};
+use super::sampling::SamplingParams;
+
+/// Répertoire du cache par défaut
+pub const DEFAULT_CACHE_DIR: &str = ".ldsi-cache";
+
+/// Version du format de l'empreinte (à incrémenter si ses champs changent)
+const KEY_VERSION: u32 = 2;
+
+/// Utilisation du cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CacheMode {
+    /// Aucune lecture ni écriture
+    #[default]
+    Off,
+    /// Lit les réponses connues, enregistre les nouvelles
+    ReadWrite,
+    /// Lit les réponses connues, n'enregistre rien
+    ReadOnly,
+    /// Hors ligne : toute réponse absente du cache est une erreur
+    ReplayOnly,
+}
+
+/// Mode et emplacement du cache
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    pub mode: CacheMode,
+    /// Répertoire des entrées (un fichier JSON par réponse)
+    pub dir: PathBuf,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            mode: CacheMode::Off,
+            dir: PathBuf::from(DEFAULT_CACHE_DIR),
+        }
+    }
+}
+
+/// Erreur de lecture ou d'écriture du cache
+#[derive(Debug, Clone, PartialEq)]
+pub enum CacheError {
+    /// Répertoire ou fichier inaccessible
+    Io(String),
+    /// Entrée illisible
+    Corrupt(String),
+    /// Réponse absente en mode `replay-only`
+    Miss(String),
+}
+
+impl std::fmt::Display for CacheError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CacheError::Io(e) => write!(f, "IO error: {}", e),
+            CacheError::Corrupt(e) => write!(f, "Entrée de cache invalide: {}", e),
+            CacheError::Miss(key) => {
+                write!(f, "Réponse absente du cache (replay-only): {}", key)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CacheError {}
+
+/// Ce qui détermine une réponse, sérialisé pour l'empreinte
+#[derive(Serialize)]
+struct KeyMaterial<'a> {
+    version: u32,
+    api_type: ApiType,
+    /// Deux serveurs peuvent servir des modèles de même nom
+    base_url: &'a str,
+    path: Option<&'a str>,
+    query: &'a BTreeMap<String, String>,
+    model: &'a str,
+    messages: Vec<Message>,
+    temperature: f32,
+    max_tokens: u32,
+    /// Graine comprise
+    sampling: &'a SamplingParams,
+}
+
+/// Entrée du cache sur disque
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    key: String,
+    model: String,
+    cached_at: DateTime<Utc>,
+    completion: Completion,
+}
+
+/// FNV-1a 128 bits : empreinte stable d'une version de Rust à l'autre,
+/// contrairement au hasher de la bibliothèque standard
+fn fnv1a_128(bytes: &[u8]) -> u128 {
+    const OFFSET: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+    bytes
+        .iter()
+        .fold(OFFSET, |hash, &b| (hash ^ b as u128).wrapping_mul(PRIME))
+}
+
+impl CacheConfig {
+    /// Le cache est-il consulté ?
+    pub fn is_enabled(&self) -> bool {
+        self.mode != CacheMode::Off
+    }
+
+    /// Empreinte d'une conversation envoyée avec `config`
+    pub fn key(config: &LlmConfig, conversation: &Conversation) -> String {
+        let material = KeyMaterial {
+            version: KEY_VERSION,
+            api_type: config.api_type,
+            base_url: &config.base_url,
+            path: config.endpoint.path.as_deref(),
+            query: &config.endpoint.query,
+            model: &config.model,
+            messages: conversation.with_system_first(),
+            temperature: config.temperature,
+            max_tokens: config.max_tokens,
+            sampling: &config.sampling,
+        };
+        let bytes = serde_json::to_vec(&material).expect("Empreinte toujours sérialisable");
+        format!("{:032x}", fnv1a_128(&bytes))
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    /// Réponse enregistrée sous `key` (None si absente, erreur en `replay-only`)
+    pub fn lookup(&self, key: &str) -> Result<Option<Completion>, CacheError> {
+        if !self.is_enabled() {
+            return Ok(None);
+        }
+        let path = self.path(key);
+        let source = match std::fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return match self.mode {
+                    CacheMode::ReplayOnly => Err(CacheError::Miss(key.to_string())),
+                    _ => Ok(None),
+                };
+            }
+            Err(e) => return Err(CacheError::Io(format!("{}: {}", path.display(), e))),
+        };
+        let entry: CacheEntry = serde_json::from_str(&source)
+            .map_err(|e| CacheError::Corrupt(format!("{}: {}", path.display(), e)))?;
+        Ok(Some(Completion {
+            retry: RetryStats::default(),
+            ..entry.completion
+        }))
+    }
+
+    /// Enregistre une réponse (mode `read-write` uniquement)
+    ///
+    /// L'écriture passe par un fichier temporaire renommé : une requête
+    /// concurrente ne lit jamais une entrée à moitié écrite.
+    pub fn store(&self, key: &str, model: &str, completion: &Completion) -> Result<(), CacheError> {
+        if self.mode != CacheMode::ReadWrite {
+            return Ok(());
+        }
+        let io_error = |e: std::io::Error| CacheError::Io(format!("{}: {}", self.dir.display(), e));
+        std::fs::create_dir_all(&self.dir).map_err(io_error)?;
+        let entry = CacheEntry {
+            key: key.to_string(),
+            model: model.to_string(),
+            cached_at: Utc::now(),
+            completion: completion.clone(),
+        };
+        let json =
+            serde_json::to_string_pretty(&entry).map_err(|e| CacheError::Io(e.to_string()))?;
+        let tmp = self
+            .dir
+            .join(format!("{}.{}.tmp", key, uuid::Uuid::new_v4().simple()));
+        std::fs::write(&tmp, json).map_err(io_error)?;
+        std::fs::rename(&tmp, self.path(key)).map_err(io_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::probe::injector::EndpointConfig;
+
+    fn temp_cache(mode: CacheMode) -> CacheConfig {
+        CacheConfig {
+            mode,
+            dir: std::env::temp_dir().join(format!("ldsi-cache-{}", uuid::Uuid::new_v4())),
+        }
+    }
+
+    #[test]
+    fn test_key_covers_request() {
+        let config = LlmConfig::default();
+        let conversation = Conversation::user("Qu'est-ce que la liberté ?");
+        let key = CacheConfig::key(&config, &conversation);
+        assert_eq!(key.len(), 32);
+        assert_eq!(key, CacheConfig::key(&config.clone(), &conversation));
+
+        let seeded = LlmConfig {
+            sampling: SamplingParams {
+                seed: Some(42),
+                ..Default::default()
+            },
+            ..config.clone()
+        };
+        let variants = [
+            CacheConfig::key(&seeded, &conversation),
+            CacheConfig::key(
+                &LlmConfig {
+                    temperature: 0.0,
+                    ..config.clone()
+                },
+                &conversation,
+            ),
+            CacheConfig::key(
+                &LlmConfig {
+                    api_type: ApiType::OpenAI,
+                    ..config.clone()
+                },
+                &conversation,
+            ),
+            CacheConfig::key(&config, &conversation.clone().with_system("Poète.")),
+            CacheConfig::key(
+                &LlmConfig {
+                    base_url: "http://gpu-2:11434".to_string(),
+                    ..config.clone()
+                },
+                &conversation,
+            ),
+            CacheConfig::key(
+                &LlmConfig {
+                    endpoint: EndpointConfig {
+                        path: Some("/v2/chat".to_string()),
+                        ..Default::default()
+                    },
+                    ..config.clone()
+                },
+                &conversation,
+            ),
+            CacheConfig::key(
+                &LlmConfig {
+                    endpoint: EndpointConfig {
+                        query: BTreeMap::from([(
+                            "api-version".to_string(),
+                            "2024-06-01".to_string(),
+                        )]),
+                        ..Default::default()
+                    },
+                    ..config.clone()
+                },
+                &conversation,
+            ),
+        ];
+        for variant in &variants {
+            assert_ne!(variant, &key);
+        }
+        // Le streaming et la clé API ne changent pas la réponse attendue
+        let streamed = LlmConfig {
+            stream: true,
+            api_key: Some("sk-test".to_string()),
+            ..config
+        };
+        assert_eq!(CacheConfig::key(&streamed, &conversation), key);
+    }
+
+    #[test]
+    fn test_modes() {
+        let completion = Completion {
+            content: "Une ruine qui respire.".to_string(),
+            latency_ms: 812.0,
+            retry: RetryStats {
+                attempts: 2,
+                backoff_ms: vec![500],
+            },
+            ..Default::default()
+        };
+
+        let read_only = temp_cache(CacheMode::ReadOnly);
+        read_only.store("k", "llama3", &completion).unwrap();
+        assert_eq!(read_only.lookup("k"), Ok(None));
+
+        let read_write = CacheConfig {
+            mode: CacheMode::ReadWrite,
+            ..read_only.clone()
+        };
+        read_write.store("k", "llama3", &completion).unwrap();
+        let hit = read_write.lookup("k").unwrap().unwrap();
+        assert_eq!(hit.content, completion.content);
+        assert_eq!(hit.latency_ms, 812.0);
+        assert_eq!(hit.retry.attempts, 0, "Réponse rejouée, pas de réseau");
+        assert_eq!(read_only.lookup("k").unwrap().unwrap().content, hit.content);
+
+        let replay = CacheConfig {
+            mode: CacheMode::ReplayOnly,
+            ..read_only.clone()
+        };
+        assert!(replay.lookup("k").unwrap().is_some());
+        assert_eq!(
+            replay.lookup("absente"),
+            Err(CacheError::Miss("absente".to_string()))
+        );
+        assert_eq!(
+            CacheConfig {
+                mode: CacheMode::Off,
+                ..read_only.clone()
+            }
+            .lookup("k"),
+            Ok(None)
+        );
+
+        std::fs::write(read_only.dir.join("abime.json"), "{").unwrap();
+        assert!(matches!(
+            read_only.lookup("abime"),
+            Err(CacheError::Corrupt(_))
+        ));
+        std::fs::remove_dir_all(&read_only.dir).unwrap();
+    }
+}
@@ -7,8 +7,13 @@
 //! LDSI - Lyapunov-Dabert Stability Index
 
 use regex::Regex;
-use std::collections::{HashMap, HashSet};
-use unicode_normalization::UnicodeNormalization;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+use super::language::detect_language;
+use super::markup::MarkupConfig;
+use super::numerals::NumeralConfig;
+use super::pipeline::CleaningPipeline;
 
 /// Stop-words français (mots vides à filtrer)
 const FRENCH_STOPWORDS: &[&str] = &[
@@ -148,8 +153,75 @@ const ENGLISH_STOPWORDS: &[&str] = &[
     "any",
 ];
 
+/// Stop-words espagnols
+const SPANISH_STOPWORDS: &[&str] = &[
+    "el", "la", "los", "las", "un", "una", "unos", "unas", "lo", "al", "del", "de", "y", "e", "o",
+    "u", "pero", "sino", "ni", "que", "quien", "cual", "cuyo", "donde", "cuando", "como", "yo",
+    "tú", "él", "ella", "ello", "nosotros", "vosotros", "ellos", "ellas", "usted", "ustedes", "me",
+    "te", "se", "nos", "os", "le", "les", "mi", "mis", "tu", "tus", "su", "sus", "nuestro",
+    "nuestra", "este", "esta", "estos", "estas", "ese", "esa", "esos", "esas", "aquel", "en",
+    "con", "sin", "sobre", "entre", "para", "por", "desde", "hasta", "hacia", "contra", "bajo",
+    "ser", "es", "son", "era", "fue", "sido", "estar", "está", "están", "estaba", "haber", "ha",
+    "han", "había", "hay", "hacer", "hace", "puede", "pueden", "no", "sí", "más", "menos", "muy",
+    "ya", "también", "todo", "todos", "toda", "todas", "otro", "otra", "otros", "mismo", "así",
+    "porque", "si", "cada",
+];
+
+/// Stop-words allemands
+const GERMAN_STOPWORDS: &[&str] = &[
+    "der", "die", "das", "den", "dem", "des", "ein", "eine", "einen", "einem", "einer", "eines",
+    "und", "oder", "aber", "doch", "sondern", "denn", "dass", "ob", "wenn", "als", "wie", "weil",
+    "ich", "du", "er", "sie", "es", "wir", "ihr", "mich", "dich", "sich", "uns", "euch", "mir",
+    "dir", "ihm", "ihn", "ihnen", "mein", "meine", "dein", "sein", "seine", "ihre", "unser",
+    "dieser", "diese", "dieses", "jener", "welche", "welcher", "in", "im", "an", "am", "auf",
+    "aus", "bei", "mit", "nach", "von", "vom", "zu", "zum", "zur", "für", "über", "unter", "um",
+    "durch", "gegen", "ohne", "vor", "zwischen", "ist", "sind", "war", "waren", "bin", "bist",
+    "seid", "sein", "hat", "haben", "hatte", "wird", "werden", "wurde", "kann", "können", "muss",
+    "nicht", "kein", "keine", "auch", "noch", "nur", "schon", "sehr", "so", "alle", "alles", "man",
+    "hier", "dort", "dann", "wieder",
+];
+
+/// Stop-words italiens
+const ITALIAN_STOPWORDS: &[&str] = &[
+    "il", "lo", "la", "i", "gli", "le", "un", "uno", "una", "di", "del", "dello", "della", "dei",
+    "degli", "delle", "a", "al", "allo", "alla", "ai", "agli", "alle", "da", "dal", "dalla", "in",
+    "nel", "nella", "nei", "con", "su", "sul", "sulla", "per", "tra", "fra", "e", "ed", "o", "ma",
+    "però", "che", "chi", "cui", "quale", "dove", "quando", "come", "se", "perché", "io", "tu",
+    "lui", "lei", "noi", "voi", "loro", "mi", "ti", "si", "ci", "vi", "ne", "mio", "mia", "tuo",
+    "suo", "sua", "nostro", "questo", "questa", "questi", "quello", "quella", "essere", "è",
+    "sono", "era", "erano", "stato", "avere", "ha", "hanno", "aveva", "fare", "fa", "può", "non",
+    "più", "meno", "molto", "anche", "già", "ancora", "tutto", "tutti", "altro", "altri", "così",
+    "solo", "ogni",
+];
+
+/// Stop-words portugais
+const PORTUGUESE_STOPWORDS: &[&str] = &[
+    "o", "a", "os", "as", "um", "uma", "uns", "umas", "de", "do", "da", "dos", "das", "em", "no",
+    "na", "nos", "nas", "ao", "aos", "à", "às", "por", "pelo", "pela", "para", "com", "sem",
+    "sobre", "entre", "até", "desde", "e", "ou", "mas", "nem", "que", "quem", "qual", "onde",
+    "quando", "como", "se", "porque", "eu", "tu", "ele", "ela", "nós", "vós", "eles", "elas",
+    "você", "vocês", "me", "te", "lhe", "lhes", "meu", "minha", "seu", "sua", "seus", "suas",
+    "nosso", "nossa", "este", "esta", "estes", "esse", "essa", "isso", "isto", "aquele", "ser",
+    "é", "são", "era", "foi", "sido", "estar", "está", "estão", "ter", "tem", "têm", "tinha", "há",
+    "fazer", "faz", "pode", "não", "sim", "mais", "menos", "muito", "também", "já", "ainda",
+    "todo", "toda", "todos", "outro", "outra", "mesmo", "assim", "cada",
+];
+
+/// Stop-words néerlandais
+const DUTCH_STOPWORDS: &[&str] = &[
+    "de", "het", "een", "en", "of", "maar", "want", "dus", "dat", "die", "dit", "deze", "wie",
+    "wat", "waar", "wanneer", "hoe", "als", "omdat", "ik", "jij", "je", "hij", "zij", "ze", "wij",
+    "we", "jullie", "u", "mij", "me", "hem", "haar", "ons", "hun", "hen", "mijn", "jouw", "zijn",
+    "onze", "in", "op", "aan", "bij", "met", "van", "voor", "naar", "uit", "over", "onder", "door",
+    "tegen", "zonder", "tussen", "om", "tot", "is", "was", "waren", "ben", "bent", "heb", "hebt",
+    "heeft", "hebben", "had", "wordt", "worden", "werd", "kan", "kunnen", "moet", "zal", "zou",
+    "niet", "geen", "ook", "nog", "al", "alle", "alles", "er", "hier", "daar", "dan", "toen", "zo",
+    "zeer", "veel", "meer", "wel", "men",
+];
+
 /// Configuration du nettoyeur
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CleanerConfig {
     /// Supprimer les stop-words
     pub remove_stopwords: bool,
@@ -169,14 +241,60 @@ pub struct CleanerConfig {
     pub dynamic_stopwords: bool,
     /// Seuil de fréquence pour la détection dynamique (ratio vs total tokens)
     pub dynamic_stopwords_threshold: f64,
+    /// Supprimer les traces de raisonnement en ligne (<think>…</think>)
+    pub strip_reasoning: bool,
+    /// Racinisation Snowball (français/anglais, sans effet pour les autres langues)
+    pub stem: bool,
+    /// Prétraitement markdown/code/LaTeX/HTML (None = texte brut)
+    pub markup: Option<MarkupConfig>,
+    /// Retirer refus, avertissements d'IA, mises en garde et formules de clôture
+    pub strip_boilerplate: bool,
+    /// Normalisation des nombres, unités, dates et ordinaux (None = inchangés)
+    ///
+    /// Avec `remove_numbers`, la forme `Words` évite que les nombres normalisés
+    /// soient aussitôt supprimés.
+    pub numerals: Option<NumeralConfig>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub enum Language {
     French,
     English,
+    /// Français + anglais (défaut historique)
     Both,
+    Spanish,
+    German,
+    Italian,
+    Portuguese,
+    Dutch,
+    /// Langue détectée pour chaque texte (profil de trigrammes),
+    /// français + anglais si la détection échoue
+    Auto,
+}
+
+impl Language {
+    /// Stop-words de la langue (`Auto` doit être résolu au préalable)
+    pub(crate) fn stopwords(&self) -> Vec<&'static str> {
+        match self {
+            Language::French => FRENCH_STOPWORDS.to_vec(),
+            Language::English => ENGLISH_STOPWORDS.to_vec(),
+            Language::Both | Language::Auto => [FRENCH_STOPWORDS, ENGLISH_STOPWORDS].concat(),
+            Language::Spanish => SPANISH_STOPWORDS.to_vec(),
+            Language::German => GERMAN_STOPWORDS.to_vec(),
+            Language::Italian => ITALIAN_STOPWORDS.to_vec(),
+            Language::Portuguese => PORTUGUESE_STOPWORDS.to_vec(),
+            Language::Dutch => DUTCH_STOPWORDS.to_vec(),
+        }
+    }
+
+    /// Langue effective pour un texte : détection si `Auto`
+    pub fn resolve(&self, text: &str) -> Language {
+        match self {
+            Language::Auto => detect_language(text).unwrap_or(Language::Both),
+            other => *other,
+        }
+    }
 }
 
 impl Default for CleanerConfig {
@@ -191,6 +309,11 @@ impl Default for CleanerConfig {
             min_word_length: 2,
             dynamic_stopwords: false,
             dynamic_stopwords_threshold: 0.01,
+            strip_reasoning: true,
+            stem: false,
+            markup: None,
+            strip_boilerplate: false,
+            numerals: None,
         }
     }
 }
@@ -204,89 +327,89 @@ impl Default for CleanerConfig {
 /// # Returns
 /// Texte nettoyé prêt pour l'analyse
 pub fn clean_text(text: &str, config: &CleanerConfig) -> String {
-    let mut result = text.to_string();
+    CleaningPipeline::from_config(config).clean(text)
+}
 
-    // 1. Normalisation Unicode
-    if config.normalize_unicode {
-        result = result.nfd().collect::<String>().nfc().collect();
-    }
+/// Sépare la trace de raisonnement en ligne de la réponse finale
+///
+/// Reconnaît `<think>`, `<thinking>` et `<reasoning>` (casse ignorée), ainsi
+/// que deux formes tronquées : balise fermante seule (le gabarit de DeepSeek R1
+/// ouvre `<think>` dans le prompt) et balise ouvrante jamais refermée
+/// (réponse coupée par `max_tokens`).
+///
+/// Les formes tronquées ne sont retenues que si la trace ouvre la réponse :
+/// balise ouvrante en tête (aux blancs près), balise fermante sans autre
+/// balise, hors span de code et placée en fin de phrase ou de ligne. Une
+/// réponse qui cite `<think>` ou `</think>` reste intacte.
+///
+/// # Returns
+/// (réponse finale, trace de raisonnement si présente)
+pub fn split_reasoning(text: &str) -> (String, Option<String>) {
+    const TAGS: &str = "think|thinking|reasoning";
+    static PATTERNS: OnceLock<[Regex; 3]> = OnceLock::new();
+    let [paired, orphan_close, unclosed_open] = PATTERNS.get_or_init(|| {
+        [
+            Regex::new(
+                r"(?is)<think>(.*?)</think>|<thinking>(.*?)</thinking>|<reasoning>(.*?)</reasoning>",
+            )
+            .unwrap(),
+            Regex::new(&format!(r"(?is)^(.*?)</(?:{})>", TAGS)).unwrap(),
+            Regex::new(&format!(r"(?is)\A\s*<(?:{})>(.*)$", TAGS)).unwrap(),
+        ]
+    });
 
-    // 2. Minuscules
-    if config.lowercase {
-        result = result.to_lowercase();
-    }
+    let mut traces: Vec<String> = Vec::new();
+    let mut push = |trace: &str| {
+        let trace = trace.trim();
+        if !trace.is_empty() {
+            traces.push(trace.to_string());
+        }
+    };
 
-    // 3. Suppression des nombres
-    if config.remove_numbers {
-        let re = Regex::new(r"\d+").unwrap();
-        result = re.replace_all(&result, " ").to_string();
+    let mut answer = paired
+        .replace_all(text, |caps: &regex::Captures| {
+            let trace = caps
+                .iter()
+                .skip(1)
+                .flatten()
+                .next()
+                .map_or("", |m| m.as_str());
+            push(trace);
+            " "
+        })
+        .to_string();
+    if !paired.is_match(text)
+        && let Some(caps) = orphan_close.captures(&answer)
+        && !in_code_span(&caps[1])
+        && ends_trace(&caps[1])
+    {
+        push(&caps[1]);
+        answer = answer[caps.get(0).unwrap().end()..].to_string();
     }
-
-    // 4. Suppression de la ponctuation (garde les espaces)
-    if config.remove_punctuation {
-        result = result
-            .chars()
-            .map(|c| {
-                if c.is_alphabetic() || c.is_whitespace() {
-                    c
-                } else {
-                    ' '
-                }
-            })
-            .collect();
+    if let Some(caps) = unclosed_open.captures(&answer) {
+        push(&caps[1]);
+        answer.truncate(caps.get(0).unwrap().start());
     }
 
-    // 5. Construction du set de stop-words
-    let stopwords: HashSet<&str> = match config.language {
-        Language::French => FRENCH_STOPWORDS.iter().copied().collect(),
-        Language::English => ENGLISH_STOPWORDS.iter().copied().collect(),
-        Language::Both => FRENCH_STOPWORDS
-            .iter()
-            .chain(ENGLISH_STOPWORDS.iter())
-            .copied()
-            .collect(),
-    };
-
-    // 5b. Détection dynamique des stopwords (loi de Zipf)
-    let dynamic_stops: HashSet<String> = if config.dynamic_stopwords {
-        let all_words: Vec<&str> = result
-            .split_whitespace()
-            .filter(|w| w.len() >= config.min_word_length)
-            .collect();
-        let total = all_words.len();
-        if total > 0 {
-            let mut freq: HashMap<&str, usize> = HashMap::new();
-            for w in &all_words {
-                *freq.entry(w).or_insert(0) += 1;
-            }
-            let min_count =
-                ((total as f64 * config.dynamic_stopwords_threshold).ceil() as usize).max(3);
-            freq.into_iter()
-                .filter(|(_, count)| *count >= min_count)
-                .map(|(word, _)| word.to_string())
-                .collect()
-        } else {
-            HashSet::new()
-        }
-    } else {
-        HashSet::new()
-    };
+    let reasoning = (!traces.is_empty()).then(|| traces.join("\n\n"));
+    (answer.trim().to_string(), reasoning)
+}
 
-    // 6. Filtrage des mots
-    let words: Vec<&str> = result
-        .split_whitespace()
-        .filter(|word| {
-            let long_enough = word.len() >= config.min_word_length;
-            let not_static = !config.remove_stopwords || !stopwords.contains(word);
-            let not_dynamic = !config.dynamic_stopwords || !dynamic_stops.contains(*word);
-            long_enough && not_static && not_dynamic
-        })
-        .collect();
+/// Le texte qui précède ouvre-t-il un span de code markdown ?
+/// (nombre impair d'accents graves : `…` et ``` … ``` vont par paires)
+fn in_code_span(prefix: &str) -> bool {
+    prefix.matches('`').count() % 2 == 1
+}
 
-    words.join(" ")
+/// La balise fermante termine-t-elle une trace plutôt qu'un fragment de
+/// phrase ? (réponse vide avant elle, ou fin de ligne / de phrase)
+fn ends_trace(prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches([' ', '\t']);
+    prefix.is_empty() || prefix.ends_with(['\n', '.', '!', '?', ':', '…'])
 }
 
 /// Nettoie avec la configuration par défaut
+#[allow(dead_code)]
 pub fn clean_default(text: &str) -> String {
     clean_text(text, &CleanerConfig::default())
 }
@@ -339,6 +462,122 @@ mod tests {
         assert!(cleaned.contains("mange"));
     }
 
+    #[test]
+    fn test_auto_language() {
+        let config = CleanerConfig {
+            language: Language::Auto,
+            ..Default::default()
+        };
+
+        let spanish = "El gato duerme en el sofá mientras nosotros preparamos la cena";
+        let cleaned = clean_text(spanish, &config);
+        assert!(
+            !cleaned
+                .split(' ')
+                .any(|w| w == "el" || w == "en" || w == "nosotros")
+        );
+        assert!(cleaned.contains("gato"));
+        assert!(cleaned.contains("duerme"));
+
+        let german = "Die Katze schläft auf dem Sofa während wir das Abendessen kochen";
+        let cleaned = clean_text(german, &config);
+        assert!(
+            !cleaned
+                .split(' ')
+                .any(|w| w == "die" || w == "dem" || w == "wir")
+        );
+        assert!(cleaned.contains("katze"));
+
+        // Texte trop court : repli sur FR+EN
+        assert_eq!(Language::Auto.resolve("ok"), Language::Both);
+    }
+
+    #[test]
+    fn test_markup_preprocessing() {
+        let text = "## Réponse\n\nLe serveur démarre.\n\n```bash\ncargo run --release\n```";
+        let raw = clean_default(text);
+        assert!(raw.contains("cargo") && raw.contains("release"));
+
+        let config = CleanerConfig {
+            markup: Some(MarkupConfig::default()),
+            ..Default::default()
+        };
+        assert_eq!(clean_text(text, &config), "réponse serveur démarre");
+    }
+
+    #[test]
+    fn test_split_reasoning() {
+        let (answer, reasoning) =
+            split_reasoning("<think>\nL'utilisateur demande...\n</think>\n\nParis.");
+        assert_eq!(answer, "Paris.");
+        assert_eq!(reasoning.as_deref(), Some("L'utilisateur demande..."));
+
+        // Gabarit R1 : balise ouvrante dans le prompt
+        let (answer, reasoning) = split_reasoning("Je calcule 2+2.</think>4");
+        assert_eq!(answer, "4");
+        assert_eq!(reasoning.as_deref(), Some("Je calcule 2+2."));
+
+        // Coupé par max_tokens
+        let (answer, reasoning) = split_reasoning("\n<THINKING>hypothèse");
+        assert_eq!(answer, "");
+        assert_eq!(reasoning.as_deref(), Some("hypothèse"));
+
+        // Balise citée dans la réponse : pas une trace
+        for quoted in [
+            "Fermez la trace avec `</think>` puis répondez.",
+            "Exemple :\n```xml\n</think>\n```",
+            "Pour ouvrir la trace, utilisez `<think>` puis fermez-la.",
+            "Use the </think> tag to close the block.",
+            "Intro. <THINKING>hypothèse",
+        ] {
+            let (answer, reasoning) = split_reasoning(quoted);
+            assert_eq!(answer, quoted);
+            assert_eq!(reasoning, None);
+        }
+        let (answer, reasoning) = split_reasoning("<think>ok</think>La balise </think> ferme.");
+        assert_eq!(answer, "La balise </think> ferme.");
+        assert_eq!(reasoning.as_deref(), Some("ok"));
+
+        let (answer, reasoning) = split_reasoning("Pas de trace.");
+        assert_eq!(answer, "Pas de trace.");
+        assert_eq!(reasoning, None);
+
+        let cleaned = clean_default("<think>raisonnement caché</think> chat noir");
+        assert_eq!(cleaned, "chat noir");
+    }
+
+    #[test]
+    fn test_stemming() {
+        let config = CleanerConfig {
+            language: Language::French,
+            stem: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            clean_text("Le chat transcende les chats", &config),
+            "chat transcend chat"
+        );
+        assert_eq!(clean_text("Les chats noirs", &config), "chat noir");
+
+        let english = CleanerConfig {
+            language: Language::English,
+            stem: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            clean_text("Running cats transcends", &english),
+            "run cat transcend"
+        );
+
+        // Sans racinisateur pour la langue : mots inchangés
+        let spanish = CleanerConfig {
+            language: Language::Spanish,
+            stem: true,
+            ..Default::default()
+        };
+        assert_eq!(clean_text("gatos negros", &spanish), "gatos negros");
+    }
+
     #[test]
     fn test_min_word_length() {
         let text = "a ab abc abcd abcde";
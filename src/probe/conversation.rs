@@ -0,0 +1,257 @@
+//! Module Conversation - Prompt système et échanges multi-tours
+//!
+//! Le prompting "fracturé" passe souvent par le prompt système ou par des
+//! tours précédents plutôt que par le seul message utilisateur. Une
+//! conversation se construit en code ou depuis un fichier JSON/TOML :
+//!
+//! ```toml
+//! system = "Tu es un poète du chaos."
+//!
+//! [[messages]]
+//! role = "user"
+//! content = "Qu'est-ce que la liberté ?"
+//!
+//! [[messages]]
+//! role = "assistant"
+//! content = "Une ruine qui respire."
+//!
+//! [[messages]]
+//! role = "user"
+//! content = "Développe."
+//! ```
+//!
+//! Auteur: Julien DABERT
+//! LDSI - Lyapunov-Dabert Stability Index
+
+use serde::{Deserialize, Serialize};
+
+/// Auteur d'un message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    System,
+    User,
+    Assistant,
+}
+
+impl Role {
+    /// Nom du rôle côté API (OpenAI, Ollama, Anthropic)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::System => "system",
+            Role::User => "user",
+            Role::Assistant => "assistant",
+        }
+    }
+}
+
+/// Message d'une conversation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Message {
+    pub role: Role,
+    pub content: String,
+}
+
+/// Prompt système et suite de messages envoyés au modèle
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Conversation {
+    /// Prompt système (champ `system` d'Anthropic, premier message ailleurs)
+    #[serde(default)]
+    pub system: Option<String>,
+    /// Messages dans l'ordre d'envoi
+    #[serde(default)]
+    pub messages: Vec<Message>,
+}
+
+/// Erreur de chargement d'une conversation
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConversationError {
+    /// Fichier JSON ou TOML invalide
+    Parse(String),
+    /// Aucun message utilisateur ou assistant à envoyer
+    Empty,
+}
+
+impl std::fmt::Display for ConversationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConversationError::Parse(e) => write!(f, "Parse error: {}", e),
+            ConversationError::Empty => write!(f, "Conversation sans message"),
+        }
+    }
+}
+
+impl std::error::Error for ConversationError {}
+
+impl Conversation {
+    /// Conversation réduite à un message utilisateur
+    pub fn user(prompt: &str) -> Self {
+        Self::default().with_message(Role::User, prompt)
+    }
+
+    /// Remplace le prompt système
+    pub fn with_system(mut self, system: &str) -> Self {
+        self.system = Some(system.to_string());
+        self
+    }
+
+    /// Ajoute un message en fin de conversation
+    pub fn with_message(mut self, role: Role, content: &str) -> Self {
+        self.messages.push(Message {
+            role,
+            content: content.to_string(),
+        });
+        self
+    }
+
+    /// Charge une conversation JSON (`{"system": ..., "messages": [...]}` ou
+    /// tableau de messages au format OpenAI)
+    pub fn from_json(source: &str) -> Result<Self, ConversationError> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum JsonFile {
+            Messages(Vec<Message>),
+            Conversation(Conversation),
+        }
+        let conversation = match serde_json::from_str(source)
+            .map_err(|e| ConversationError::Parse(e.to_string()))?
+        {
+            JsonFile::Messages(messages) => Conversation {
+                system: None,
+                messages,
+            },
+            JsonFile::Conversation(conversation) => conversation,
+        };
+        conversation.validated()
+    }
+
+    /// Charge une conversation TOML (`system` et liste `[[messages]]`)
+    pub fn from_toml(source: &str) -> Result<Self, ConversationError> {
+        let conversation: Conversation =
+            toml::from_str(source).map_err(|e| ConversationError::Parse(e.to_string()))?;
+        conversation.validated()
+    }
+
+    fn validated(self) -> Result<Self, ConversationError> {
+        if self.turns().next().is_none() {
+            return Err(ConversationError::Empty);
+        }
+        Ok(self)
+    }
+
+    /// Un seul message utilisateur, sans prompt système
+    pub fn is_single_prompt(&self) -> bool {
+        self.system.is_none() && matches!(self.messages.as_slice(), [m] if m.role == Role::User)
+    }
+
+    /// Prompt système effectif : champ `system` puis messages `system`
+    pub fn system_prompt(&self) -> Option<String> {
+        let parts: Vec<&str> = self
+            .system
+            .iter()
+            .map(String::as_str)
+            .chain(
+                self.messages
+                    .iter()
+                    .filter(|m| m.role == Role::System)
+                    .map(|m| m.content.as_str()),
+            )
+            .collect();
+        (!parts.is_empty()).then(|| parts.join("\n\n"))
+    }
+
+    /// Messages utilisateur et assistant, sans les messages système
+    pub fn turns(&self) -> impl Iterator<Item = &Message> {
+        self.messages.iter().filter(|m| m.role != Role::System)
+    }
+
+    /// Messages à envoyer aux API qui portent le système en premier message
+    pub fn with_system_first(&self) -> Vec<Message> {
+        let system = self.system.iter().map(|s| Message {
+            role: Role::System,
+            content: s.clone(),
+        });
+        system.chain(self.messages.iter().cloned()).collect()
+    }
+
+    /// Dernier message utilisateur (le prompt testé)
+    pub fn last_user(&self) -> Option<&str> {
+        self.messages
+            .iter()
+            .rev()
+            .find(|m| m.role == Role::User)
+            .map(|m| m.content.as_str())
+    }
+}
+
+impl From<&str> for Conversation {
+    fn from(prompt: &str) -> Self {
+        Self::user(prompt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_prompt() {
+        let conversation = Conversation::user("Bonjour");
+        assert!(conversation.is_single_prompt());
+        assert_eq!(conversation.last_user(), Some("Bonjour"));
+        assert_eq!(conversation.system_prompt(), None);
+        assert!(!conversation.with_system("Sois bref.").is_single_prompt());
+    }
+
+    #[test]
+    fn test_from_toml_and_json() {
+        let toml = r#"
+system = "Tu es un poète du chaos."
+
+[[messages]]
+role = "user"
+content = "Qu'est-ce que la liberté ?"
+
+[[messages]]
+role = "assistant"
+content = "Une ruine qui respire."
+
+[[messages]]
+role = "user"
+content = "Développe."
+"#;
+        let conversation = Conversation::from_toml(toml).unwrap();
+        assert_eq!(conversation.messages.len(), 3);
+        assert_eq!(conversation.last_user(), Some("Développe."));
+        assert_eq!(conversation.with_system_first()[0].role, Role::System);
+
+        // Tableau OpenAI : le message système reste en place
+        let json = r#"[
+            {"role": "system", "content": "Réponds en vers."},
+            {"role": "user", "content": "Le vent ?"}
+        ]"#;
+        let conversation = Conversation::from_json(json).unwrap();
+        assert_eq!(
+            conversation.system_prompt().as_deref(),
+            Some("Réponds en vers.")
+        );
+        assert_eq!(conversation.turns().count(), 1);
+
+        let json = r#"{"system": "A", "messages": [{"role": "system", "content": "B"},
+                      {"role": "user", "content": "?"}]}"#;
+        let conversation = Conversation::from_json(json).unwrap();
+        assert_eq!(conversation.system_prompt().as_deref(), Some("A\n\nB"));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Conversation::from_json(r#"{"system": "Seul"}"#),
+            Err(ConversationError::Empty)
+        );
+        assert!(matches!(
+            Conversation::from_toml("[[messages]]\nrole = \"robot\"\ncontent = \"?\""),
+            Err(ConversationError::Parse(_))
+        ));
+    }
+}
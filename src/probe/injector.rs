@@ -1,14 +1,23 @@
 //! Module Injector - Client API LLM
 //!
 //! Envoie les prompts aux modèles et récupère les réponses.
-//! Compatible OpenAI API, Ollama, OpenRouter, Anthropic.
+//! Compatible OpenAI API, Ollama, OpenRouter, Anthropic, Gemini, Mistral.
 //!
 //! Auteur: Julien DABERT
 //! LDSI - Lyapunov-Dabert Stability Index
 
-use reqwest::Client;
+use futures::StreamExt;
+use futures::stream::BoxStream;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use super::cache::{CacheConfig, CacheError};
+use super::cleaner::split_reasoning;
+use super::conversation::{Conversation, Role};
+use super::sampling::{EffectiveSampling, SamplingError, SamplingParams};
 
 /// Configuration de l'endpoint LLM
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,8 +34,23 @@ pub struct LlmConfig {
     pub temperature: f32,
     /// Nombre max de tokens de réponse
     pub max_tokens: u32,
+    /// Graine, top_p, top_k, séquences d'arrêt et pénalités
+    #[serde(default)]
+    pub sampling: SamplingParams,
     /// Type d'API
     pub api_type: ApiType,
+    /// Politique de nouvelle tentative (429, 5xx, timeouts)
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    /// Réception en streaming (profil de latence : TTFT, intervalles)
+    #[serde(default)]
+    pub stream: bool,
+    /// Chemin, en-têtes, authentification et paramètres de requête (format OpenAI)
+    #[serde(default)]
+    pub endpoint: EndpointConfig,
+    /// Cache disque des réponses (rejeu hors ligne)
+    #[serde(default)]
+    pub cache: CacheConfig,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -39,6 +63,162 @@ pub enum ApiType {
     Anthropic,
     /// OpenRouter (OpenAI-compatible, multi-model gateway)
     OpenRouter,
+    /// Passerelle OpenAI-compatible (vLLM, LiteLLM, déploiements Azure...)
+    OpenAICompatible,
+    /// Format Google Gemini (/v1beta/models/{model}:generateContent)
+    Gemini,
+    /// Format Mistral (/v1/chat/completions, graine `random_seed`)
+    Mistral,
+}
+
+/// Chemin par défaut des requêtes au format OpenAI
+const OPENAI_CHAT_PATH: &str = "/v1/chat/completions";
+
+/// Transmission de la clé API
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthScheme {
+    /// `Authorization: Bearer <clé>`
+    #[default]
+    Bearer,
+    /// Clé dans un en-tête dédié (ex: `api-key` chez Azure)
+    Header(String),
+    /// Aucune authentification
+    None,
+}
+
+/// Forme de l'endpoint pour les API au format OpenAI
+///
+/// Les passerelles auto-hébergées exposent souvent un chemin propre, des
+/// en-têtes supplémentaires ou un paramètre `api-version` :
+///
+/// ```toml
+/// path = "/openai/deployments/gpt-4o/chat/completions"
+/// auth = { header = "api-key" }
+/// query = { api-version = "2024-06-01" }
+/// headers = { X-Team = "ldsi" }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EndpointConfig {
+    /// Chemin ajouté à `base_url` (défaut : `/v1/chat/completions`)
+    pub path: Option<String>,
+    /// En-têtes ajoutés à chaque requête
+    pub headers: BTreeMap<String, String>,
+    /// Paramètres de requête (ex: `api-version`)
+    pub query: BTreeMap<String, String>,
+    pub auth: AuthScheme,
+}
+
+/// Politique de nouvelle tentative sur erreur transitoire
+///
+/// Le délai suit un backoff exponentiel (`base_delay_ms * 2^n`, plafonné à
+/// `max_delay_ms`) perturbé de ±`jitter`. Un en-tête `Retry-After` remplace
+/// le backoff calculé, dans la limite de `max_delay_ms`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Nombre total de tentatives, première incluse (1 = aucune reprise)
+    pub max_attempts: u32,
+    /// Délai de la première reprise en millisecondes
+    pub base_delay_ms: u64,
+    /// Délai maximal entre deux tentatives en millisecondes
+    pub max_delay_ms: u64,
+    /// Fraction aléatoire appliquée au délai (0.0 = aucune, 0.2 = ±20 %)
+    pub jitter: f64,
+    /// Codes HTTP à retenter
+    pub retryable_status: Vec<u16>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 500,
+            max_delay_ms: 30_000,
+            jitter: 0.2,
+            retryable_status: vec![408, 425, 429, 500, 502, 503, 504],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Politique sans reprise : la première erreur est renvoyée telle quelle
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Le code HTTP justifie-t-il une nouvelle tentative ?
+    pub fn is_retryable(&self, status: StatusCode) -> bool {
+        self.retryable_status.contains(&status.as_u16())
+    }
+
+    /// Délai avant la tentative suivant l'échec numéro `failed` (à partir de 1)
+    pub fn delay(&self, failed: u32, retry_after: Option<Duration>) -> Duration {
+        let max = Duration::from_millis(self.max_delay_ms);
+        if let Some(wait) = retry_after {
+            return wait.min(max);
+        }
+        let exponent = failed.saturating_sub(1).min(32);
+        let base = self.base_delay_ms.saturating_mul(1u64 << exponent) as f64;
+        let jitter = self.jitter.clamp(0.0, 1.0) * (2.0 * unit_random() - 1.0);
+        let millis = (base * (1.0 + jitter)).max(0.0) as u64;
+        Duration::from_millis(millis).min(max)
+    }
+}
+
+/// Tentatives effectuées pour obtenir une réponse
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryStats {
+    /// Nombre de requêtes envoyées (0 = réponse non issue du réseau)
+    pub attempts: u32,
+    /// Délais d'attente observés avant chaque reprise, en millisecondes
+    pub backoff_ms: Vec<u64>,
+}
+
+impl RetryStats {
+    /// Temps total passé à attendre entre les tentatives
+    pub fn total_backoff_ms(&self) -> u64 {
+        self.backoff_ms.iter().sum()
+    }
+}
+
+/// Tirage uniforme dans [0, 1) sans dépendance externe
+fn unit_random() -> f64 {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default(),
+    );
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Erreur de lecture du corps : un timeout reste un timeout, le reste est
+/// une réponse illisible
+fn body_error(e: reqwest::Error) -> InjectorError {
+    if e.is_timeout() {
+        InjectorError::Timeout
+    } else {
+        InjectorError::ParseError(e.to_string())
+    }
+}
+
+/// Lit un en-tête `Retry-After` : secondes entières ou date HTTP
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = date.with_timezone(&chrono::Utc) - chrono::Utc::now();
+    Some(wait.to_std().unwrap_or(Duration::ZERO))
 }
 
 impl Default for LlmConfig {
@@ -50,7 +230,12 @@ impl Default for LlmConfig {
             timeout_secs: 120,
             temperature: 0.7,
             max_tokens: 2048,
+            sampling: SamplingParams::default(),
             api_type: ApiType::Ollama,
+            retry: RetryPolicy::default(),
+            stream: false,
+            endpoint: EndpointConfig::default(),
+            cache: CacheConfig::default(),
         }
     }
 }
@@ -65,7 +250,12 @@ impl LlmConfig {
             timeout_secs: 120,
             temperature: 0.7,
             max_tokens: 2048,
+            sampling: SamplingParams::default(),
             api_type: ApiType::OpenRouter,
+            retry: RetryPolicy::default(),
+            stream: false,
+            endpoint: EndpointConfig::default(),
+            cache: CacheConfig::default(),
         }
     }
 
@@ -78,7 +268,12 @@ impl LlmConfig {
             timeout_secs: 120,
             temperature: 0.7,
             max_tokens: 2048,
+            sampling: SamplingParams::default(),
             api_type: ApiType::Ollama,
+            retry: RetryPolicy::default(),
+            stream: false,
+            endpoint: EndpointConfig::default(),
+            cache: CacheConfig::default(),
         }
     }
 
@@ -92,7 +287,12 @@ impl LlmConfig {
             timeout_secs: 120,
             temperature: 0.7,
             max_tokens: 2048,
+            sampling: SamplingParams::default(),
             api_type: ApiType::OpenAI,
+            retry: RetryPolicy::default(),
+            stream: false,
+            endpoint: EndpointConfig::default(),
+            cache: CacheConfig::default(),
         }
     }
 
@@ -106,7 +306,59 @@ impl LlmConfig {
             timeout_secs: 120,
             temperature: 0.7,
             max_tokens: 2048,
+            sampling: SamplingParams::default(),
             api_type: ApiType::Anthropic,
+            retry: RetryPolicy::default(),
+            stream: false,
+            endpoint: EndpointConfig::default(),
+            cache: CacheConfig::default(),
+        }
+    }
+
+    /// Configuration pour Google Gemini (API directe, sans passerelle)
+    pub fn gemini(model: &str, api_key: &str) -> Self {
+        Self {
+            base_url: "https://generativelanguage.googleapis.com".to_string(),
+            model: model.to_string(),
+            api_key: Some(api_key.to_string()),
+            timeout_secs: 120,
+            temperature: 0.7,
+            max_tokens: 2048,
+            sampling: SamplingParams::default(),
+            api_type: ApiType::Gemini,
+            retry: RetryPolicy::default(),
+            stream: false,
+            endpoint: EndpointConfig::default(),
+            cache: CacheConfig::default(),
+        }
+    }
+
+    /// Configuration pour Mistral (API directe, sans passerelle)
+    pub fn mistral(model: &str, api_key: &str) -> Self {
+        Self {
+            base_url: "https://api.mistral.ai".to_string(),
+            model: model.to_string(),
+            api_key: Some(api_key.to_string()),
+            timeout_secs: 120,
+            temperature: 0.7,
+            max_tokens: 2048,
+            sampling: SamplingParams::default(),
+            api_type: ApiType::Mistral,
+            retry: RetryPolicy::default(),
+            stream: false,
+            endpoint: EndpointConfig::default(),
+            cache: CacheConfig::default(),
+        }
+    }
+
+    /// Paramètres d'échantillonnage appliqués, pour l'audit
+    pub fn effective_sampling(&self) -> EffectiveSampling {
+        EffectiveSampling {
+            api_type: self.api_type,
+            model: self.model.clone(),
+            temperature: self.temperature,
+            max_tokens: self.max_tokens,
+            params: self.sampling.clone(),
         }
     }
 }
@@ -196,6 +448,51 @@ struct OpenAiRequest {
     messages: Vec<OpenAiMessage>,
     temperature: f32,
     max_tokens: u32,
+    #[serde(flatten)]
+    sampling: OpenAiSampling,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+    /// Demande l'usage dans le dernier fragment du flux
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<OpenAiStreamOptions>,
+}
+
+#[derive(Serialize)]
+struct OpenAiStreamOptions {
+    include_usage: bool,
+}
+
+/// Paramètres d'échantillonnage OpenAI (top_k et répétition : OpenRouter)
+#[derive(Serialize)]
+struct OpenAiSampling {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_k: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repetition_penalty: Option<f32>,
+}
+
+impl From<&SamplingParams> for OpenAiSampling {
+    fn from(params: &SamplingParams) -> Self {
+        Self {
+            seed: params.seed,
+            top_p: params.top_p,
+            top_k: params.top_k,
+            stop: params.stop.clone(),
+            frequency_penalty: params.frequency_penalty,
+            presence_penalty: params.presence_penalty,
+            repetition_penalty: params.repeat_penalty,
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -207,16 +504,78 @@ struct OpenAiMessage {
 #[derive(Deserialize)]
 struct OpenAiResponse {
     choices: Vec<OpenAiChoice>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    usage: Option<OpenAiUsage>,
 }
 
 #[derive(Deserialize)]
 struct OpenAiChoice {
     message: OpenAiMessageResponse,
+    #[serde(default)]
+    finish_reason: Option<String>,
 }
 
 #[derive(Deserialize)]
+struct OpenAiUsage {
+    #[serde(default)]
+    prompt_tokens: Option<u32>,
+    #[serde(default)]
+    completion_tokens: Option<u32>,
+}
+
+impl OpenAiUsage {
+    fn meta(
+        usage: Option<Self>,
+        finish_reason: Option<&str>,
+        model: Option<String>,
+    ) -> CompletionMeta {
+        let usage = usage.unwrap_or(OpenAiUsage {
+            prompt_tokens: None,
+            completion_tokens: None,
+        });
+        CompletionMeta {
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            finish_reason: finish_reason.map(FinishReason::from_provider),
+            model_id: model,
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
 struct OpenAiMessageResponse {
-    content: String,
+    /// Peut être null quand le modèle n'a produit que du raisonnement
+    #[serde(default)]
+    content: Option<String>,
+    /// Trace de raisonnement (OpenRouter)
+    #[serde(default)]
+    reasoning: Option<String>,
+    /// Trace de raisonnement (DeepSeek, vLLM)
+    #[serde(default)]
+    reasoning_content: Option<String>,
+}
+
+impl OpenAiMessageResponse {
+    fn into_response(self) -> Completion {
+        Completion::from_parts(
+            self.content.unwrap_or_default(),
+            self.reasoning.or(self.reasoning_content),
+        )
+    }
+}
+
+/// Messages au format OpenAI (aussi utilisé par Ollama `/api/chat`)
+fn openai_messages(conversation: &Conversation) -> Vec<OpenAiMessage> {
+    conversation
+        .with_system_first()
+        .into_iter()
+        .map(|m| OpenAiMessage {
+            role: m.role.as_str().to_string(),
+            content: m.content,
+        })
+        .collect()
 }
 
 // ============ Structures de requête/réponse Ollama ============
@@ -233,11 +592,67 @@ struct OllamaRequest {
 struct OllamaOptions {
     temperature: f32,
     num_predict: u32,
+    /// Noms identiques côté Ollama
+    #[serde(flatten)]
+    sampling: SamplingParams,
+}
+
+/// Requête `/api/chat` (prompt système ou conversation multi-tours)
+#[derive(Serialize)]
+struct OllamaChatRequest {
+    model: String,
+    messages: Vec<OpenAiMessage>,
+    stream: bool,
+    options: OllamaOptions,
 }
 
+/// Réponse `/api/generate` (champ `response`) ou `/api/chat` (champ `message`)
 #[derive(Deserialize)]
 struct OllamaResponse {
+    #[serde(default)]
     response: String,
+    /// Trace de raisonnement (modèles "thinking")
+    #[serde(default)]
+    thinking: Option<String>,
+    #[serde(default)]
+    message: Option<OllamaChatMessage>,
+    #[serde(default)]
+    model: Option<String>,
+    /// "stop" ou "length" (coupée à `num_predict`)
+    #[serde(default)]
+    done_reason: Option<String>,
+    #[serde(default)]
+    prompt_eval_count: Option<u32>,
+    #[serde(default)]
+    eval_count: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct OllamaChatMessage {
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    thinking: Option<String>,
+}
+
+impl OllamaResponse {
+    /// Compteurs de tokens, raison d'arrêt et modèle
+    fn meta(&self) -> CompletionMeta {
+        CompletionMeta {
+            prompt_tokens: self.prompt_eval_count,
+            completion_tokens: self.eval_count,
+            finish_reason: self.done_reason.as_deref().map(FinishReason::from_provider),
+            model_id: self.model.clone(),
+        }
+    }
+
+    /// Contenu et raisonnement, quel que soit l'endpoint
+    fn into_parts(self) -> (String, Option<String>) {
+        match self.message {
+            Some(message) => (message.content, message.thinking.or(self.thinking)),
+            None => (self.response, self.thinking),
+        }
+    }
 }
 
 // ============ Structures Anthropic ============
@@ -248,6 +663,17 @@ struct AnthropicRequest {
     messages: Vec<AnthropicMessage>,
     max_tokens: u32,
     temperature: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_k: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop_sequences: Vec<String>,
+    /// Prompt système (champ de premier niveau, pas un message)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
 }
 
 #[derive(Serialize)]
@@ -259,643 +685,3094 @@ struct AnthropicMessage {
 #[derive(Deserialize)]
 struct AnthropicResponse {
     content: Vec<AnthropicContent>,
+    #[serde(default)]
+    model: Option<String>,
+    /// "end_turn", "stop_sequence", "max_tokens"...
+    #[serde(default)]
+    stop_reason: Option<String>,
+    #[serde(default)]
+    usage: Option<AnthropicUsage>,
+}
+
+#[derive(Deserialize, Default)]
+struct AnthropicUsage {
+    #[serde(default)]
+    input_tokens: Option<u32>,
+    #[serde(default)]
+    output_tokens: Option<u32>,
+}
+
+impl AnthropicUsage {
+    fn meta(
+        usage: Option<Self>,
+        stop_reason: Option<&str>,
+        model: Option<String>,
+    ) -> CompletionMeta {
+        let usage = usage.unwrap_or_default();
+        CompletionMeta {
+            prompt_tokens: usage.input_tokens,
+            completion_tokens: usage.output_tokens,
+            finish_reason: stop_reason.map(FinishReason::from_provider),
+            model_id: model,
+        }
+    }
 }
 
+/// Bloc de contenu : "text" ou "thinking"
 #[derive(Deserialize)]
 struct AnthropicContent {
-    text: String,
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    thinking: Option<String>,
 }
 
-/// Erreur d'injection
-#[derive(Debug, Clone)]
-#[allow(dead_code)]
-pub enum InjectorError {
-    NetworkError(String),
-    ApiError(String),
-    ParseError(String),
-    Timeout,
+impl AnthropicResponse {
+    fn into_response(self) -> Option<Completion> {
+        if self.content.is_empty() {
+            return None;
+        }
+        let mut texts = Vec::new();
+        let mut thoughts = Vec::new();
+        for block in self.content {
+            texts.extend(block.text);
+            thoughts.extend(block.thinking);
+        }
+        let reasoning = (!thoughts.is_empty()).then(|| thoughts.join("\n\n"));
+        let meta = AnthropicUsage::meta(self.usage, self.stop_reason.as_deref(), self.model);
+        Some(Completion::from_parts(texts.join("\n\n"), reasoning).with_meta(meta))
+    }
 }
 
-impl std::fmt::Display for InjectorError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            InjectorError::NetworkError(e) => write!(f, "Network error: {}", e),
-            InjectorError::ApiError(e) => write!(f, "API error: {}", e),
-            InjectorError::ParseError(e) => write!(f, "Parse error: {}", e),
-            InjectorError::Timeout => write!(f, "Request timeout"),
+// ============ Structures Mistral ============
+
+/// Requête Mistral : format OpenAI strict (un champ inconnu est rejeté en
+/// 422), graine sous `random_seed`, usage toujours inclus dans le flux
+#[derive(Serialize)]
+struct MistralRequest {
+    model: String,
+    messages: Vec<OpenAiMessage>,
+    temperature: f32,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    random_seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    presence_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+}
+
+// ============ Structures Gemini ============
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiRequest {
+    contents: Vec<GeminiContent>,
+    /// Prompt système (champ de premier niveau, pas un message)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_instruction: Option<GeminiContent>,
+    generation_config: GeminiGenerationConfig,
+}
+
+#[derive(Serialize)]
+struct GeminiContent {
+    /// "user" ou "model" (absent pour `systemInstruction`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<&'static str>,
+    parts: Vec<GeminiPart>,
+}
+
+#[derive(Serialize)]
+struct GeminiPart {
+    text: String,
+}
+
+impl GeminiContent {
+    fn text(role: Option<&'static str>, text: &str) -> Self {
+        Self {
+            role,
+            parts: vec![GeminiPart {
+                text: text.to_string(),
+            }],
         }
     }
 }
 
-impl std::error::Error for InjectorError {}
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiGenerationConfig {
+    temperature: f32,
+    max_output_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_k: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop_sequences: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    frequency_penalty: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    presence_penalty: Option<f32>,
+}
 
-/// Client d'injection LLM
-pub struct Injector {
-    client: Client,
-    config: LlmConfig,
+/// Réponse `generateContent` (aussi chaque fragment de `streamGenerateContent`)
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiResponse {
+    #[serde(default)]
+    candidates: Vec<GeminiCandidate>,
+    /// Présent quand le prompt lui-même est bloqué
+    #[serde(default)]
+    prompt_feedback: Option<GeminiPromptFeedback>,
+    #[serde(default)]
+    usage_metadata: Option<GeminiUsage>,
+    #[serde(default)]
+    model_version: Option<String>,
+    /// Erreur transmise en cours de flux
+    #[serde(default)]
+    error: Option<serde_json::Value>,
 }
 
-impl Injector {
-    /// Crée un nouvel injecteur avec la configuration donnée
-    pub fn new(config: LlmConfig) -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(config.timeout_secs))
-            .build()
-            .expect("Failed to create HTTP client");
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiCandidate {
+    #[serde(default)]
+    content: Option<GeminiCandidateContent>,
+    /// "STOP", "MAX_TOKENS", "SAFETY", "RECITATION"...
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
 
-        Self { client, config }
+#[derive(Deserialize)]
+struct GeminiCandidateContent {
+    #[serde(default)]
+    parts: Vec<GeminiCandidatePart>,
+}
+
+/// Partie de réponse : texte final ou résumé de raisonnement (`thought`)
+#[derive(Deserialize)]
+struct GeminiCandidatePart {
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    thought: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiPromptFeedback {
+    #[serde(default)]
+    block_reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiUsage {
+    #[serde(default)]
+    prompt_token_count: Option<u32>,
+    #[serde(default)]
+    candidates_token_count: Option<u32>,
+    /// Tokens de raisonnement, comptés à part des candidats
+    #[serde(default)]
+    thoughts_token_count: Option<u32>,
+}
+
+impl GeminiResponse {
+    /// Erreur du flux ou prompt bloqué par le filtre de sécurité
+    fn check(&mut self) -> Result<(), InjectorError> {
+        if let Some(error) = self.error.take() {
+            return Err(InjectorError::ApiError(error.to_string()));
+        }
+        if let Some(reason) = self
+            .prompt_feedback
+            .take()
+            .and_then(|feedback| feedback.block_reason)
+        {
+            return Err(InjectorError::Blocked(format!("prompt ({})", reason)));
+        }
+        Ok(())
     }
 
-    /// Retourne la configuration actuelle
-    #[allow(dead_code)]
-    pub fn config(&self) -> &LlmConfig {
-        &self.config
+    /// Tokens, raison d'arrêt du premier candidat et version du modèle
+    fn meta(&self) -> CompletionMeta {
+        let usage = self.usage_metadata.as_ref();
+        let completion_tokens =
+            usage.and_then(
+                |u| match (u.candidates_token_count, u.thoughts_token_count) {
+                    (None, None) => None,
+                    (candidates, thoughts) => Some(candidates.unwrap_or(0) + thoughts.unwrap_or(0)),
+                },
+            );
+        CompletionMeta {
+            prompt_tokens: usage.and_then(|u| u.prompt_token_count),
+            completion_tokens,
+            finish_reason: self
+                .candidates
+                .first()
+                .and_then(|c| c.finish_reason.as_deref())
+                .map(FinishReason::from_provider),
+            model_id: self.model_version.clone(),
+        }
     }
 
-    /// Envoie un prompt et récupère la réponse
-    pub async fn inject(&self, prompt: &str) -> Result<String, InjectorError> {
-        match self.config.api_type {
-            ApiType::OpenAI => self.inject_openai(prompt).await,
-            ApiType::Ollama => self.inject_ollama(prompt).await,
-            ApiType::Anthropic => self.inject_anthropic(prompt).await,
-            ApiType::OpenRouter => self.inject_openrouter(prompt).await,
+    /// Texte et raisonnement du premier candidat (None sans candidat)
+    fn into_parts(self) -> Option<(String, Option<String>)> {
+        let candidate = self.candidates.into_iter().next()?;
+        let mut texts = Vec::new();
+        let mut thoughts = Vec::new();
+        for part in candidate.content.map(|c| c.parts).unwrap_or_default() {
+            match part.thought {
+                true => thoughts.extend(part.text),
+                false => texts.extend(part.text),
+            }
         }
+        Some((
+            texts.concat(),
+            (!thoughts.is_empty()).then(|| thoughts.concat()),
+        ))
     }
+}
 
-    async fn inject_openai(&self, prompt: &str) -> Result<String, InjectorError> {
-        let url = format!("{}/v1/chat/completions", self.config.base_url);
+// ============ Streaming (SSE OpenAI/Anthropic/Gemini, NDJSON Ollama) ============
 
-        let request = OpenAiRequest {
-            model: self.config.model.clone(),
-            messages: vec![OpenAiMessage {
-                role: "user".to_string(),
-                content: prompt.to_string(),
-            }],
-            temperature: self.config.temperature,
-            max_tokens: self.config.max_tokens,
-        };
+/// Fragment SSE OpenAI / OpenRouter
+#[derive(Deserialize)]
+struct OpenAiStreamChunk {
+    #[serde(default)]
+    choices: Vec<OpenAiStreamChoice>,
+    #[serde(default)]
+    model: Option<String>,
+    /// Dernier fragment seulement (`stream_options.include_usage`)
+    #[serde(default)]
+    usage: Option<OpenAiUsage>,
+    /// Erreur transmise en cours de flux (OpenRouter)
+    #[serde(default)]
+    error: Option<serde_json::Value>,
+}
 
-        let mut req_builder = self.client.post(&url).json(&request);
+#[derive(Deserialize)]
+struct OpenAiStreamChoice {
+    #[serde(default)]
+    delta: OpenAiMessageResponse,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
 
-        if let Some(ref api_key) = self.config.api_key {
-            req_builder = req_builder.header("Authorization", format!("Bearer {}", api_key));
-        }
+/// Événement SSE Anthropic (seuls les deltas, la fin et les erreurs comptent)
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum AnthropicStreamEvent {
+    /// Modèle et tokens du prompt
+    MessageStart {
+        message: AnthropicStreamMessage,
+    },
+    ContentBlockDelta {
+        delta: AnthropicContent,
+    },
+    /// Raison d'arrêt et tokens générés
+    MessageDelta {
+        delta: AnthropicStopDelta,
+        #[serde(default)]
+        usage: Option<AnthropicUsage>,
+    },
+    MessageStop,
+    Error {
+        error: serde_json::Value,
+    },
+    #[serde(other)]
+    Other,
+}
 
-        let response = req_builder
-            .send()
-            .await
-            .map_err(|e| InjectorError::NetworkError(e.to_string()))?;
+#[derive(Deserialize)]
+struct AnthropicStreamMessage {
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    usage: Option<AnthropicUsage>,
+}
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(InjectorError::ApiError(format!("{}: {}", status, body)));
-        }
+#[derive(Deserialize)]
+struct AnthropicStopDelta {
+    #[serde(default)]
+    stop_reason: Option<String>,
+}
 
-        let parsed: OpenAiResponse = response
-            .json()
-            .await
-            .map_err(|e| InjectorError::ParseError(e.to_string()))?;
+/// Ligne NDJSON Ollama
+#[derive(Deserialize)]
+struct OllamaStreamChunk {
+    #[serde(flatten)]
+    body: OllamaResponse,
+    #[serde(default)]
+    done: bool,
+    #[serde(default)]
+    error: Option<String>,
+}
 
-        parsed
-            .choices
-            .first()
-            .map(|c| c.message.content.clone())
-            .ok_or_else(|| InjectorError::ParseError("No response content".to_string()))
+/// Fragment de réponse reçu en streaming
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StreamDelta {
+    /// Fragment de la réponse finale
+    Content(String),
+    /// Fragment de la trace de raisonnement
+    Reasoning(String),
+    /// Tokens, raison d'arrêt ou modèle annoncés en cours de flux
+    Meta(CompletionMeta),
+}
+
+/// Profil de latence d'une réponse streamée, en millisecondes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StreamTiming {
+    /// Délai entre l'envoi et le premier fragment (None = réponse vide).
+    /// L'origine est la tentative ayant abouti : les reprises sont exclues.
+    pub ttft_ms: Option<f64>,
+    /// Délai entre l'envoi et la fin du flux
+    pub total_ms: f64,
+    /// Intervalles entre fragments successifs
+    pub inter_token_ms: Vec<f64>,
+}
+
+impl StreamTiming {
+    /// Nombre de fragments reçus
+    pub fn deltas(&self) -> usize {
+        self.ttft_ms.map_or(0, |_| self.inter_token_ms.len() + 1)
     }
 
-    async fn inject_ollama(&self, prompt: &str) -> Result<String, InjectorError> {
-        let url = format!("{}/api/generate", self.config.base_url);
+    /// Intervalle moyen entre fragments
+    pub fn mean_inter_token_ms(&self) -> Option<f64> {
+        (!self.inter_token_ms.is_empty())
+            .then(|| self.inter_token_ms.iter().sum::<f64>() / self.inter_token_ms.len() as f64)
+    }
 
-        let request = OllamaRequest {
-            model: self.config.model.clone(),
-            prompt: prompt.to_string(),
-            stream: false,
-            options: OllamaOptions {
-                temperature: self.config.temperature,
-                num_predict: self.config.max_tokens,
-            },
-        };
+    /// Plus long silence entre deux fragments
+    pub fn max_inter_token_ms(&self) -> Option<f64> {
+        self.inter_token_ms.iter().copied().reduce(f64::max)
+    }
+}
 
-        let response = self
-            .client
-            .post(&url)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| InjectorError::NetworkError(e.to_string()))?;
+/// Chronomètre des fragments d'une réponse streamée
+struct TimingRecorder {
+    start: Instant,
+    last: Option<Instant>,
+    timing: StreamTiming,
+}
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(InjectorError::ApiError(format!("{}: {}", status, body)));
+impl TimingRecorder {
+    fn new(start: Instant) -> Self {
+        Self {
+            start,
+            last: None,
+            timing: StreamTiming::default(),
         }
+    }
 
-        let parsed: OllamaResponse = response
-            .json()
-            .await
-            .map_err(|e| InjectorError::ParseError(e.to_string()))?;
+    /// Enregistre l'arrivée d'un fragment
+    fn tick(&mut self) {
+        let now = Instant::now();
+        match self.last {
+            None => self.timing.ttft_ms = Some(millis(now - self.start)),
+            Some(last) => self.timing.inter_token_ms.push(millis(now - last)),
+        }
+        self.last = Some(now);
+    }
 
-        Ok(parsed.response)
+    fn finish(mut self) -> StreamTiming {
+        self.timing.total_ms = millis(self.start.elapsed());
+        self.timing
     }
+}
 
-    async fn inject_anthropic(&self, prompt: &str) -> Result<String, InjectorError> {
-        let url = format!("{}/v1/messages", self.config.base_url);
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
 
-        let request = AnthropicRequest {
-            model: self.config.model.clone(),
-            messages: vec![AnthropicMessage {
-                role: "user".to_string(),
-                content: prompt.to_string(),
-            }],
-            max_tokens: self.config.max_tokens,
-            temperature: self.config.temperature,
-        };
+/// Flux de fragments d'une réponse et reprises nécessaires pour l'ouvrir
+pub struct LlmStream {
+    pub retry: RetryStats,
+    /// Envoi de la tentative ayant abouti (les délais de reprise sont exclus)
+    pub sent_at: Instant,
+    pub deltas: BoxStream<'static, Result<StreamDelta, InjectorError>>,
+}
 
-        let api_key = self
-            .config
-            .api_key
-            .as_ref()
-            .ok_or_else(|| InjectorError::ApiError("Anthropic requires API key".to_string()))?;
+/// Fragments extraits d'une ligne du flux
+#[derive(Debug, Default, PartialEq)]
+struct StreamLine {
+    deltas: Vec<StreamDelta>,
+    done: bool,
+}
 
-        let response = self
-            .client
-            .post(&url)
-            .header("x-api-key", api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| InjectorError::NetworkError(e.to_string()))?;
+impl StreamLine {
+    fn push_meta(&mut self, meta: CompletionMeta) {
+        if meta != CompletionMeta::default() {
+            self.deltas.push(StreamDelta::Meta(meta));
+        }
+    }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(InjectorError::ApiError(format!("{}: {}", status, body)));
+    fn push(&mut self, delta: fn(String) -> StreamDelta, text: Option<String>) {
+        if let Some(text) = text.filter(|t| !t.is_empty()) {
+            self.deltas.push(delta(text));
         }
+    }
+}
 
-        let parsed: AnthropicResponse = response
-            .json()
-            .await
-            .map_err(|e| InjectorError::ParseError(e.to_string()))?;
+/// Décode une ligne SSE (OpenAI, OpenRouter, Anthropic, Gemini, Mistral) ou
+/// NDJSON (Ollama)
+fn parse_stream_line(api: ApiType, line: &str) -> Result<StreamLine, InjectorError> {
+    let line = line.trim();
+    let parse_error = |e: serde_json::Error| InjectorError::ParseError(format!("{}: {}", e, line));
+    let mut out = StreamLine::default();
+    if line.is_empty() {
+        return Ok(out);
+    }
 
-        parsed
-            .content
-            .first()
-            .map(|c| c.text.clone())
-            .ok_or_else(|| InjectorError::ParseError("No response content".to_string()))
+    if api == ApiType::Ollama {
+        let chunk: OllamaStreamChunk = serde_json::from_str(line).map_err(parse_error)?;
+        if let Some(error) = chunk.error {
+            return Err(InjectorError::ApiError(error));
+        }
+        // Le modèle est répété à chaque ligne, les compteurs n'arrivent qu'à la fin
+        let meta = chunk.done.then(|| chunk.body.meta());
+        let (content, thinking) = chunk.body.into_parts();
+        out.push(StreamDelta::Reasoning, thinking);
+        out.push(StreamDelta::Content, Some(content));
+        out.push_meta(meta.unwrap_or_default());
+        out.done = chunk.done;
+        return Ok(out);
     }
 
-    /// Injection via OpenRouter (OpenAI-compatible avec headers spécifiques)
-    async fn inject_openrouter(&self, prompt: &str) -> Result<String, InjectorError> {
-        let url = format!("{}/v1/chat/completions", self.config.base_url);
+    // SSE : seules les lignes `data:` portent du contenu (commentaires et
+    // lignes `event:` ignorés)
+    let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+        return Ok(out);
+    };
+    if data == "[DONE]" {
+        out.done = true;
+        return Ok(out);
+    }
 
-        let request = OpenAiRequest {
-            model: self.config.model.clone(),
-            messages: vec![OpenAiMessage {
-                role: "user".to_string(),
-                content: prompt.to_string(),
-            }],
-            temperature: self.config.temperature,
-            max_tokens: self.config.max_tokens,
-        };
+    if api == ApiType::Anthropic {
+        match serde_json::from_str(data).map_err(parse_error)? {
+            AnthropicStreamEvent::MessageStart { message } => {
+                out.push_meta(AnthropicUsage::meta(message.usage, None, message.model));
+            }
+            AnthropicStreamEvent::ContentBlockDelta { delta } => {
+                out.push(StreamDelta::Reasoning, delta.thinking);
+                out.push(StreamDelta::Content, delta.text);
+            }
+            AnthropicStreamEvent::MessageDelta { delta, usage } => {
+                out.push_meta(AnthropicUsage::meta(
+                    usage,
+                    delta.stop_reason.as_deref(),
+                    None,
+                ));
+            }
+            AnthropicStreamEvent::MessageStop => out.done = true,
+            AnthropicStreamEvent::Error { error } => {
+                return Err(InjectorError::ApiError(error.to_string()));
+            }
+            AnthropicStreamEvent::Other => {}
+        }
+    } else if api == ApiType::Gemini {
+        // Pas de marqueur de fin : le flux s'arrête avec le corps
+        let mut chunk: GeminiResponse = serde_json::from_str(data).map_err(parse_error)?;
+        chunk.check()?;
+        let meta = chunk.meta();
+        if let Some((content, thoughts)) = chunk.into_parts() {
+            out.push(StreamDelta::Reasoning, thoughts);
+            out.push(StreamDelta::Content, Some(content));
+        }
+        out.push_meta(meta);
+    } else {
+        let chunk: OpenAiStreamChunk = serde_json::from_str(data).map_err(parse_error)?;
+        if let Some(error) = chunk.error {
+            return Err(InjectorError::ApiError(error.to_string()));
+        }
+        let mut finish_reason = None;
+        if let Some(choice) = chunk.choices.into_iter().next() {
+            let delta = choice.delta;
+            out.push(
+                StreamDelta::Reasoning,
+                delta.reasoning.or(delta.reasoning_content),
+            );
+            out.push(StreamDelta::Content, delta.content);
+            finish_reason = choice.finish_reason;
+        }
+        // Le modèle est répété à chaque fragment : seul le dernier compte
+        if finish_reason.is_some() || chunk.usage.is_some() {
+            out.push_meta(OpenAiUsage::meta(
+                chunk.usage,
+                finish_reason.as_deref(),
+                chunk.model,
+            ));
+        }
+    }
+    Ok(out)
+}
 
-        let api_key =
-            self.config.api_key.as_ref().ok_or_else(|| {
-                InjectorError::ApiError("OpenRouter requires API key".to_string())
-            })?;
+/// État du décodage d'un corps HTTP streamé
+struct StreamState {
+    api: ApiType,
+    /// None une fois le flux terminé ou en erreur
+    response: Option<Response>,
+    buffer: Vec<u8>,
+    pending: VecDeque<Result<StreamDelta, InjectorError>>,
+}
 
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", api_key))
-            .header("HTTP-Referer", "https://github.com/JulienDbrt/LDSI")
-            .header("X-Title", "LDSI Benchmark")
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| InjectorError::NetworkError(e.to_string()))?;
+impl StreamState {
+    /// Décode les lignes complètes du tampon (et le reliquat en fin de corps)
+    fn drain_lines(&mut self, end_of_body: bool) {
+        while self.response.is_some() {
+            let line: Vec<u8> = match self.buffer.iter().position(|&b| b == b'\n') {
+                Some(pos) => self.buffer.drain(..=pos).collect(),
+                None if end_of_body && !self.buffer.is_empty() => std::mem::take(&mut self.buffer),
+                None => break,
+            };
+            match parse_stream_line(self.api, &String::from_utf8_lossy(&line)) {
+                Ok(parsed) => {
+                    self.pending.extend(parsed.deltas.into_iter().map(Ok));
+                    if parsed.done {
+                        self.response = None;
+                    }
+                }
+                Err(e) => {
+                    self.pending.push_back(Err(e));
+                    self.response = None;
+                }
+            }
+        }
+        if end_of_body {
+            self.response = None;
+        }
+    }
+}
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(InjectorError::ApiError(format!("{}: {}", status, body)));
+/// Transforme le corps d'une réponse streamée en flux de fragments
+///
+/// Le flux s'arrête au marqueur de fin du provider, à la fin du corps ou à
+/// la première erreur.
+fn delta_stream(
+    api: ApiType,
+    response: Response,
+) -> BoxStream<'static, Result<StreamDelta, InjectorError>> {
+    let state = StreamState {
+        api,
+        response: Some(response),
+        buffer: Vec::new(),
+        pending: VecDeque::new(),
+    };
+    futures::stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(item) = state.pending.pop_front() {
+                return Some((item, state));
+            }
+            let response = state.response.as_mut()?;
+            match response.chunk().await {
+                Ok(Some(bytes)) => {
+                    state.buffer.extend_from_slice(&bytes);
+                    state.drain_lines(false);
+                }
+                Ok(None) => state.drain_lines(true),
+                Err(e) => {
+                    state.response = None;
+                    let error = if e.is_timeout() {
+                        InjectorError::Timeout
+                    } else {
+                        InjectorError::NetworkError(e.to_string())
+                    };
+                    return Some((Err(error), state));
+                }
+            }
         }
+    })
+    .boxed()
+}
 
-        let parsed: OpenAiResponse = response
-            .json()
-            .await
-            .map_err(|e| InjectorError::ParseError(e.to_string()))?;
+/// Raison de fin de génération, normalisée entre providers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FinishReason {
+    /// Fin naturelle ou séquence d'arrêt
+    Stop,
+    /// Coupée à `max_tokens` : réponse tronquée
+    Length,
+    /// Bloquée par le filtre de contenu du provider
+    ContentFilter,
+    /// Appel d'outil
+    ToolCalls,
+    /// Valeur propre au provider
+    Other(String),
+}
 
-        parsed
-            .choices
-            .first()
-            .map(|c| c.message.content.clone())
-            .ok_or_else(|| InjectorError::ParseError("No response content".to_string()))
+impl FinishReason {
+    /// Traduit `finish_reason` (OpenAI, Mistral), `stop_reason` (Anthropic),
+    /// `done_reason` (Ollama) ou `finishReason` (Gemini)
+    pub fn from_provider(reason: &str) -> Self {
+        match reason.to_lowercase().as_str() {
+            "stop" | "end_turn" | "stop_sequence" | "eos" => FinishReason::Stop,
+            "length" | "max_tokens" | "model_length" => FinishReason::Length,
+            "content_filter" | "refusal" | "safety" | "recitation" | "blocklist"
+            | "prohibited_content" | "spii" => FinishReason::ContentFilter,
+            "tool_calls" | "tool_use" | "function_call" => FinishReason::ToolCalls,
+            _ => FinishReason::Other(reason.to_string()),
+        }
+    }
+}
+
+/// Usage et fin de génération déclarés par le provider
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CompletionMeta {
+    /// Tokens du prompt (conversation complète)
+    pub prompt_tokens: Option<u32>,
+    /// Tokens générés, raisonnement compris
+    pub completion_tokens: Option<u32>,
+    pub finish_reason: Option<FinishReason>,
+    /// Modèle effectivement servi (version datée, route OpenRouter)
+    pub model_id: Option<String>,
+}
+
+impl CompletionMeta {
+    /// Complète avec les valeurs reçues plus loin dans le flux
+    fn merge(&mut self, other: CompletionMeta) {
+        self.prompt_tokens = other.prompt_tokens.or(self.prompt_tokens);
+        self.completion_tokens = other.completion_tokens.or(self.completion_tokens);
+        self.finish_reason = other.finish_reason.or(self.finish_reason.take());
+        self.model_id = other.model_id.or(self.model_id.take());
     }
 
-    /// Exécute une injection A/B (standard puis fracturé)
-    pub async fn inject_ab(
-        &self,
-        prompt_standard: &str,
-        prompt_fractured: &str,
-    ) -> Result<(String, String), InjectorError> {
-        let response_a = self.inject(prompt_standard).await?;
-        let response_b = self.inject(prompt_fractured).await?;
-        Ok((response_a, response_b))
+    /// Génération coupée à `max_tokens` (NCD biaisé par la troncature)
+    pub fn is_truncated(&self) -> bool {
+        self.finish_reason == Some(FinishReason::Length)
     }
 }
 
-/// Multi-Injector pour benchmarks parallèles sur plusieurs modèles
-#[allow(dead_code)]
-pub struct MultiInjector {
-    injectors: Vec<(String, Injector)>,
+/// Réponse d'un modèle : texte, raisonnement, usage et latence
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Completion {
+    /// Réponse finale (seule notée par défaut)
+    pub content: String,
+    /// Trace de raisonnement (champ dédié de l'API ou balises en ligne)
+    pub reasoning: Option<String>,
+    /// Tokens, raison d'arrêt et modèle servi
+    #[serde(flatten)]
+    pub meta: CompletionMeta,
+    /// Durée de la tentative ayant abouti en millisecondes, délais de reprise exclus
+    #[serde(default)]
+    pub latency_ms: f64,
+    /// Tentatives et délais de reprise nécessaires pour l'obtenir
+    #[serde(default)]
+    pub retry: RetryStats,
+    /// Profil de latence (réponses streamées uniquement)
+    #[serde(default)]
+    pub timing: Option<StreamTiming>,
 }
 
-#[allow(dead_code)]
-impl MultiInjector {
-    /// Crée un multi-injecteur vide
-    pub fn new() -> Self {
+impl Completion {
+    /// Assemble une réponse, en extrayant les balises <think> restées dans le contenu
+    pub fn from_parts(content: String, reasoning: Option<String>) -> Self {
+        let (content, inline) = split_reasoning(&content);
+        let reasoning = match (reasoning.filter(|r| !r.trim().is_empty()), inline) {
+            (Some(field), Some(inline)) => Some(format!("{}\n\n{}", field.trim(), inline)),
+            (field, inline) => field.map(|r| r.trim().to_string()).or(inline),
+        };
         Self {
-            injectors: Vec::new(),
+            content,
+            reasoning,
+            meta: CompletionMeta::default(),
+            latency_ms: 0.0,
+            retry: RetryStats::default(),
+            timing: None,
         }
     }
 
-    /// Ajoute un modèle au benchmark
-    pub fn add_model(&mut self, name: &str, config: LlmConfig) {
-        self.injectors
-            .push((name.to_string(), Injector::new(config)));
+    /// Attache les statistiques de reprise de la requête
+    fn with_retry(mut self, retry: RetryStats) -> Self {
+        self.retry = retry;
+        self
     }
 
-    /// Ajoute un modèle OpenRouter
-    pub fn add_openrouter(&mut self, model_id: &str, api_key: &str) {
-        let config = LlmConfig::openrouter(model_id, api_key);
-        // Extraire le nom court du modèle (après le /)
-        let name = model_id.split('/').next_back().unwrap_or(model_id);
-        self.injectors
-            .push((name.to_string(), Injector::new(config)));
+    /// Attache l'usage et la raison d'arrêt déclarés par le provider
+    fn with_meta(mut self, meta: CompletionMeta) -> Self {
+        self.meta = meta;
+        self
     }
 
-    /// Ajoute un modèle Ollama local
-    pub fn add_ollama(&mut self, model: &str) {
-        let config = LlmConfig::ollama_local(model);
-        self.injectors
-            .push((model.to_string(), Injector::new(config)));
+    /// Génération coupée à `max_tokens`
+    pub fn is_truncated(&self) -> bool {
+        self.meta.is_truncated()
     }
 
-    /// Retourne la liste des modèles configurés
-    pub fn models(&self) -> Vec<&str> {
-        self.injectors
-            .iter()
-            .map(|(name, _)| name.as_str())
-            .collect()
+    /// Une réponse entièrement retenue par le filtre du provider est une
+    /// erreur, pas une réponse vide à noter
+    fn unblocked(self) -> Result<Self, InjectorError> {
+        let withheld = self.content.is_empty() && self.reasoning.is_none();
+        match &self.meta.finish_reason {
+            Some(FinishReason::ContentFilter) if withheld => {
+                Err(InjectorError::Blocked("content_filter".to_string()))
+            }
+            _ => Ok(self),
+        }
     }
+}
 
-    /// Exécute le prompt sur tous les modèles en parallèle
-    pub async fn inject_all(&self, prompt: &str) -> Vec<(String, Result<String, InjectorError>)> {
-        use futures::future::join_all;
+/// Erreur d'injection
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub enum InjectorError {
+    NetworkError(String),
+    ApiError(String),
+    ParseError(String),
+    Timeout,
+    /// Paramètre d'échantillonnage refusé avant l'envoi
+    Sampling(SamplingError),
+    /// Prompt ou réponse bloqué par le filtre de sécurité du provider
+    Blocked(String),
+    /// Cache illisible, ou réponse absente en mode `replay-only`
+    Cache(CacheError),
+}
 
-        let futures: Vec<_> = self
-            .injectors
-            .iter()
-            .map(|(name, injector)| {
-                let name = name.clone();
-                let prompt = prompt.to_string();
-                async move {
-                    let result = injector.inject(&prompt).await;
-                    (name, result)
-                }
-            })
-            .collect();
+impl std::fmt::Display for InjectorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InjectorError::NetworkError(e) => write!(f, "Network error: {}", e),
+            InjectorError::ApiError(e) => write!(f, "API error: {}", e),
+            InjectorError::ParseError(e) => write!(f, "Parse error: {}", e),
+            InjectorError::Timeout => write!(f, "Request timeout"),
+            InjectorError::Sampling(e) => write!(f, "Sampling error: {}", e),
+            InjectorError::Blocked(reason) => write!(f, "Blocked by safety filter: {}", reason),
+            InjectorError::Cache(e) => write!(f, "Cache error: {}", e),
+        }
+    }
+}
 
-        join_all(futures).await
+impl std::error::Error for InjectorError {}
+
+/// Client d'injection LLM
+pub struct Injector {
+    client: Client,
+    config: LlmConfig,
+}
+
+impl Injector {
+    /// Crée un nouvel injecteur avec la configuration donnée
+    pub fn new(config: LlmConfig) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self { client, config }
     }
 
-    /// Exécute un benchmark A/B sur tous les modèles en parallèle
-    pub async fn benchmark_all(
+    /// Retourne la configuration actuelle
+    #[allow(dead_code)]
+    pub fn config(&self) -> &LlmConfig {
+        &self.config
+    }
+
+    /// Envoie un prompt : texte, raisonnement, tokens, raison d'arrêt, latence
+    pub async fn inject(&self, prompt: &str) -> Result<Completion, InjectorError> {
+        self.inject_conversation(&Conversation::user(prompt)).await
+    }
+
+    /// Envoie une conversation (prompt système, tours précédents)
+    ///
+    /// Avec `stream` activé, la réponse est reçue en streaming et porte son
+    /// profil de latence. Une réponse présente dans le cache est rejouée sans
+    /// requête.
+    pub async fn inject_conversation(
         &self,
-        prompt_a: &str,
-        prompt_b: &str,
-    ) -> Vec<(String, Result<(String, String), InjectorError>)> {
-        use futures::future::join_all;
+        conversation: &Conversation,
+    ) -> Result<Completion, InjectorError> {
+        if self.config.stream {
+            return self.inject_streaming(conversation, |_| {}).await;
+        }
+        let key = self.cache_key(conversation);
+        if let Some(cached) = self.cached(key.as_deref())? {
+            return Ok(cached);
+        }
+        let (response, retry, sent_at) = self.send(self.request(conversation, false)?).await?;
+        let parsed = match self.config.api_type {
+            ApiType::OpenAI
+            | ApiType::OpenRouter
+            | ApiType::OpenAICompatible
+            | ApiType::Mistral => Self::parse_openai(response).await,
+            ApiType::Ollama => Self::parse_ollama(response).await,
+            ApiType::Anthropic => Self::parse_anthropic(response).await,
+            ApiType::Gemini => Self::parse_gemini(response).await,
+        };
+        let mut completion = parsed?.unblocked()?.with_retry(retry);
+        completion.latency_ms = millis(sent_at.elapsed());
+        self.remember(key.as_deref(), &completion)?;
+        Ok(completion)
+    }
 
-        let futures: Vec<_> = self
-            .injectors
-            .iter()
-            .map(|(name, injector)| {
-                let name = name.clone();
-                let pa = prompt_a.to_string();
-                let pb = prompt_b.to_string();
-                async move {
-                    let result = injector.inject_ab(&pa, &pb).await;
-                    (name, result)
-                }
-            })
-            .collect();
+    /// Empreinte de la conversation (None si le cache est désactivé)
+    fn cache_key(&self, conversation: &Conversation) -> Option<String> {
+        self.config
+            .cache
+            .is_enabled()
+            .then(|| CacheConfig::key(&self.config, conversation))
+    }
 
-        join_all(futures).await
+    /// Réponse rejouée depuis le cache (paramètres validés comme pour un envoi)
+    fn cached(&self, key: Option<&str>) -> Result<Option<Completion>, InjectorError> {
+        let Some(key) = key else {
+            return Ok(None);
+        };
+        self.config
+            .sampling
+            .validate(self.config.api_type)
+            .map_err(InjectorError::Sampling)?;
+        self.config.cache.lookup(key).map_err(InjectorError::Cache)
     }
-}
 
-impl Default for MultiInjector {
-    fn default() -> Self {
-        Self::new()
+    /// Enregistre une réponse obtenue du provider
+    fn remember(&self, key: Option<&str>, completion: &Completion) -> Result<(), InjectorError> {
+        match key {
+            Some(key) => self
+                .config
+                .cache
+                .store(key, &self.config.model, completion)
+                .map_err(InjectorError::Cache),
+            None => Ok(()),
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Ouvre un flux de fragments de réponse (SSE ou NDJSON selon le provider)
+    pub async fn inject_stream(
+        &self,
+        conversation: &Conversation,
+    ) -> Result<LlmStream, InjectorError> {
+        let (response, retry, sent_at) = self.send(self.request(conversation, true)?).await?;
+        Ok(LlmStream {
+            retry,
+            sent_at,
+            deltas: delta_stream(self.config.api_type, response),
+        })
+    }
 
-    #[test]
-    fn test_default_config() {
-        let config = LlmConfig::default();
-        assert_eq!(config.api_type, ApiType::Ollama);
-        assert!(config.base_url.contains("11434"));
+    /// Reçoit une réponse en streaming et mesure sa latence
+    ///
+    /// `on_delta` est appelé à chaque fragment de texte, pour affichage ou
+    /// notation à la volée. La réponse assemblée porte TTFT, durée totale et
+    /// intervalles, ainsi que l'usage annoncé en fin de flux. Une réponse du
+    /// cache est transmise en un fragment de raisonnement et un de contenu.
+    pub async fn inject_streaming<F>(
+        &self,
+        conversation: &Conversation,
+        mut on_delta: F,
+    ) -> Result<Completion, InjectorError>
+    where
+        F: FnMut(&StreamDelta),
+    {
+        let key = self.cache_key(conversation);
+        if let Some(cached) = self.cached(key.as_deref())? {
+            if let Some(reasoning) = &cached.reasoning {
+                on_delta(&StreamDelta::Reasoning(reasoning.clone()));
+            }
+            on_delta(&StreamDelta::Content(cached.content.clone()));
+            return Ok(cached);
+        }
+        let mut stream = self.inject_stream(conversation).await?;
+        let mut recorder = TimingRecorder::new(stream.sent_at);
+        let mut content = String::new();
+        let mut reasoning = String::new();
+        let mut meta = CompletionMeta::default();
+        while let Some(delta) = stream.deltas.next().await {
+            let delta = delta?;
+            if let StreamDelta::Meta(update) = delta {
+                meta.merge(update);
+                continue;
+            }
+            recorder.tick();
+            on_delta(&delta);
+            match delta {
+                StreamDelta::Content(text) => content.push_str(&text),
+                StreamDelta::Reasoning(text) => reasoning.push_str(&text),
+                StreamDelta::Meta(_) => {}
+            }
+        }
+        let reasoning = (!reasoning.is_empty()).then_some(reasoning);
+        let mut response = Completion::from_parts(content, reasoning)
+            .with_retry(stream.retry)
+            .with_meta(meta)
+            .unblocked()?;
+        let timing = recorder.finish();
+        response.latency_ms = timing.total_ms;
+        response.timing = Some(timing);
+        self.remember(key.as_deref(), &response)?;
+        Ok(response)
     }
 
-    #[test]
-    fn test_openrouter_config() {
-        let config = LlmConfig::openrouter("anthropic/claude-3-opus", "test-key");
-        assert_eq!(config.api_type, ApiType::OpenRouter);
-        assert!(config.base_url.contains("openrouter.ai"));
+    /// Construit la requête du provider configuré
+    fn request(
+        &self,
+        conversation: &Conversation,
+        stream: bool,
+    ) -> Result<RequestBuilder, InjectorError> {
+        self.config
+            .sampling
+            .validate(self.config.api_type)
+            .map_err(InjectorError::Sampling)?;
+        match self.config.api_type {
+            ApiType::OpenAI | ApiType::OpenAICompatible => {
+                Ok(self.openai_request(conversation, stream))
+            }
+            ApiType::Ollama => Ok(self.ollama_request(conversation, stream)),
+            ApiType::Anthropic => self.anthropic_request(conversation, stream),
+            ApiType::OpenRouter => self.openrouter_request(conversation, stream),
+            ApiType::Gemini => self.gemini_request(conversation, stream),
+            ApiType::Mistral => self.mistral_request(conversation, stream),
+        }
     }
 
-    #[test]
-    fn test_injector_creation() {
-        let config = LlmConfig::default();
-        let _injector = Injector::new(config);
+    /// Envoie une requête en appliquant la politique de reprise
+    ///
+    /// La requête est clonée à chaque tentative. Les codes HTTP retentables,
+    /// les timeouts et les erreurs de connexion déclenchent une nouvelle
+    /// tentative tant que `max_attempts` n'est pas atteint. L'instant renvoyé
+    /// est l'envoi de la tentative ayant abouti : origine des mesures de
+    /// latence, hors délais de reprise.
+    async fn send(
+        &self,
+        request: RequestBuilder,
+    ) -> Result<(Response, RetryStats, Instant), InjectorError> {
+        let policy = &self.config.retry;
+        let mut stats = RetryStats::default();
+        loop {
+            stats.attempts += 1;
+            let last = stats.attempts >= policy.max_attempts.max(1);
+            let attempt = request.try_clone().expect("Corps JSON toujours clonable");
+            let sent_at = Instant::now();
+            let (error, retry_after) = match attempt.send().await {
+                Ok(response) if response.status().is_success() => {
+                    return Ok((response, stats, sent_at));
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    let retry_after = response
+                        .headers()
+                        .get(RETRY_AFTER)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(parse_retry_after);
+                    let body = response.text().await.unwrap_or_default();
+                    let error = InjectorError::ApiError(format!("{}: {}", status, body));
+                    if !policy.is_retryable(status) {
+                        return Err(error);
+                    }
+                    (error, retry_after)
+                }
+                Err(e) if e.is_timeout() => (InjectorError::Timeout, None),
+                Err(e) if e.is_connect() => (InjectorError::NetworkError(e.to_string()), None),
+                Err(e) => return Err(InjectorError::NetworkError(e.to_string())),
+            };
+            if last {
+                return Err(error);
+            }
+            let delay = policy.delay(stats.attempts, retry_after);
+            stats.backoff_ms.push(delay.as_millis() as u64);
+            tokio::time::sleep(delay).await;
+        }
     }
 
-    #[test]
-    fn test_multi_injector() {
-        let mut multi = MultiInjector::new();
-        multi.add_ollama("llama3");
-        multi.add_openrouter("openai/gpt-4-turbo", "fake-key");
-        assert_eq!(multi.models().len(), 2);
+    /// Requête au format OpenAI, selon la forme de l'endpoint configurée
+    fn openai_request(&self, conversation: &Conversation, stream: bool) -> RequestBuilder {
+        let endpoint = &self.config.endpoint;
+        let path = endpoint.path.as_deref().unwrap_or(OPENAI_CHAT_PATH);
+        let url = format!("{}{}", self.config.base_url, path);
+
+        let request = OpenAiRequest {
+            model: self.config.model.clone(),
+            messages: openai_messages(conversation),
+            temperature: self.config.temperature,
+            max_tokens: self.config.max_tokens,
+            sampling: OpenAiSampling::from(&self.config.sampling),
+            stream,
+            stream_options: stream.then_some(OpenAiStreamOptions {
+                include_usage: true,
+            }),
+        };
+
+        let mut req_builder = self.client.post(&url).json(&request);
+        if !endpoint.query.is_empty() {
+            req_builder = req_builder.query(&endpoint.query);
+        }
+        for (name, value) in &endpoint.headers {
+            req_builder = req_builder.header(name, value);
+        }
+
+        match (&endpoint.auth, &self.config.api_key) {
+            (AuthScheme::Bearer, Some(api_key)) => {
+                req_builder.header("Authorization", format!("Bearer {}", api_key))
+            }
+            (AuthScheme::Header(name), Some(api_key)) => req_builder.header(name, api_key),
+            _ => req_builder,
+        }
     }
 
-    // ============================================================
-    // MOCK TESTS - Couverture complète via wiremock
-    // ============================================================
+    async fn parse_openai(response: Response) -> Result<Completion, InjectorError> {
+        let parsed: OpenAiResponse = response.json().await.map_err(body_error)?;
+
+        let choice = parsed
+            .choices
+            .into_iter()
+            .next()
+            .ok_or_else(|| InjectorError::ParseError("No response content".to_string()))?;
+        let meta = OpenAiUsage::meta(parsed.usage, choice.finish_reason.as_deref(), parsed.model);
+        Ok(choice.message.into_response().with_meta(meta))
+    }
+
+    /// Prompt seul : `/api/generate` ; système ou multi-tours : `/api/chat`
+    fn ollama_request(&self, conversation: &Conversation, stream: bool) -> RequestBuilder {
+        let options = OllamaOptions {
+            temperature: self.config.temperature,
+            num_predict: self.config.max_tokens,
+            sampling: self.config.sampling.clone(),
+        };
+
+        if let (true, Some(prompt)) = (conversation.is_single_prompt(), conversation.last_user()) {
+            let url = format!("{}/api/generate", self.config.base_url);
+            let request = OllamaRequest {
+                model: self.config.model.clone(),
+                prompt: prompt.to_string(),
+                stream,
+                options,
+            };
+            return self.client.post(&url).json(&request);
+        }
+
+        let url = format!("{}/api/chat", self.config.base_url);
+        let request = OllamaChatRequest {
+            model: self.config.model.clone(),
+            messages: openai_messages(conversation),
+            stream,
+            options,
+        };
+        self.client.post(&url).json(&request)
+    }
+
+    async fn parse_ollama(response: Response) -> Result<Completion, InjectorError> {
+        let parsed: OllamaResponse = response.json().await.map_err(body_error)?;
+
+        let meta = parsed.meta();
+        let (content, thinking) = parsed.into_parts();
+        Ok(Completion::from_parts(content, thinking).with_meta(meta))
+    }
+
+    fn anthropic_request(
+        &self,
+        conversation: &Conversation,
+        stream: bool,
+    ) -> Result<RequestBuilder, InjectorError> {
+        let url = format!("{}/v1/messages", self.config.base_url);
+
+        // Les messages système rejoignent le champ `system` de premier niveau
+        let request = AnthropicRequest {
+            model: self.config.model.clone(),
+            messages: conversation
+                .turns()
+                .map(|m| AnthropicMessage {
+                    role: m.role.as_str().to_string(),
+                    content: m.content.clone(),
+                })
+                .collect(),
+            max_tokens: self.config.max_tokens,
+            temperature: self.config.temperature,
+            top_p: self.config.sampling.top_p,
+            top_k: self.config.sampling.top_k,
+            stop_sequences: self.config.sampling.stop.clone(),
+            system: conversation.system_prompt(),
+            stream,
+        };
+
+        let api_key = self
+            .config
+            .api_key
+            .as_ref()
+            .ok_or_else(|| InjectorError::ApiError("Anthropic requires API key".to_string()))?;
+
+        Ok(self
+            .client
+            .post(&url)
+            .header("x-api-key", api_key)
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json")
+            .json(&request))
+    }
+
+    async fn parse_anthropic(response: Response) -> Result<Completion, InjectorError> {
+        let parsed: AnthropicResponse = response.json().await.map_err(body_error)?;
+
+        parsed
+            .into_response()
+            .ok_or_else(|| InjectorError::ParseError("No response content".to_string()))
+    }
+
+    /// Requête OpenRouter (OpenAI-compatible avec headers spécifiques)
+    fn openrouter_request(
+        &self,
+        conversation: &Conversation,
+        stream: bool,
+    ) -> Result<RequestBuilder, InjectorError> {
+        if self.config.api_key.is_none() {
+            return Err(InjectorError::ApiError(
+                "OpenRouter requires API key".to_string(),
+            ));
+        }
+
+        Ok(self
+            .openai_request(conversation, stream)
+            .header("HTTP-Referer", "https://github.com/JulienDbrt/LDSI")
+            .header("X-Title", "LDSI Benchmark"))
+    }
+
+    /// Requête Gemini : `generateContent`, ou `streamGenerateContent` en SSE
+    fn gemini_request(
+        &self,
+        conversation: &Conversation,
+        stream: bool,
+    ) -> Result<RequestBuilder, InjectorError> {
+        let api_key = self
+            .config
+            .api_key
+            .as_ref()
+            .ok_or_else(|| InjectorError::ApiError("Gemini requires API key".to_string()))?;
+
+        let model = self.config.model.trim_start_matches("models/");
+        let url = match stream {
+            true => format!(
+                "{}/v1beta/models/{}:streamGenerateContent",
+                self.config.base_url, model
+            ),
+            false => format!(
+                "{}/v1beta/models/{}:generateContent",
+                self.config.base_url, model
+            ),
+        };
+
+        // Le rôle "assistant" s'appelle "model" ; le système passe par
+        // `systemInstruction`
+        let sampling = &self.config.sampling;
+        let request = GeminiRequest {
+            contents: conversation
+                .turns()
+                .map(|m| {
+                    let role = match m.role {
+                        Role::Assistant => "model",
+                        _ => "user",
+                    };
+                    GeminiContent::text(Some(role), &m.content)
+                })
+                .collect(),
+            system_instruction: conversation
+                .system_prompt()
+                .map(|system| GeminiContent::text(None, &system)),
+            generation_config: GeminiGenerationConfig {
+                temperature: self.config.temperature,
+                max_output_tokens: self.config.max_tokens,
+                seed: sampling.seed,
+                top_p: sampling.top_p,
+                top_k: sampling.top_k,
+                stop_sequences: sampling.stop.clone(),
+                frequency_penalty: sampling.frequency_penalty,
+                presence_penalty: sampling.presence_penalty,
+            },
+        };
+
+        let mut req_builder = self
+            .client
+            .post(&url)
+            .header("x-goog-api-key", api_key)
+            .json(&request);
+        if stream {
+            req_builder = req_builder.query(&[("alt", "sse")]);
+        }
+        Ok(req_builder)
+    }
+
+    async fn parse_gemini(response: Response) -> Result<Completion, InjectorError> {
+        let mut parsed: GeminiResponse = response.json().await.map_err(body_error)?;
+
+        parsed.check()?;
+        let meta = parsed.meta();
+        let reason = parsed
+            .candidates
+            .first()
+            .and_then(|c| c.finish_reason.clone());
+        let (content, thoughts) = parsed
+            .into_parts()
+            .ok_or_else(|| InjectorError::ParseError("No response content".to_string()))?;
+        // Candidat vide arrêté par le filtre : la raison exacte est conservée
+        if content.is_empty()
+            && thoughts.is_none()
+            && meta.finish_reason == Some(FinishReason::ContentFilter)
+        {
+            return Err(InjectorError::Blocked(reason.unwrap_or_default()));
+        }
+        Ok(Completion::from_parts(content, thoughts).with_meta(meta))
+    }
+
+    /// Requête Mistral (format OpenAI, clé obligatoire)
+    fn mistral_request(
+        &self,
+        conversation: &Conversation,
+        stream: bool,
+    ) -> Result<RequestBuilder, InjectorError> {
+        let api_key = self
+            .config
+            .api_key
+            .as_ref()
+            .ok_or_else(|| InjectorError::ApiError("Mistral requires API key".to_string()))?;
+
+        let url = format!("{}{}", self.config.base_url, OPENAI_CHAT_PATH);
+        let sampling = &self.config.sampling;
+        let request = MistralRequest {
+            model: self.config.model.clone(),
+            messages: openai_messages(conversation),
+            temperature: self.config.temperature,
+            max_tokens: self.config.max_tokens,
+            random_seed: sampling.seed,
+            top_p: sampling.top_p,
+            stop: sampling.stop.clone(),
+            frequency_penalty: sampling.frequency_penalty,
+            presence_penalty: sampling.presence_penalty,
+            stream,
+        };
+
+        Ok(self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", api_key))
+            .json(&request))
+    }
+
+    /// Exécute une injection A/B (standard puis fracturé)
+    pub async fn inject_ab(
+        &self,
+        prompt_standard: &str,
+        prompt_fractured: &str,
+    ) -> Result<(Completion, Completion), InjectorError> {
+        self.inject_ab_conversations(
+            &Conversation::user(prompt_standard),
+            &Conversation::user(prompt_fractured),
+        )
+        .await
+    }
+
+    /// Injection A/B de deux conversations (prompts système ou tours différents)
+    pub async fn inject_ab_conversations(
+        &self,
+        conversation_a: &Conversation,
+        conversation_b: &Conversation,
+    ) -> Result<(Completion, Completion), InjectorError> {
+        let response_a = self.inject_conversation(conversation_a).await?;
+        let response_b = self.inject_conversation(conversation_b).await?;
+        Ok((response_a, response_b))
+    }
+}
+
+/// Multi-Injector pour benchmarks parallèles sur plusieurs modèles
+#[allow(dead_code)]
+pub struct MultiInjector {
+    injectors: Vec<(String, Injector)>,
+}
+
+#[allow(dead_code)]
+impl MultiInjector {
+    /// Crée un multi-injecteur vide
+    pub fn new() -> Self {
+        Self {
+            injectors: Vec::new(),
+        }
+    }
+
+    /// Ajoute un modèle au benchmark
+    pub fn add_model(&mut self, name: &str, config: LlmConfig) {
+        self.injectors
+            .push((name.to_string(), Injector::new(config)));
+    }
+
+    /// Ajoute un modèle OpenRouter
+    pub fn add_openrouter(&mut self, model_id: &str, api_key: &str) {
+        let config = LlmConfig::openrouter(model_id, api_key);
+        // Extraire le nom court du modèle (après le /)
+        let name = model_id.split('/').next_back().unwrap_or(model_id);
+        self.injectors
+            .push((name.to_string(), Injector::new(config)));
+    }
+
+    /// Ajoute un modèle Ollama local
+    pub fn add_ollama(&mut self, model: &str) {
+        let config = LlmConfig::ollama_local(model);
+        self.injectors
+            .push((model.to_string(), Injector::new(config)));
+    }
+
+    /// Retourne la liste des modèles configurés
+    pub fn models(&self) -> Vec<&str> {
+        self.injectors
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// Exécute le prompt sur tous les modèles en parallèle
+    pub async fn inject_all(
+        &self,
+        prompt: &str,
+    ) -> Vec<(String, Result<Completion, InjectorError>)> {
+        use futures::future::join_all;
+
+        let futures: Vec<_> = self
+            .injectors
+            .iter()
+            .map(|(name, injector)| {
+                let name = name.clone();
+                let prompt = prompt.to_string();
+                async move {
+                    let result = injector.inject(&prompt).await;
+                    (name, result)
+                }
+            })
+            .collect();
+
+        join_all(futures).await
+    }
+
+    /// Exécute un benchmark A/B sur tous les modèles en parallèle
+    pub async fn benchmark_all(
+        &self,
+        prompt_a: &str,
+        prompt_b: &str,
+    ) -> Vec<(String, Result<(Completion, Completion), InjectorError>)> {
+        use futures::future::join_all;
+
+        let futures: Vec<_> = self
+            .injectors
+            .iter()
+            .map(|(name, injector)| {
+                let name = name.clone();
+                let pa = prompt_a.to_string();
+                let pb = prompt_b.to_string();
+                async move {
+                    let result = injector.inject_ab(&pa, &pb).await;
+                    (name, result)
+                }
+            })
+            .collect();
+
+        join_all(futures).await
+    }
+}
+
+impl Default for MultiInjector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config() {
+        let config = LlmConfig::default();
+        assert_eq!(config.api_type, ApiType::Ollama);
+        assert!(config.base_url.contains("11434"));
+    }
+
+    #[test]
+    fn test_openrouter_config() {
+        let config = LlmConfig::openrouter("anthropic/claude-3-opus", "test-key");
+        assert_eq!(config.api_type, ApiType::OpenRouter);
+        assert!(config.base_url.contains("openrouter.ai"));
+    }
+
+    #[test]
+    fn test_injector_creation() {
+        let config = LlmConfig::default();
+        let _injector = Injector::new(config);
+    }
+
+    #[test]
+    fn test_multi_injector() {
+        let mut multi = MultiInjector::new();
+        multi.add_ollama("llama3");
+        multi.add_openrouter("openai/gpt-4-turbo", "fake-key");
+        assert_eq!(multi.models().len(), 2);
+    }
+
+    // ============================================================
+    // MOCK TESTS - Couverture complète via wiremock
+    // ============================================================
+
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_ollama_happy_path() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/generate"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "response": "Le chat est un félin domestique."
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::Ollama,
+            ..Default::default()
+        };
+        let injector = Injector::new(config);
+        let result = injector.inject("Qu'est-ce qu'un chat?").await;
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().content, "Le chat est un félin domestique.");
+    }
+
+    #[tokio::test]
+    async fn test_openai_happy_path() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{"message": {"content": "La gravité courbe l'espace-temps."}}]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::OpenAI,
+            api_key: Some("test-key".to_string()),
+            ..Default::default()
+        };
+        let injector = Injector::new(config);
+        let result = injector.inject("Explique la gravité").await;
+        assert!(result.is_ok());
+        assert!(result.unwrap().content.contains("espace-temps"));
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_happy_path() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "content": [{"text": "Réponse Anthropic simulée."}]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::Anthropic,
+            api_key: Some("test-key".to_string()),
+            ..Default::default()
+        };
+        let injector = Injector::new(config);
+        let result = injector.inject("Test").await;
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().content, "Réponse Anthropic simulée.");
+    }
+
+    #[tokio::test]
+    async fn test_openrouter_happy_path() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{"message": {"content": "Réponse OpenRouter."}}]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::OpenRouter,
+            api_key: Some("test-key".to_string()),
+            ..Default::default()
+        };
+        let injector = Injector::new(config);
+        let result = injector.inject("Test").await;
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().content, "Réponse OpenRouter.");
+    }
+
+    #[tokio::test]
+    async fn test_api_error_429_rate_limit() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/generate"))
+            .respond_with(ResponseTemplate::new(429).set_body_string("Rate limit exceeded"))
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::Ollama,
+            retry: RetryPolicy::none(),
+            ..Default::default()
+        };
+        let injector = Injector::new(config);
+        let result = injector.inject("Test").await;
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            InjectorError::ApiError(msg) => assert!(msg.contains("429"), "Got: {}", msg),
+            other => panic!("Expected ApiError, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_api_error_500_server() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(
+                ResponseTemplate::new(500).set_body_string("Internal Server Error"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::OpenAI,
+            api_key: Some("test-key".to_string()),
+            retry: RetryPolicy::none(),
+            ..Default::default()
+        };
+        let injector = Injector::new(config);
+        let result = injector.inject("Test").await;
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            InjectorError::ApiError(msg) => assert!(msg.contains("500"), "Got: {}", msg),
+            other => panic!("Expected ApiError, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_malformed_json_response() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/generate"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string("{not valid json!!!}"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::Ollama,
+            ..Default::default()
+        };
+        let injector = Injector::new(config);
+        let result = injector.inject("Test").await;
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            InjectorError::ParseError(_) => {}
+            other => panic!("Expected ParseError, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_missing_api_key() {
+        let config = LlmConfig {
+            base_url: "http://127.0.0.1:1".to_string(),
+            api_type: ApiType::Anthropic,
+            api_key: None,
+            ..Default::default()
+        };
+        let injector = Injector::new(config);
+        let result = injector.inject("Test").await;
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            InjectorError::ApiError(msg) => {
+                assert!(msg.contains("API key"), "Got: {}", msg)
+            }
+            other => panic!("Expected ApiError about missing key, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_openrouter_missing_api_key() {
+        let config = LlmConfig {
+            base_url: "http://127.0.0.1:1".to_string(),
+            api_type: ApiType::OpenRouter,
+            api_key: None,
+            ..Default::default()
+        };
+        let injector = Injector::new(config);
+        let result = injector.inject("Test").await;
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            InjectorError::ApiError(msg) => {
+                assert!(msg.contains("API key"), "Got: {}", msg)
+            }
+            other => panic!("Expected ApiError about missing key, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_openai_empty_choices() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": []
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::OpenAI,
+            api_key: Some("test-key".to_string()),
+            ..Default::default()
+        };
+        let injector = Injector::new(config);
+        let result = injector.inject("Test").await;
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            InjectorError::ParseError(msg) => {
+                assert!(msg.contains("No response"), "Got: {}", msg)
+            }
+            other => panic!("Expected ParseError, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_inject_ab_double_call() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/generate"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "response": "Réponse mock."
+            })))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::Ollama,
+            ..Default::default()
+        };
+        let injector = Injector::new(config);
+        let result = injector.inject_ab("prompt A", "prompt B").await;
+        assert!(result.is_ok());
+        let (a, b) = result.unwrap();
+        assert_eq!(a.content, "Réponse mock.");
+        assert_eq!(b.content, "Réponse mock.");
+    }
+
+    #[tokio::test]
+    async fn test_openrouter_reasoning_field() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{"message": {
+                    "content": "42.",
+                    "reasoning": "Six fois sept."
+                }}]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::OpenRouter,
+            api_key: Some("key".to_string()),
+            ..Default::default()
+        };
+        let injector = Injector::new(config);
+        let response = injector.inject("6x7?").await.unwrap();
+        assert_eq!(response.content, "42.");
+        assert_eq!(response.reasoning.as_deref(), Some("Six fois sept."));
+        assert_eq!(injector.inject("6x7?").await.unwrap().content, "42.");
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_thinking_blocks() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "content": [
+                    {"type": "thinking", "thinking": "Analyse du prompt.", "signature": "x"},
+                    {"type": "text", "text": "Réponse finale."}
+                ]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::Anthropic,
+            api_key: Some("key".to_string()),
+            ..Default::default()
+        };
+        let response = Injector::new(config).inject("?").await.unwrap();
+        assert_eq!(response.content, "Réponse finale.");
+        assert_eq!(response.reasoning.as_deref(), Some("Analyse du prompt."));
+    }
+
+    #[tokio::test]
+    async fn test_ollama_thinking_and_inline_tags() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/generate"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "response": "<think>Trace en ligne.</think>\n\nRéponse.",
+                "thinking": "Trace dédiée."
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::Ollama,
+            ..Default::default()
+        };
+        let response = Injector::new(config).inject("?").await.unwrap();
+        assert_eq!(response.content, "Réponse.");
+        assert_eq!(
+            response.reasoning.as_deref(),
+            Some("Trace dédiée.\n\nTrace en ligne.")
+        );
+    }
+
+    /// Reprise rapide et déterministe pour les tests
+    fn fast_retry(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay_ms: 10,
+            max_delay_ms: 1_000,
+            jitter: 0.0,
+            ..Default::default()
+        }
+    }
+
+    fn ollama_ok() -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({"response": "OK"}))
+    }
+
+    #[test]
+    fn test_retry_policy_delay() {
+        let policy = fast_retry(5);
+        let delays: Vec<u64> = (1..=8)
+            .map(|n| policy.delay(n, None).as_millis() as u64)
+            .collect();
+        assert_eq!(delays, vec![10, 20, 40, 80, 160, 320, 640, 1_000]);
+        // Retry-After prioritaire mais plafonné
+        assert_eq!(
+            policy
+                .delay(1, Some(Duration::from_millis(300)))
+                .as_millis(),
+            300
+        );
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(60))).as_millis(),
+            1_000
+        );
+
+        let jittered = RetryPolicy {
+            jitter: 0.5,
+            ..fast_retry(5)
+        };
+        for _ in 0..50 {
+            let ms = jittered.delay(3, None).as_millis();
+            assert!((20..=60).contains(&ms), "Got: {}", ms);
+        }
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after(" 7 "), Some(Duration::from_secs(7)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        let future = (chrono::Utc::now() + chrono::Duration::seconds(120)).to_rfc2822();
+        let wait = parse_retry_after(&future).unwrap();
+        assert!(wait > Duration::from_secs(100) && wait <= Duration::from_secs(120));
+        assert_eq!(parse_retry_after("bientôt"), None);
+    }
+
+    #[tokio::test]
+    async fn test_retry_5xx_sequence_then_success() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/generate"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/generate"))
+            .respond_with(ollama_ok())
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::Ollama,
+            retry: fast_retry(3),
+            ..Default::default()
+        };
+        let response = Injector::new(config).inject("?").await.unwrap();
+        assert_eq!(response.content, "OK");
+        assert_eq!(response.retry.attempts, 3);
+        assert_eq!(response.retry.backoff_ms, vec![10, 20]);
+    }
+
+    #[tokio::test]
+    async fn test_latency_excludes_backoff() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/generate"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/generate"))
+            .respond_with(ollama_ok())
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::Ollama,
+            retry: RetryPolicy {
+                base_delay_ms: 400,
+                ..fast_retry(2)
+            },
+            ..Default::default()
+        };
+        let response = Injector::new(config).inject("?").await.unwrap();
+        assert_eq!(response.retry.backoff_ms, vec![400]);
+        assert!(response.latency_ms < 400.0, "{}", response.latency_ms);
+    }
+
+    #[tokio::test]
+    async fn test_retry_ab_pair_survives_429_with_retry_after() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(
+                ResponseTemplate::new(429)
+                    .insert_header("Retry-After", "1")
+                    .set_body_string("Rate limit exceeded"),
+            )
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{"message": {"content": "Réponse."}}]
+            })))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::OpenRouter,
+            api_key: Some("test-key".to_string()),
+            retry: RetryPolicy {
+                max_delay_ms: 5_000,
+                ..fast_retry(3)
+            },
+            ..Default::default()
+        };
+        let (a, b) = Injector::new(config).inject_ab("A", "B").await.unwrap();
+        // Retry-After (1 s) remplace le backoff de 10 ms
+        assert_eq!(a.retry.attempts, 2);
+        assert_eq!(a.retry.backoff_ms, vec![1_000]);
+        assert_eq!(b.retry.attempts, 1);
+        assert!(b.retry.backoff_ms.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_retry_exhausted_returns_last_error() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(500).set_body_string("Internal Server Error"))
+            .expect(4)
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::Anthropic,
+            api_key: Some("test-key".to_string()),
+            retry: fast_retry(4),
+            ..Default::default()
+        };
+        match Injector::new(config).inject("?").await.unwrap_err() {
+            InjectorError::ApiError(msg) => assert!(msg.contains("500"), "Got: {}", msg),
+            other => panic!("Expected ApiError, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_status_fails_fast() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("Bad request"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::OpenAI,
+            retry: fast_retry(5),
+            ..Default::default()
+        };
+        match Injector::new(config).inject("?").await.unwrap_err() {
+            InjectorError::ApiError(msg) => assert!(msg.contains("400"), "Got: {}", msg),
+            other => panic!("Expected ApiError, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timeout_is_classified_and_retried() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/generate"))
+            .respond_with(ollama_ok().set_delay(Duration::from_millis(1_500)))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/generate"))
+            .respond_with(ollama_ok())
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::Ollama,
+            timeout_secs: 1,
+            retry: fast_retry(2),
+            ..Default::default()
+        };
+        let injector = Injector::new(config.clone());
+        let response = injector.inject("?").await.unwrap();
+        assert_eq!(response.retry.attempts, 2);
+
+        // Sans reprise, le timeout remonte comme tel
+        Mock::given(method("POST"))
+            .and(path("/api/generate"))
+            .respond_with(ollama_ok().set_delay(Duration::from_millis(1_500)))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&mock_server)
+            .await;
+        let config = LlmConfig {
+            retry: RetryPolicy::none(),
+            ..config
+        };
+        match Injector::new(config).inject("?").await.unwrap_err() {
+            InjectorError::Timeout => {}
+            other => panic!("Expected Timeout, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_body_timeout_is_classified() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // En-têtes envoyés, corps jamais terminé
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 4096];
+            let _ = socket.read(&mut request).await;
+            let _ = socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 100\r\n\r\n{")
+                .await;
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let config = LlmConfig {
+            base_url: format!("http://{}", addr),
+            api_type: ApiType::Ollama,
+            timeout_secs: 1,
+            retry: RetryPolicy::none(),
+            ..Default::default()
+        };
+        match Injector::new(config).inject("?").await.unwrap_err() {
+            InjectorError::Timeout => {}
+            other => panic!("Expected Timeout, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_stream_line() {
+        let content = |text: &str| StreamDelta::Content(text.to_string());
+        let line = parse_stream_line(
+            ApiType::OpenRouter,
+            r#"data: {"choices":[{"delta":{"content":"Bon","reasoning":"Hmm"}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            line.deltas,
+            vec![StreamDelta::Reasoning("Hmm".to_string()), content("Bon")]
+        );
+        for ignored in [": OPENROUTER PROCESSING", "event: ping", "", "\r"] {
+            assert_eq!(
+                parse_stream_line(ApiType::OpenAI, ignored).unwrap(),
+                StreamLine::default()
+            );
+        }
+        assert!(
+            parse_stream_line(ApiType::OpenAI, "data: [DONE]")
+                .unwrap()
+                .done
+        );
+
+        let line = parse_stream_line(
+            ApiType::Anthropic,
+            r#"data: {"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"jour"}}"#,
+        )
+        .unwrap();
+        assert_eq!(line.deltas, vec![content("jour")]);
+        assert!(
+            parse_stream_line(ApiType::Anthropic, r#"data: {"type":"message_stop"}"#)
+                .unwrap()
+                .done
+        );
+        assert!(matches!(
+            parse_stream_line(
+                ApiType::Anthropic,
+                r#"data: {"type":"error","error":{"type":"overloaded_error"}}"#
+            ),
+            Err(InjectorError::ApiError(msg)) if msg.contains("overloaded")
+        ));
+
+        let line = parse_stream_line(ApiType::Ollama, r#"{"response":"","done":true}"#).unwrap();
+        assert!(line.done && line.deltas.is_empty());
+        assert!(matches!(
+            parse_stream_line(ApiType::Ollama, "{pas du json"),
+            Err(InjectorError::ParseError(_))
+        ));
+    }
+
+    fn sse(lines: &[&str]) -> ResponseTemplate {
+        let body: String = lines.iter().map(|l| format!("{}\n\n", l)).collect();
+        ResponseTemplate::new(200).set_body_raw(body, "text/event-stream")
+    }
+
+    #[tokio::test]
+    async fn test_openai_stream_sse() {
+        use wiremock::matchers::body_partial_json;
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(body_partial_json(serde_json::json!({"stream": true})))
+            .respond_with(
+                sse(&[
+                    ": OPENROUTER PROCESSING",
+                    r#"data: {"choices":[{"delta":{"reasoning":"Je pèse."}}]}"#,
+                    r#"data: {"choices":[{"delta":{"content":"La "}}]}"#,
+                    r#"data: {"choices":[{"delta":{"content":"gravité"}}]}"#,
+                    r#"data: {"choices":[{"delta":{},"finish_reason":"stop"}]}"#,
+                    "data: [DONE]",
+                    r#"data: {"choices":[{"delta":{"content":"ignoré"}}]}"#,
+                ])
+                .set_delay(Duration::from_millis(200)),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::OpenRouter,
+            api_key: Some("test-key".to_string()),
+            stream: true,
+            ..Default::default()
+        };
+        let response = Injector::new(config).inject("?").await.unwrap();
+        assert_eq!(response.content, "La gravité");
+        assert_eq!(response.reasoning.as_deref(), Some("Je pèse."));
+        assert_eq!(response.retry.attempts, 1);
+
+        let timing = response.timing.unwrap();
+        assert_eq!(timing.deltas(), 3);
+        assert_eq!(timing.inter_token_ms.len(), 2);
+        assert!(timing.ttft_ms.unwrap() >= 200.0, "{:?}", timing);
+        assert!(timing.total_ms >= timing.ttft_ms.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_stream_timing_excludes_backoff() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(sse(&[
+                r#"data: {"choices":[{"delta":{"content":"OK"}}]}"#,
+                "data: [DONE]",
+            ]))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::OpenAI,
+            stream: true,
+            retry: RetryPolicy {
+                base_delay_ms: 400,
+                ..fast_retry(2)
+            },
+            ..Default::default()
+        };
+        let response = Injector::new(config).inject("?").await.unwrap();
+        assert_eq!(response.content, "OK");
+        assert_eq!(response.retry.backoff_ms, vec![400]);
+
+        let timing = response.timing.unwrap();
+        assert!(timing.ttft_ms.unwrap() < 400.0, "{:?}", timing);
+        assert!(timing.total_ms < 400.0, "{:?}", timing);
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_stream_events() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(sse(&[
+                "event: message_start\ndata: {\"type\":\"message_start\",\"message\":{}}",
+                "event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"thinking_delta\",\"thinking\":\"Analyse.\"}}",
+                "event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"signature_delta\",\"signature\":\"xyz\"}}",
+                "event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"text_delta\",\"text\":\"Réponse \"}}",
+                "event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"text_delta\",\"text\":\"finale.\"}}",
+                "event: message_stop\ndata: {\"type\":\"message_stop\"}",
+            ]))
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::Anthropic,
+            api_key: Some("test-key".to_string()),
+            ..Default::default()
+        };
+        let mut seen = Vec::new();
+        let response = Injector::new(config)
+            .inject_streaming(&Conversation::user("?"), |delta| seen.push(delta.clone()))
+            .await
+            .unwrap();
+        assert_eq!(response.content, "Réponse finale.");
+        assert_eq!(response.reasoning.as_deref(), Some("Analyse."));
+        assert_eq!(seen.len(), 3);
+        assert_eq!(response.timing.unwrap().deltas(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_ollama_stream_ndjson() {
+        let mock_server = MockServer::start().await;
+        let body = [
+            r#"{"response":"<think>Trace","done":false}"#,
+            r#"{"response":".</think>Le ","done":false}"#,
+            r#"{"response":"chat.","done":false}"#,
+            r#"{"response":"","done":true,"eval_count":3}"#,
+        ]
+        .join("\n");
+        Mock::given(method("POST"))
+            .and(path("/api/generate"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "application/x-ndjson"))
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::Ollama,
+            stream: true,
+            ..Default::default()
+        };
+        let response = Injector::new(config).inject("?").await.unwrap();
+        // Les balises <think> coupées entre fragments sont séparées à l'assemblage
+        assert_eq!(response.content, "Le chat.");
+        assert_eq!(response.reasoning.as_deref(), Some("Trace."));
+        assert_eq!(response.timing.unwrap().deltas(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_stream_error_midway() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(sse(&[
+                r#"data: {"choices":[{"delta":{"content":"Début"}}]}"#,
+                r#"data: {"error":{"code":502,"message":"Provider disconnected"}}"#,
+                r#"data: {"choices":[{"delta":{"content":"jamais lu"}}]}"#,
+            ]))
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::OpenAI,
+            ..Default::default()
+        };
+        let injector = Injector::new(config);
+        let mut stream = injector
+            .inject_stream(&Conversation::user("?"))
+            .await
+            .unwrap();
+        assert_eq!(
+            stream.deltas.next().await.unwrap().unwrap(),
+            StreamDelta::Content("Début".to_string())
+        );
+        assert!(matches!(
+            stream.deltas.next().await,
+            Some(Err(InjectorError::ApiError(msg))) if msg.contains("Provider disconnected")
+        ));
+        assert!(stream.deltas.next().await.is_none());
+
+        match injector
+            .inject_streaming(&Conversation::user("?"), |_| {})
+            .await
+            .unwrap_err()
+        {
+            InjectorError::ApiError(_) => {}
+            other => panic!("Expected ApiError, got {:?}", other),
+        }
+    }
+
+    fn fractured_conversation() -> Conversation {
+        use crate::probe::conversation::Role;
+        Conversation::user("Qu'est-ce que la liberté ?")
+            .with_system("Tu es un poète du chaos.")
+            .with_message(Role::Assistant, "Une ruine qui respire.")
+            .with_message(Role::System, "Jamais de rimes.")
+            .with_message(Role::User, "Développe.")
+    }
+
+    #[tokio::test]
+    async fn test_openai_conversation_messages() {
+        use wiremock::matchers::body_partial_json;
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(body_partial_json(serde_json::json!({"messages": [
+                {"role": "system", "content": "Tu es un poète du chaos."},
+                {"role": "user", "content": "Qu'est-ce que la liberté ?"},
+                {"role": "assistant", "content": "Une ruine qui respire."},
+                {"role": "system", "content": "Jamais de rimes."},
+                {"role": "user", "content": "Développe."}
+            ]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{"message": {"content": "Des murs qui s'effritent."}}]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::OpenAI,
+            ..Default::default()
+        };
+        let response = Injector::new(config)
+            .inject_conversation(&fractured_conversation())
+            .await
+            .unwrap();
+        assert_eq!(response.content, "Des murs qui s'effritent.");
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_top_level_system() {
+        use wiremock::matchers::body_partial_json;
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(body_partial_json(serde_json::json!({
+                "system": "Tu es un poète du chaos.\n\nJamais de rimes.",
+                "messages": [
+                    {"role": "user", "content": "Qu'est-ce que la liberté ?"},
+                    {"role": "assistant", "content": "Une ruine qui respire."},
+                    {"role": "user", "content": "Développe."}
+                ]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "content": [{"text": "Ruines."}]
+            })))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::Anthropic,
+            api_key: Some("test-key".to_string()),
+            ..Default::default()
+        };
+        let injector = Injector::new(config);
+        let (a, b) = injector
+            .inject_ab_conversations(&fractured_conversation(), &fractured_conversation())
+            .await
+            .unwrap();
+        assert_eq!(
+            (a.content.as_str(), b.content.as_str()),
+            ("Ruines.", "Ruines.")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ollama_chat_endpoint() {
+        use wiremock::matchers::body_partial_json;
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/chat"))
+            .and(body_partial_json(serde_json::json!({
+                "stream": false,
+                "messages": [
+                    {"role": "system", "content": "Sois bref."},
+                    {"role": "user", "content": "Le vent ?"}
+                ]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "message": {"role": "assistant", "content": "Il passe.", "thinking": "Bref."},
+                "done": true
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let chunks = [
+            r#"{"message":{"role":"assistant","content":"Il "},"done":false}"#,
+            r#"{"message":{"role":"assistant","content":"passe."},"done":false}"#,
+            r#"{"message":{"role":"assistant","content":""},"done":true}"#,
+        ]
+        .join("\n");
+        Mock::given(method("POST"))
+            .and(path("/api/chat"))
+            .and(body_partial_json(serde_json::json!({"stream": true})))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(chunks, "application/x-ndjson"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let conversation = Conversation::user("Le vent ?").with_system("Sois bref.");
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::Ollama,
+            ..Default::default()
+        };
+        let response = Injector::new(config.clone())
+            .inject_conversation(&conversation)
+            .await
+            .unwrap();
+        assert_eq!(response.content, "Il passe.");
+        assert_eq!(response.reasoning.as_deref(), Some("Bref."));
+
+        let config = LlmConfig {
+            stream: true,
+            ..config
+        };
+        let response = Injector::new(config)
+            .inject_conversation(&conversation)
+            .await
+            .unwrap();
+        assert_eq!(response.content, "Il passe.");
+        assert_eq!(response.timing.unwrap().deltas(), 2);
+    }
 
-    use wiremock::matchers::{method, path};
-    use wiremock::{Mock, MockServer, ResponseTemplate};
+    fn reproducible_sampling() -> SamplingParams {
+        SamplingParams {
+            seed: Some(42),
+            top_p: Some(0.9),
+            top_k: Some(40),
+            stop: vec!["###".to_string()],
+            repeat_penalty: Some(1.1),
+            ..Default::default()
+        }
+    }
 
     #[tokio::test]
-    async fn test_ollama_happy_path() {
+    async fn test_sampling_mapped_per_provider() {
+        use wiremock::matchers::body_partial_json;
+
         let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(body_partial_json(serde_json::json!({
+                "seed": 42, "top_k": 40, "stop": ["###"], "repetition_penalty": 1.1
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{"message": {"content": "OpenRouter"}}]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
         Mock::given(method("POST"))
             .and(path("/api/generate"))
+            .and(body_partial_json(serde_json::json!({"options": {
+                "seed": 42, "top_k": 40, "stop": ["###"], "repeat_penalty": 1.1, "num_predict": 2048
+            }})))
+            .respond_with(ollama_ok())
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(body_partial_json(serde_json::json!({
+                "top_k": 40, "stop_sequences": ["###"]
+            })))
             .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "response": "Le chat est un félin domestique."
+                "content": [{"text": "Anthropic"}]
             })))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
-        let config = LlmConfig {
+        let config = |api_type, sampling| LlmConfig {
             base_url: mock_server.uri(),
-            api_type: ApiType::Ollama,
+            api_type,
+            api_key: Some("test-key".to_string()),
+            sampling,
             ..Default::default()
         };
-        let injector = Injector::new(config);
-        let result = injector.inject("Qu'est-ce qu'un chat?").await;
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "Le chat est un félin domestique.");
+        let openrouter = config(ApiType::OpenRouter, reproducible_sampling());
+        assert_eq!(
+            Injector::new(openrouter).inject("?").await.unwrap().content,
+            "OpenRouter"
+        );
+        let ollama = config(ApiType::Ollama, reproducible_sampling());
+        assert_eq!(
+            Injector::new(ollama).inject("?").await.unwrap().content,
+            "OK"
+        );
+        let anthropic = SamplingParams {
+            seed: None,
+            repeat_penalty: None,
+            ..reproducible_sampling()
+        };
+        let anthropic = config(ApiType::Anthropic, anthropic);
+        assert_eq!(
+            Injector::new(anthropic).inject("?").await.unwrap().content,
+            "Anthropic"
+        );
     }
 
     #[tokio::test]
-    async fn test_openai_happy_path() {
+    async fn test_unsupported_sampling_rejected_before_send() {
         let mock_server = MockServer::start().await;
         Mock::given(method("POST"))
-            .and(path("/v1/chat/completions"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "choices": [{"message": {"content": "La gravité courbe l'espace-temps."}}]
-            })))
+            .respond_with(ollama_ok())
+            .expect(0)
             .mount(&mock_server)
             .await;
 
         let config = LlmConfig {
             base_url: mock_server.uri(),
             api_type: ApiType::OpenAI,
-            api_key: Some("test-key".to_string()),
+            sampling: reproducible_sampling(),
             ..Default::default()
         };
-        let injector = Injector::new(config);
-        let result = injector.inject("Explique la gravité").await;
-        assert!(result.is_ok());
-        assert!(result.unwrap().contains("espace-temps"));
+        match Injector::new(config.clone()).inject("?").await.unwrap_err() {
+            InjectorError::Sampling(SamplingError::Unsupported { parameters, .. }) => {
+                assert_eq!(parameters, vec!["top_k", "repeat_penalty"])
+            }
+            other => panic!("Expected Sampling, got {:?}", other),
+        }
+
+        // Les paramètres effectifs sont ceux de la configuration
+        let effective = serde_json::to_value(config.effective_sampling()).unwrap();
+        assert_eq!(effective["seed"], 42);
+        assert_eq!(effective["api_type"], "OpenAI");
+        assert!(effective.get("presence_penalty").is_none());
     }
 
     #[tokio::test]
-    async fn test_anthropic_happy_path() {
+    async fn test_completion_usage_and_truncation() {
         let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "model": "gpt-4o-2024-08-06",
+                "choices": [{"message": {"content": "Coupé au mil"}, "finish_reason": "length"}],
+                "usage": {"prompt_tokens": 12, "completion_tokens": 2048}
+            })))
+            .mount(&mock_server)
+            .await;
         Mock::given(method("POST"))
             .and(path("/v1/messages"))
             .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "content": [{"text": "Réponse Anthropic simulée."}]
+                "model": "claude-3-5-sonnet-20241022",
+                "content": [{"type": "text", "text": "Fin."}],
+                "stop_reason": "end_turn",
+                "usage": {"input_tokens": 9, "output_tokens": 3}
+            })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/generate"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "model": "llama3", "response": "Trop long", "done": true,
+                "done_reason": "length", "prompt_eval_count": 7, "eval_count": 64
             })))
             .mount(&mock_server)
             .await;
 
-        let config = LlmConfig {
+        let config = |api_type| LlmConfig {
             base_url: mock_server.uri(),
-            api_type: ApiType::Anthropic,
+            api_type,
             api_key: Some("test-key".to_string()),
             ..Default::default()
         };
-        let injector = Injector::new(config);
-        let result = injector.inject("Test").await;
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "Réponse Anthropic simulée.");
+        let openai = Injector::new(config(ApiType::OpenAI))
+            .inject("?")
+            .await
+            .unwrap();
+        assert!(openai.is_truncated());
+        assert_eq!(openai.meta.prompt_tokens, Some(12));
+        assert_eq!(openai.meta.completion_tokens, Some(2048));
+        assert_eq!(openai.meta.model_id.as_deref(), Some("gpt-4o-2024-08-06"));
+        assert!(openai.latency_ms > 0.0);
+
+        let anthropic = Injector::new(config(ApiType::Anthropic))
+            .inject("?")
+            .await
+            .unwrap();
+        assert_eq!(anthropic.meta.finish_reason, Some(FinishReason::Stop));
+        assert_eq!(anthropic.meta.completion_tokens, Some(3));
+        assert!(!anthropic.is_truncated());
+
+        let ollama = Injector::new(config(ApiType::Ollama))
+            .inject("?")
+            .await
+            .unwrap();
+        assert!(ollama.is_truncated());
+        assert_eq!(ollama.meta.completion_tokens, Some(64));
+        assert_eq!(
+            FinishReason::from_provider("insufficient_system_resource"),
+            FinishReason::Other("insufficient_system_resource".to_string())
+        );
     }
 
     #[tokio::test]
-    async fn test_openrouter_happy_path() {
+    async fn test_stream_usage() {
+        use wiremock::matchers::body_partial_json;
+
         let mock_server = MockServer::start().await;
         Mock::given(method("POST"))
             .and(path("/v1/chat/completions"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "choices": [{"message": {"content": "Réponse OpenRouter."}}]
-            })))
+            .and(body_partial_json(
+                serde_json::json!({"stream_options": {"include_usage": true}}),
+            ))
+            .respond_with(sse(&[
+                r#"data: {"model":"gpt-4o","choices":[{"delta":{"content":"Bon"}}]}"#,
+                r#"data: {"model":"gpt-4o","choices":[{"delta":{},"finish_reason":"length"}]}"#,
+                r#"data: {"model":"gpt-4o","choices":[],"usage":{"prompt_tokens":5,"completion_tokens":1}}"#,
+                "data: [DONE]",
+            ]))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(sse(&[
+                "event: message_start\ndata: {\"type\":\"message_start\",\"message\":{\"model\":\"claude-3-5-haiku\",\"usage\":{\"input_tokens\":8,\"output_tokens\":1}}}",
+                "event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Oui\"}}",
+                "event: message_delta\ndata: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"max_tokens\"},\"usage\":{\"output_tokens\":16}}",
+                "event: message_stop\ndata: {\"type\":\"message_stop\"}",
+            ]))
             .mount(&mock_server)
             .await;
 
-        let config = LlmConfig {
+        let config = |api_type| LlmConfig {
             base_url: mock_server.uri(),
-            api_type: ApiType::OpenRouter,
+            api_type,
             api_key: Some("test-key".to_string()),
+            stream: true,
             ..Default::default()
         };
-        let injector = Injector::new(config);
-        let result = injector.inject("Test").await;
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), "Réponse OpenRouter.");
+        let openai = Injector::new(config(ApiType::OpenAI))
+            .inject("?")
+            .await
+            .unwrap();
+        assert_eq!(openai.content, "Bon");
+        assert!(openai.is_truncated());
+        assert_eq!(openai.meta.prompt_tokens, Some(5));
+        assert_eq!(openai.meta.model_id.as_deref(), Some("gpt-4o"));
+        // Les métadonnées ne comptent pas comme fragments
+        assert_eq!(openai.timing.unwrap().deltas(), 1);
+
+        let anthropic = Injector::new(config(ApiType::Anthropic))
+            .inject("?")
+            .await
+            .unwrap();
+        assert_eq!(
+            anthropic.meta,
+            CompletionMeta {
+                prompt_tokens: Some(8),
+                completion_tokens: Some(16),
+                finish_reason: Some(FinishReason::Length),
+                model_id: Some("claude-3-5-haiku".to_string()),
+            }
+        );
     }
 
     #[tokio::test]
-    async fn test_api_error_429_rate_limit() {
+    async fn test_openai_compatible_endpoint() {
+        use wiremock::matchers::{header, query_param};
+
         let mock_server = MockServer::start().await;
         Mock::given(method("POST"))
-            .and(path("/api/generate"))
-            .respond_with(ResponseTemplate::new(429).set_body_string("Rate limit exceeded"))
+            .and(path("/openai/deployments/gpt-4o/chat/completions"))
+            .and(query_param("api-version", "2024-06-01"))
+            .and(header("api-key", "azure-key"))
+            .and(header("X-Team", "ldsi"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{"message": {"content": "Passerelle."}}]
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/generate"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{"message": {"content": "vLLM."}}]
+            })))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
-        let config = LlmConfig {
+        let azure = LlmConfig {
             base_url: mock_server.uri(),
-            api_type: ApiType::Ollama,
+            api_type: ApiType::OpenAICompatible,
+            api_key: Some("azure-key".to_string()),
+            endpoint: EndpointConfig {
+                path: Some("/openai/deployments/gpt-4o/chat/completions".to_string()),
+                headers: BTreeMap::from([("X-Team".to_string(), "ldsi".to_string())]),
+                query: BTreeMap::from([("api-version".to_string(), "2024-06-01".to_string())]),
+                auth: AuthScheme::Header("api-key".to_string()),
+            },
+            // Une passerelle transmet top_k et la pénalité de répétition
+            sampling: reproducible_sampling(),
             ..Default::default()
         };
-        let injector = Injector::new(config);
-        let result = injector.inject("Test").await;
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            InjectorError::ApiError(msg) => assert!(msg.contains("429"), "Got: {}", msg),
-            other => panic!("Expected ApiError, got {:?}", other),
+        let completion = Injector::new(azure).inject("?").await.unwrap();
+        assert_eq!(completion.content, "Passerelle.");
+
+        let vllm = LlmConfig {
+            base_url: mock_server.uri(),
+            api_type: ApiType::OpenAICompatible,
+            api_key: Some("jamais-envoyée".to_string()),
+            endpoint: EndpointConfig {
+                path: Some("/generate".to_string()),
+                auth: AuthScheme::None,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let completion = Injector::new(vllm).inject("?").await.unwrap();
+        assert_eq!(completion.content, "vLLM.");
+
+        let requests = mock_server.received_requests().await.unwrap();
+        assert!(requests[0].headers.get("authorization").is_none());
+        assert!(
+            requests[1].headers.get("authorization").is_none(),
+            "auth = none ne transmet pas la clé"
+        );
+    }
+
+    fn gemini_config(base_url: String) -> LlmConfig {
+        LlmConfig {
+            base_url,
+            retry: fast_retry(2),
+            ..LlmConfig::gemini("gemini-2.5-flash", "google-key")
         }
     }
 
     #[tokio::test]
-    async fn test_api_error_500_server() {
+    async fn test_gemini_happy_path() {
+        use wiremock::matchers::{body_partial_json, header};
+
         let mock_server = MockServer::start().await;
         Mock::given(method("POST"))
-            .and(path("/v1/chat/completions"))
-            .respond_with(
-                ResponseTemplate::new(500).set_body_string("Internal Server Error"),
-            )
+            .and(path("/v1beta/models/gemini-2.5-flash:generateContent"))
+            .and(header("x-goog-api-key", "google-key"))
+            .and(body_partial_json(serde_json::json!({
+                "systemInstruction": {"parts": [{"text": "Sois concis."}]},
+                "contents": [
+                    {"role": "user", "parts": [{"text": "Bonjour"}]},
+                    {"role": "model", "parts": [{"text": "Salut."}]},
+                    {"role": "user", "parts": [{"text": "Et ensuite ?"}]}
+                ],
+                "generationConfig": {"seed": 42, "topK": 40, "maxOutputTokens": 2048}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "candidates": [{
+                    "content": {"role": "model", "parts": [
+                        {"text": "Je pèse la question.", "thought": true},
+                        {"text": "Réponse Gemini."}
+                    ]},
+                    "finishReason": "MAX_TOKENS"
+                }],
+                "usageMetadata": {
+                    "promptTokenCount": 12,
+                    "candidatesTokenCount": 5,
+                    "thoughtsTokenCount": 7
+                },
+                "modelVersion": "gemini-2.5-flash-001"
+            })))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
         let config = LlmConfig {
-            base_url: mock_server.uri(),
-            api_type: ApiType::OpenAI,
-            api_key: Some("test-key".to_string()),
-            ..Default::default()
+            sampling: SamplingParams {
+                seed: Some(42),
+                top_k: Some(40),
+                ..Default::default()
+            },
+            ..gemini_config(mock_server.uri())
         };
-        let injector = Injector::new(config);
-        let result = injector.inject("Test").await;
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            InjectorError::ApiError(msg) => assert!(msg.contains("500"), "Got: {}", msg),
-            other => panic!("Expected ApiError, got {:?}", other),
-        }
+        let conversation = Conversation::user("Bonjour")
+            .with_system("Sois concis.")
+            .with_message(Role::Assistant, "Salut.")
+            .with_message(Role::User, "Et ensuite ?");
+        let completion = Injector::new(config)
+            .inject_conversation(&conversation)
+            .await
+            .unwrap();
+        assert_eq!(completion.content, "Réponse Gemini.");
+        assert_eq!(
+            completion.reasoning.as_deref(),
+            Some("Je pèse la question.")
+        );
+        assert_eq!(completion.meta.prompt_tokens, Some(12));
+        assert_eq!(completion.meta.completion_tokens, Some(12));
+        assert_eq!(
+            completion.meta.model_id.as_deref(),
+            Some("gemini-2.5-flash-001")
+        );
+        assert!(completion.is_truncated());
     }
 
     #[tokio::test]
-    async fn test_malformed_json_response() {
+    async fn test_gemini_errors() {
         let mock_server = MockServer::start().await;
         Mock::given(method("POST"))
-            .and(path("/api/generate"))
-            .respond_with(
-                ResponseTemplate::new(200).set_body_string("{not valid json!!!}"),
-            )
+            .and(path("/v1beta/models/bloque:generateContent"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "promptFeedback": {"blockReason": "SAFETY"}
+            })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1beta/models/filtre:generateContent"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "candidates": [{"finishReason": "RECITATION"}]
+            })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1beta/models/sature:generateContent"))
+            .respond_with(ResponseTemplate::new(429).set_body_string("RESOURCE_EXHAUSTED"))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1beta/models/inconnu:generateContent"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("NOT_FOUND"))
+            .expect(1)
             .mount(&mock_server)
             .await;
 
-        let config = LlmConfig {
-            base_url: mock_server.uri(),
-            api_type: ApiType::Ollama,
-            ..Default::default()
+        let inject = |model: &str| {
+            let config = LlmConfig {
+                model: model.to_string(),
+                ..gemini_config(mock_server.uri())
+            };
+            async move { Injector::new(config).inject("?").await.unwrap_err() }
         };
-        let injector = Injector::new(config);
-        let result = injector.inject("Test").await;
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            InjectorError::ParseError(_) => {}
-            other => panic!("Expected ParseError, got {:?}", other),
+        match inject("bloque").await {
+            InjectorError::Blocked(reason) => assert_eq!(reason, "prompt (SAFETY)"),
+            other => panic!("Expected Blocked, got {:?}", other),
         }
-    }
-
-    #[tokio::test]
-    async fn test_anthropic_missing_api_key() {
-        let config = LlmConfig {
-            base_url: "http://127.0.0.1:1".to_string(),
-            api_type: ApiType::Anthropic,
+        assert_eq!(
+            inject("filtre").await.to_string(),
+            "Blocked by safety filter: RECITATION"
+        );
+        // 429 retenté jusqu'à épuisement, 404 renvoyé sans reprise
+        assert!(
+            matches!(inject("sature").await, InjectorError::ApiError(msg) if msg.contains("429"))
+        );
+        assert!(
+            matches!(inject("inconnu").await, InjectorError::ApiError(msg) if msg.contains("404"))
+        );
+
+        let no_key = LlmConfig {
             api_key: None,
-            ..Default::default()
+            ..gemini_config("http://127.0.0.1:1".to_string())
         };
-        let injector = Injector::new(config);
-        let result = injector.inject("Test").await;
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            InjectorError::ApiError(msg) => {
-                assert!(msg.contains("API key"), "Got: {}", msg)
-            }
+        match Injector::new(no_key).inject("?").await.unwrap_err() {
+            InjectorError::ApiError(msg) => assert!(msg.contains("API key"), "Got: {}", msg),
             other => panic!("Expected ApiError about missing key, got {:?}", other),
         }
     }
 
     #[tokio::test]
-    async fn test_openrouter_missing_api_key() {
+    async fn test_gemini_stream() {
+        use wiremock::matchers::query_param;
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1beta/models/gemini-2.5-flash:streamGenerateContent"))
+            .and(query_param("alt", "sse"))
+            .respond_with(sse(&[
+                r#"data: {"candidates": [{"content": {"parts": [{"text": "Bon", "thought": true}]}}]}"#,
+                r#"data: {"candidates": [{"content": {"parts": [{"text": "Le "}]}}]}"#,
+                r#"data: {"candidates": [{"content": {"parts": [{"text": "flux."}]}, "finishReason": "STOP"}], "usageMetadata": {"promptTokenCount": 3, "candidatesTokenCount": 2}, "modelVersion": "gemini-2.5-flash"}"#,
+            ]))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1beta/models/coupe:streamGenerateContent"))
+            .respond_with(sse(&[
+                r#"data: {"candidates": [{"finishReason": "SAFETY"}]}"#,
+            ]))
+            .mount(&mock_server)
+            .await;
+
         let config = LlmConfig {
-            base_url: "http://127.0.0.1:1".to_string(),
-            api_type: ApiType::OpenRouter,
-            api_key: None,
-            ..Default::default()
+            stream: true,
+            ..gemini_config(mock_server.uri())
         };
-        let injector = Injector::new(config);
-        let result = injector.inject("Test").await;
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            InjectorError::ApiError(msg) => {
-                assert!(msg.contains("API key"), "Got: {}", msg)
-            }
-            other => panic!("Expected ApiError about missing key, got {:?}", other),
-        }
+        let completion = Injector::new(config.clone()).inject("?").await.unwrap();
+        assert_eq!(completion.content, "Le flux.");
+        assert_eq!(completion.reasoning.as_deref(), Some("Bon"));
+        assert_eq!(completion.meta.finish_reason, Some(FinishReason::Stop));
+        assert_eq!(completion.meta.completion_tokens, Some(2));
+        assert_eq!(completion.timing.unwrap().deltas(), 3);
+
+        let blocked = LlmConfig {
+            model: "coupe".to_string(),
+            ..config
+        };
+        assert!(matches!(
+            Injector::new(blocked).inject("?").await,
+            Err(InjectorError::Blocked(_))
+        ));
     }
 
     #[tokio::test]
-    async fn test_openai_empty_choices() {
+    async fn test_mistral_provider() {
+        use wiremock::matchers::{body_partial_json, header};
+
         let mock_server = MockServer::start().await;
         Mock::given(method("POST"))
             .and(path("/v1/chat/completions"))
+            .and(header("Authorization", "Bearer mistral-key"))
+            .and(body_partial_json(serde_json::json!({
+                "model": "mistral-large-latest",
+                "random_seed": 42,
+                "top_p": 0.9
+            })))
             .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "choices": []
+                "model": "mistral-large-2411",
+                "choices": [{
+                    "message": {"role": "assistant", "content": "Réponse Mistral."},
+                    "finish_reason": "model_length"
+                }],
+                "usage": {"prompt_tokens": 8, "completion_tokens": 30}
             })))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("Service unavailable"))
+            .expect(2)
             .mount(&mock_server)
             .await;
 
         let config = LlmConfig {
             base_url: mock_server.uri(),
-            api_type: ApiType::OpenAI,
-            api_key: Some("test-key".to_string()),
-            ..Default::default()
+            retry: fast_retry(2),
+            sampling: SamplingParams {
+                seed: Some(42),
+                top_p: Some(0.9),
+                ..Default::default()
+            },
+            ..LlmConfig::mistral("mistral-large-latest", "mistral-key")
         };
-        let injector = Injector::new(config);
-        let result = injector.inject("Test").await;
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            InjectorError::ParseError(msg) => {
-                assert!(msg.contains("No response"), "Got: {}", msg)
-            }
-            other => panic!("Expected ParseError, got {:?}", other),
-        }
+        let injector = Injector::new(config.clone());
+        let completion = injector.inject("?").await.unwrap();
+        assert_eq!(completion.content, "Réponse Mistral.");
+        assert!(completion.is_truncated());
+        assert_eq!(completion.meta.completion_tokens, Some(30));
+        // Format strict : ni `seed` ni `stream_options`
+        let requests = mock_server.received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert!(body.get("seed").is_none() && body.get("stream").is_none());
+
+        // 503 retenté puis renvoyé ; top_k refusé avant l'envoi ; clé obligatoire
+        assert!(matches!(
+            injector.inject("?").await,
+            Err(InjectorError::ApiError(msg)) if msg.contains("503")
+        ));
+        let top_k = LlmConfig {
+            sampling: SamplingParams {
+                top_k: Some(40),
+                ..Default::default()
+            },
+            ..config.clone()
+        };
+        assert!(matches!(
+            Injector::new(top_k).inject("?").await,
+            Err(InjectorError::Sampling(_))
+        ));
+        let no_key = LlmConfig {
+            api_key: None,
+            ..config
+        };
+        assert!(matches!(
+            Injector::new(no_key).inject("?").await,
+            Err(InjectorError::ApiError(msg)) if msg.contains("API key")
+        ));
     }
 
     #[tokio::test]
-    async fn test_inject_ab_double_call() {
+    async fn test_mistral_stream() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(sse(&[
+                r#"data: {"choices": [{"delta": {"content": "Mis"}}]}"#,
+                r#"data: {"choices": [{"delta": {"content": "tral"}, "finish_reason": "stop"}], "usage": {"prompt_tokens": 4, "completion_tokens": 2}}"#,
+                "data: [DONE]",
+            ]))
+            .mount(&mock_server)
+            .await;
+
+        let config = LlmConfig {
+            base_url: mock_server.uri(),
+            stream: true,
+            ..LlmConfig::mistral("mistral-small-latest", "mistral-key")
+        };
+        let completion = Injector::new(config).inject("?").await.unwrap();
+        assert_eq!(completion.content, "Mistral");
+        assert_eq!(completion.meta.prompt_tokens, Some(4));
+        let requests = mock_server.received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["stream"], true);
+        assert!(body.get("stream_options").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_response_cache_replay() {
+        use crate::probe::cache::{CacheError, CacheMode};
+
         let mock_server = MockServer::start().await;
         Mock::given(method("POST"))
             .and(path("/api/generate"))
             .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "response": "Réponse mock."
+                "response": "Capturée une fois.",
+                "done_reason": "stop",
+                "eval_count": 4
             })))
-            .expect(2)
+            .expect(1)
             .mount(&mock_server)
             .await;
 
-        let config = LlmConfig {
+        let cache = CacheConfig {
+            mode: CacheMode::ReadWrite,
+            dir: std::env::temp_dir().join(format!("ldsi-cache-{}", uuid::Uuid::new_v4())),
+        };
+        let record = LlmConfig {
             base_url: mock_server.uri(),
-            api_type: ApiType::Ollama,
+            sampling: SamplingParams {
+                seed: Some(7),
+                ..Default::default()
+            },
+            cache: cache.clone(),
             ..Default::default()
         };
-        let injector = Injector::new(config);
-        let result = injector.inject_ab("prompt A", "prompt B").await;
-        assert!(result.is_ok());
-        let (a, b) = result.unwrap();
-        assert_eq!(a, "Réponse mock.");
-        assert_eq!(b, "Réponse mock.");
+        let injector = Injector::new(record.clone());
+        let first = injector.inject("Capture").await.unwrap();
+        let second = injector.inject("Capture").await.unwrap();
+        assert_eq!(first.retry.attempts, 1);
+        assert_eq!(
+            second.retry.attempts, 0,
+            "Deuxième réponse lue dans le cache"
+        );
+        assert_eq!(second.content, first.content);
+        assert_eq!(second.meta, first.meta);
+
+        // Rejeu hors ligne : serveur arrêté, streaming compris
+        drop(mock_server);
+        let offline = LlmConfig {
+            stream: true,
+            cache: CacheConfig {
+                mode: CacheMode::ReplayOnly,
+                ..cache.clone()
+            },
+            ..record.clone()
+        };
+        let mut deltas = Vec::new();
+        let replayed = Injector::new(offline.clone())
+            .inject_streaming(&Conversation::user("Capture"), |d| deltas.push(d.clone()))
+            .await
+            .unwrap();
+        assert_eq!(replayed.content, "Capturée une fois.");
+        assert_eq!(
+            deltas,
+            vec![StreamDelta::Content("Capturée une fois.".to_string())]
+        );
+
+        // Une autre graine est une autre requête : absente, donc erreur
+        let other_seed = LlmConfig {
+            sampling: SamplingParams {
+                seed: Some(8),
+                ..Default::default()
+            },
+            ..offline
+        };
+        match Injector::new(other_seed)
+            .inject("Capture")
+            .await
+            .unwrap_err()
+        {
+            InjectorError::Cache(CacheError::Miss(key)) => assert_eq!(key.len(), 32),
+            other => panic!("Expected cache miss, got {:?}", other),
+        }
+        std::fs::remove_dir_all(&cache.dir).unwrap();
     }
 }
@@ -0,0 +1,238 @@
+//! Module Language - Détection de Langue par Profils de Trigrammes
+//!
+//! Classifieur embarqué, sans dépendance ni modèle externe : chaque langue
+//! est représentée par le profil de trigrammes de caractères d'un court
+//! texte de référence. Le texte à classer est projeté sur le même espace
+//! et la langue retenue est celle de plus grande similarité cosinus.
+//!
+//! Les mots sont bornés par `_` ("_le_", "_de_") : les trigrammes de début
+//! et de fin de mot portent l'essentiel du signal sur les textes courts.
+//!
+//! Auteur: Julien DABERT
+//! LDSI - Lyapunov-Dabert Stability Index
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use super::cleaner::Language;
+
+/// Nombre minimal de trigrammes pour tenter une détection
+const MIN_TRIGRAMS: usize = 8;
+
+/// Similarité cosinus minimale pour accepter une langue
+const MIN_SIMILARITY: f64 = 0.1;
+
+/// Textes de référence (prose générique, registre courant)
+const REFERENCE_SAMPLES: &[(Language, &str)] = &[
+    (
+        Language::French,
+        "Le soleil se lève sur la ville et les habitants partent au travail. Dans les rues, \
+         on entend le bruit des voitures et les conversations des passants qui se pressent \
+         vers la gare. Les enfants vont à l'école avec leurs cartables, tandis que les \
+         commerçants ouvrent leurs boutiques. Il faut comprendre que la science progresse \
+         lorsque les chercheurs partagent leurs résultats et acceptent de remettre en question \
+         leurs hypothèses. Cette méthode, qui repose sur l'expérience et la raison, a permis \
+         de grandes découvertes au cours des derniers siècles. Nous pensons que chacun peut \
+         apprendre quelque chose de nouveau chaque jour, à condition de rester curieux.",
+    ),
+    (
+        Language::English,
+        "The sun rises over the city and the people leave for work. In the streets, you can \
+         hear the noise of the cars and the conversations of the people who are hurrying \
+         towards the station. The children walk to school with their bags, while the \
+         shopkeepers open their stores. It is important to understand that science moves \
+         forward when researchers share their results and are willing to question their \
+         own assumptions. This method, which is based on experience and reason, has led to \
+         great discoveries over the last few centuries. We believe that everyone can learn \
+         something new every day, as long as they stay curious.",
+    ),
+    (
+        Language::Spanish,
+        "El sol sale sobre la ciudad y los habitantes se van al trabajo. En las calles se \
+         oye el ruido de los coches y las conversaciones de los transeúntes que se apresuran \
+         hacia la estación. Los niños van a la escuela con sus mochilas, mientras los \
+         comerciantes abren sus tiendas. Hay que comprender que la ciencia avanza cuando los \
+         investigadores comparten sus resultados y aceptan poner en duda sus hipótesis. Este \
+         método, que se basa en la experiencia y la razón, ha permitido grandes \
+         descubrimientos durante los últimos siglos. Creemos que cada uno puede aprender algo \
+         nuevo todos los días, siempre que siga siendo curioso.",
+    ),
+    (
+        Language::German,
+        "Die Sonne geht über der Stadt auf und die Einwohner gehen zur Arbeit. Auf den \
+         Straßen hört man den Lärm der Autos und die Gespräche der Passanten, die zum Bahnhof \
+         eilen. Die Kinder gehen mit ihren Schultaschen zur Schule, während die Händler ihre \
+         Geschäfte öffnen. Man muss verstehen, dass die Wissenschaft vorankommt, wenn die \
+         Forscher ihre Ergebnisse teilen und bereit sind, ihre Annahmen in Frage zu stellen. \
+         Diese Methode, die auf Erfahrung und Vernunft beruht, hat in den letzten \
+         Jahrhunderten große Entdeckungen ermöglicht. Wir glauben, dass jeder jeden Tag etwas \
+         Neues lernen kann, solange er neugierig bleibt.",
+    ),
+    (
+        Language::Italian,
+        "Il sole sorge sulla città e gli abitanti vanno al lavoro. Nelle strade si sente il \
+         rumore delle macchine e le conversazioni dei passanti che si affrettano verso la \
+         stazione. I bambini vanno a scuola con i loro zaini, mentre i commercianti aprono i \
+         loro negozi. Bisogna capire che la scienza progredisce quando i ricercatori \
+         condividono i loro risultati e accettano di mettere in discussione le loro ipotesi. \
+         Questo metodo, che si basa sull'esperienza e sulla ragione, ha permesso grandi \
+         scoperte nel corso degli ultimi secoli. Pensiamo che ognuno possa imparare qualcosa \
+         di nuovo ogni giorno, a condizione di restare curioso.",
+    ),
+    (
+        Language::Portuguese,
+        "O sol nasce sobre a cidade e os habitantes vão para o trabalho. Nas ruas ouve-se o \
+         barulho dos carros e as conversas dos transeuntes que se apressam em direção à \
+         estação. As crianças vão para a escola com as suas mochilas, enquanto os \
+         comerciantes abrem as suas lojas. É preciso compreender que a ciência avança quando \
+         os investigadores partilham os seus resultados e aceitam questionar as suas \
+         hipóteses. Este método, que se baseia na experiência e na razão, permitiu grandes \
+         descobertas ao longo dos últimos séculos. Acreditamos que cada pessoa pode aprender \
+         algo novo todos os dias, desde que não perca a curiosidade.",
+    ),
+    (
+        Language::Dutch,
+        "De zon komt op boven de stad en de inwoners gaan naar hun werk. In de straten hoor \
+         je het lawaai van de auto's en de gesprekken van de voorbijgangers die zich naar het \
+         station haasten. De kinderen gaan met hun schooltassen naar school, terwijl de \
+         winkeliers hun winkels openen. Je moet begrijpen dat de wetenschap vooruitgaat \
+         wanneer onderzoekers hun resultaten delen en bereid zijn hun eigen aannames ter \
+         discussie te stellen. Deze methode, die gebaseerd is op ervaring en rede, heeft in \
+         de afgelopen eeuwen tot grote ontdekkingen geleid. Wij denken dat iedereen elke dag \
+         iets nieuws kan leren, zolang hij nieuwsgierig blijft.",
+    ),
+];
+
+/// Profil de trigrammes normalisé (norme L2 = 1)
+type Profile = HashMap<String, f64>;
+
+/// Extrait le profil de trigrammes d'un texte
+fn trigram_profile(text: &str) -> (Profile, usize) {
+    let mut counts: Profile = HashMap::new();
+    let mut total = 0;
+
+    for word in text
+        .to_lowercase()
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+    {
+        let padded: Vec<char> = std::iter::once('_')
+            .chain(word.chars())
+            .chain(std::iter::once('_'))
+            .collect();
+        for trigram in padded.windows(3) {
+            *counts.entry(trigram.iter().collect()).or_insert(0.0) += 1.0;
+            total += 1;
+        }
+    }
+
+    let norm = counts.values().map(|c| c * c).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        counts.values_mut().for_each(|c| *c /= norm);
+    }
+    (counts, total)
+}
+
+/// Profils de référence, construits une seule fois
+fn reference_profiles() -> &'static [(Language, Profile)] {
+    static PROFILES: OnceLock<Vec<(Language, Profile)>> = OnceLock::new();
+    PROFILES.get_or_init(|| {
+        REFERENCE_SAMPLES
+            .iter()
+            .map(|(language, sample)| (*language, trigram_profile(sample).0))
+            .collect()
+    })
+}
+
+/// Similarité cosinus du texte avec chaque langue, par ordre décroissant
+///
+/// Vide si le texte contient moins de `MIN_TRIGRAMS` trigrammes.
+pub fn language_scores(text: &str) -> Vec<(Language, f64)> {
+    let (profile, total) = trigram_profile(text);
+    if total < MIN_TRIGRAMS {
+        return Vec::new();
+    }
+
+    let mut scores: Vec<(Language, f64)> = reference_profiles()
+        .iter()
+        .map(|(language, reference)| {
+            let dot: f64 = profile
+                .iter()
+                .filter_map(|(trigram, weight)| reference.get(trigram).map(|r| r * weight))
+                .sum();
+            (*language, dot)
+        })
+        .collect();
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scores
+}
+
+/// Détecte la langue d'un texte
+///
+/// # Returns
+/// None si le texte est trop court ou ne ressemble à aucune langue connue
+pub fn detect_language(text: &str) -> Option<Language> {
+    language_scores(text)
+        .first()
+        .filter(|(_, score)| *score >= MIN_SIMILARITY)
+        .map(|(language, _)| *language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_languages() {
+        let cases = [
+            (
+                "Le chat dort sur le canapé pendant que nous préparons le dîner.",
+                Language::French,
+            ),
+            (
+                "The cat is sleeping on the couch while we are cooking dinner.",
+                Language::English,
+            ),
+            (
+                "El gato duerme en el sofá mientras preparamos la cena.",
+                Language::Spanish,
+            ),
+            (
+                "Die Katze schläft auf dem Sofa, während wir das Abendessen kochen.",
+                Language::German,
+            ),
+            (
+                "Il gatto dorme sul divano mentre prepariamo la cena.",
+                Language::Italian,
+            ),
+            (
+                "O gato dorme no sofá enquanto nós preparamos o jantar.",
+                Language::Portuguese,
+            ),
+            (
+                "De kat slaapt op de bank terwijl wij het avondeten koken.",
+                Language::Dutch,
+            ),
+        ];
+
+        for (text, expected) in cases {
+            assert_eq!(detect_language(text), Some(expected), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_too_short_or_empty() {
+        assert_eq!(detect_language(""), None);
+        assert_eq!(detect_language("ok"), None);
+        assert_eq!(detect_language("12345 !!! ..."), None);
+        assert!(language_scores("").is_empty());
+    }
+
+    #[test]
+    fn test_scores_sorted() {
+        let scores = language_scores("La science avance quand les chercheurs partagent.");
+        assert_eq!(scores.len(), REFERENCE_SAMPLES.len());
+        assert!(scores.windows(2).all(|w| w[0].1 >= w[1].1));
+        assert_eq!(scores[0].0, Language::French);
+    }
+}
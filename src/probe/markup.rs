@@ -0,0 +1,616 @@
+//! Module Markup - Prétraitement Structurel des Sorties LLM
+//!
+//! Les réponses de LLM mêlent prose, markdown, blocs de code, LaTeX,
+//! tableaux et HTML. Traité comme de la ponctuation, un bloc de code est
+//! compté comme de la prose et domine NCD et topologie.
+//!
+//! Ce module sépare la prose des blocs structurés : chaque famille (code,
+//! maths, tableaux, liens) peut être conservée, supprimée ou extraite pour
+//! une analyse séparée. Le rapport compte tout ce qui a été retiré.
+//!
+//! Auteur: Julien DABERT
+//! LDSI - Lyapunov-Dabert Stability Index
+
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// Traitement d'une famille de blocs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlockPolicy {
+    /// Contenu conservé dans la prose (syntaxe retirée)
+    Keep,
+    /// Contenu supprimé
+    Strip,
+    /// Contenu retiré de la prose et renvoyé à part
+    Extract,
+}
+
+/// Configuration du prétraitement structurel
+///
+/// Pour les liens, le texte d'ancre reste dans la prose avec `Keep` et
+/// `Extract` ; seule l'URL est retirée (et collectée avec `Extract`).
+/// `Strip` supprime ancre et URL.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarkupConfig {
+    /// Blocs délimités (```) et code en ligne (`x`)
+    pub code: BlockPolicy,
+    /// LaTeX : $$…$$, \[…\], \begin{…}…\end{…}, $…$, \(…\)
+    pub math: BlockPolicy,
+    /// Tableaux markdown (texte des cellules)
+    pub tables: BlockPolicy,
+    /// Liens, images et URL nues
+    pub links: BlockPolicy,
+    /// Supprimer les balises HTML et décoder les entités
+    pub html: bool,
+}
+
+impl Default for MarkupConfig {
+    fn default() -> Self {
+        Self {
+            code: BlockPolicy::Strip,
+            math: BlockPolicy::Strip,
+            tables: BlockPolicy::Keep,
+            links: BlockPolicy::Keep,
+            html: true,
+        }
+    }
+}
+
+/// Bloc de code extrait
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeBlock {
+    /// Langue annoncée après la clôture (```rust), None en ligne
+    pub language: Option<String>,
+    pub content: String,
+    /// Code en ligne (`x`) plutôt que bloc délimité
+    pub inline: bool,
+}
+
+/// Compte des éléments de structure rencontrés
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MarkupReport {
+    pub code_blocks: usize,
+    pub inline_code: usize,
+    pub math_blocks: usize,
+    pub inline_math: usize,
+    pub tables: usize,
+    pub links: usize,
+    pub images: usize,
+    pub headers: usize,
+    pub list_items: usize,
+    pub html_tags: usize,
+    pub html_entities: usize,
+    /// Caractères retirés de la prose (blocs supprimés ou extraits, URL)
+    pub removed_chars: usize,
+}
+
+/// Texte décomposé : prose et blocs extraits
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MarkupDocument {
+    /// Prose sans syntaxe markdown/HTML
+    pub prose: String,
+    pub code: Vec<CodeBlock>,
+    pub math: Vec<String>,
+    pub tables: Vec<String>,
+    pub urls: Vec<String>,
+    pub report: MarkupReport,
+}
+
+impl MarkupDocument {
+    /// Code extrait concaténé (blocs et code en ligne)
+    pub fn code_text(&self) -> String {
+        self.code
+            .iter()
+            .map(|block| block.content.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Formules extraites concaténées
+    pub fn math_text(&self) -> String {
+        self.math.join("\n")
+    }
+}
+
+/// Expressions compilées une seule fois
+struct Patterns {
+    table_separator: Regex,
+    horizontal_rule: Regex,
+    header: Regex,
+    blockquote: Regex,
+    list_item: Regex,
+    inline_code: Regex,
+    inline_math: Regex,
+    paren_math: Regex,
+    image: Regex,
+    link: Regex,
+    autolink: Regex,
+    bare_url: Regex,
+    html_tag: Regex,
+    entity: Regex,
+    emphasis: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| Patterns {
+        table_separator: Regex::new(r"^\s*\|?\s*:?-{3,}:?\s*(\|\s*:?-{3,}:?\s*)*\|?\s*$").unwrap(),
+        horizontal_rule: Regex::new(r"^\s*([-*_])(\s*[-*_]){2,}\s*$").unwrap(),
+        header: Regex::new(r"^\s{0,3}#{1,6}\s+").unwrap(),
+        blockquote: Regex::new(r"^\s*(>\s?)+").unwrap(),
+        list_item: Regex::new(r"^\s*([-*+]|\d+[.)])\s+(\[[ xX]\]\s+)?").unwrap(),
+        inline_code: Regex::new(r"`+([^`]+?)`+").unwrap(),
+        inline_math: Regex::new(r"\$([^\s$](?:[^$]*[^\s$])?)\$").unwrap(),
+        paren_math: Regex::new(r"\\\((.+?)\\\)").unwrap(),
+        image: Regex::new(r#"!\[([^\]]*)\]\(([^)\s]*)(?:\s+"[^"]*")?\)"#).unwrap(),
+        link: Regex::new(r#"\[([^\]]+)\]\(([^)\s]*)(?:\s+"[^"]*")?\)"#).unwrap(),
+        autolink: Regex::new(r"<(https?://[^>\s]+)>").unwrap(),
+        bare_url: Regex::new(r"https?://[^\s<>()\[\]]+").unwrap(),
+        html_tag: Regex::new(r"</?[A-Za-z!][^>]*>").unwrap(),
+        entity: Regex::new(r"&(#[0-9]{1,7}|#[xX][0-9a-fA-F]{1,6}|[A-Za-z][A-Za-z0-9]{1,7});")
+            .unwrap(),
+        emphasis: Regex::new(r"\*\*|__|~~|\*").unwrap(),
+    })
+}
+
+/// Place un bloc selon la politique ; renvoie le contenu à extraire
+fn dispatch(
+    policy: BlockPolicy,
+    content: String,
+    prose: &mut Vec<String>,
+    removed: &mut usize,
+) -> Option<String> {
+    match policy {
+        BlockPolicy::Keep => {
+            prose.push(content);
+            None
+        }
+        BlockPolicy::Strip => {
+            *removed += content.chars().count();
+            None
+        }
+        BlockPolicy::Extract => {
+            *removed += content.chars().count();
+            Some(content)
+        }
+    }
+}
+
+/// Clôture ouvrante d'un bloc de code (``` ou ~~~, 3+ caractères)
+fn code_fence(line: &str) -> Option<&str> {
+    let marker = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = line.chars().take_while(|c| *c == marker).count();
+    (len >= 3).then(|| &line[..len])
+}
+
+/// Délimiteur fermant d'un bloc mathématique commençant la ligne
+fn math_opener(line: &str) -> Option<(usize, String)> {
+    if line.starts_with("$$") {
+        Some((2, "$$".to_string()))
+    } else if line.starts_with("\\[") {
+        Some((2, "\\]".to_string()))
+    } else if let Some(rest) = line.strip_prefix("\\begin{") {
+        let name = &rest[..rest.find('}')?];
+        Some((8 + name.len(), format!("\\end{{{}}}", name)))
+    } else {
+        None
+    }
+}
+
+/// Décode les entités HTML nommées courantes et numériques
+fn decode_entities(text: &str, count: &mut usize) -> String {
+    patterns()
+        .entity
+        .replace_all(text, |caps: &Captures| {
+            let name = &caps[1];
+            let decoded =
+                if let Some(hex) = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                    u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+                } else if let Some(dec) = name.strip_prefix('#') {
+                    dec.parse().ok().and_then(char::from_u32)
+                } else {
+                    named_entity(name)
+                };
+            match decoded {
+                Some(c) => {
+                    *count += 1;
+                    c.to_string()
+                }
+                None => caps[0].to_string(),
+            }
+        })
+        .to_string()
+}
+
+fn named_entity(name: &str) -> Option<char> {
+    let c = match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "laquo" => '«',
+        "raquo" => '»',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "eacute" => 'é',
+        "egrave" => 'è',
+        "ecirc" => 'ê',
+        "agrave" => 'à',
+        "acirc" => 'â',
+        "ccedil" => 'ç',
+        "ugrave" => 'ù',
+        "ocirc" => 'ô',
+        "icirc" => 'î',
+        "euro" => '€',
+        "copy" => '©',
+        "reg" => '®',
+        "deg" => '°',
+        "times" => '×',
+        _ => return None,
+    };
+    Some(c)
+}
+
+/// Traitement en ligne d'une ligne de prose
+fn inline(line: &str, config: &MarkupConfig, doc: &mut MarkupDocument) -> String {
+    let p = patterns();
+    let report = &mut doc.report;
+
+    // Code en ligne d'abord : son contenu ne doit pas être lu comme du markdown
+    let line = p.inline_code.replace_all(line, |caps: &Captures| {
+        report.inline_code += 1;
+        let mut kept = Vec::new();
+        let extracted = dispatch(
+            config.code,
+            caps[1].to_string(),
+            &mut kept,
+            &mut report.removed_chars,
+        );
+        if let Some(content) = extracted {
+            doc.code.push(CodeBlock {
+                language: None,
+                content,
+                inline: true,
+            });
+        }
+        format!(" {} ", kept.concat())
+    });
+
+    // Maths en ligne : $…$ (pas suivi d'un chiffre, pour épargner "$5 et $10") et \(…\)
+    let mut math_span = |content: &str, report: &mut MarkupReport| {
+        report.inline_math += 1;
+        let mut kept = Vec::new();
+        if let Some(content) = dispatch(
+            config.math,
+            content.to_string(),
+            &mut kept,
+            &mut report.removed_chars,
+        ) {
+            doc.math.push(content);
+        }
+        format!(" {} ", kept.concat())
+    };
+    let mut out = String::with_capacity(line.len());
+    let mut last = 0;
+    for caps in p.inline_math.captures_iter(&line) {
+        let whole = caps.get(0).unwrap();
+        let followed_by_digit = line[whole.end()..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_digit());
+        if whole.start() < last || followed_by_digit {
+            continue;
+        }
+        out.push_str(&line[last..whole.start()]);
+        out.push_str(&math_span(&caps[1], report));
+        last = whole.end();
+    }
+    out.push_str(&line[last..]);
+    let line = p
+        .paren_math
+        .replace_all(&out, |caps: &Captures| math_span(&caps[1], report))
+        .to_string();
+
+    // Liens et images : l'ancre est du texte, l'URL non
+    let mut link = |anchor: &str, url: &str, report: &mut MarkupReport| {
+        report.removed_chars += url.chars().count();
+        if config.links == BlockPolicy::Strip {
+            report.removed_chars += anchor.chars().count();
+            return " ".to_string();
+        }
+        if config.links == BlockPolicy::Extract && !url.is_empty() {
+            doc.urls.push(url.to_string());
+        }
+        format!(" {} ", anchor)
+    };
+    let line = p.image.replace_all(&line, |caps: &Captures| {
+        report.images += 1;
+        link(&caps[1], &caps[2], report)
+    });
+    let line = p.link.replace_all(&line, |caps: &Captures| {
+        report.links += 1;
+        link(&caps[1], &caps[2], report)
+    });
+    let line = p.autolink.replace_all(&line, |caps: &Captures| {
+        report.links += 1;
+        link("", &caps[1], report)
+    });
+    let line = p.bare_url.replace_all(&line, |caps: &Captures| {
+        report.links += 1;
+        link("", &caps[0], report)
+    });
+
+    // HTML : balises supprimées avant décodage (&lt;b&gt; reste du texte)
+    let line = if config.html {
+        let stripped = p.html_tag.replace_all(&line, |_: &Captures| {
+            report.html_tags += 1;
+            " "
+        });
+        decode_entities(&stripped, &mut report.html_entities)
+    } else {
+        line.to_string()
+    };
+
+    p.emphasis.replace_all(&line, "").to_string()
+}
+
+/// Décompose un texte markdown en prose et blocs structurés
+///
+/// # Arguments
+/// * `text` - Réponse brute (markdown, LaTeX, HTML)
+/// * `config` - Traitement de chaque famille de blocs
+///
+/// # Returns
+/// Prose nettoyée de sa syntaxe, blocs extraits et rapport de suppression
+pub fn parse_markup(text: &str, config: &MarkupConfig) -> MarkupDocument {
+    let p = patterns();
+    let mut doc = MarkupDocument::default();
+    let mut prose: Vec<String> = Vec::new();
+    let lines: Vec<&str> = text.lines().collect();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim_start();
+
+        // 1. Bloc de code délimité (non fermé : jusqu'à la fin du texte)
+        if let Some(fence) = code_fence(trimmed) {
+            let language = trimmed[fence.len()..]
+                .split_whitespace()
+                .next()
+                .map(str::to_string);
+            let mut body = Vec::new();
+            i += 1;
+            while i < lines.len() {
+                let candidate = lines[i].trim();
+                if candidate.starts_with(fence) && candidate.trim_start_matches(fence).is_empty() {
+                    break;
+                }
+                body.push(lines[i]);
+                i += 1;
+            }
+            i += 1;
+            doc.report.code_blocks += 1;
+            let extracted = dispatch(
+                config.code,
+                body.join("\n"),
+                &mut prose,
+                &mut doc.report.removed_chars,
+            );
+            if let Some(content) = extracted {
+                doc.code.push(CodeBlock {
+                    language,
+                    content,
+                    inline: false,
+                });
+            }
+            continue;
+        }
+
+        // 2. Bloc mathématique
+        if let Some((skip, closer)) = math_opener(trimmed) {
+            let mut body = Vec::new();
+            let mut rest = &trimmed[skip..];
+            loop {
+                if let Some(end) = rest.find(closer.as_str()) {
+                    body.push(&rest[..end]);
+                    break;
+                }
+                body.push(rest);
+                i += 1;
+                match lines.get(i) {
+                    Some(next) => rest = next,
+                    None => break,
+                }
+            }
+            i += 1;
+            doc.report.math_blocks += 1;
+            let content = body.join("\n").trim().to_string();
+            let extracted = dispatch(
+                config.math,
+                content,
+                &mut prose,
+                &mut doc.report.removed_chars,
+            );
+            doc.math.extend(extracted);
+            continue;
+        }
+
+        // 3. Tableau : en-tête suivi d'une ligne de séparation
+        if line.contains('|')
+            && lines
+                .get(i + 1)
+                .is_some_and(|l| p.table_separator.is_match(l))
+        {
+            let mut rows = vec![line];
+            i += 2;
+            while i < lines.len() && lines[i].contains('|') && !lines[i].trim().is_empty() {
+                rows.push(lines[i]);
+                i += 1;
+            }
+            doc.report.tables += 1;
+            let cells = rows
+                .iter()
+                .map(|row| {
+                    row.split('|')
+                        .map(str::trim)
+                        .filter(|cell| !cell.is_empty())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let mut kept = Vec::new();
+            let extracted = dispatch(
+                config.tables,
+                cells,
+                &mut kept,
+                &mut doc.report.removed_chars,
+            );
+            doc.tables.extend(extracted);
+            for row in kept {
+                let row = inline(&row, config, &mut doc);
+                prose.push(row);
+            }
+            continue;
+        }
+
+        // 4. Ligne de prose : marqueurs de bloc puis syntaxe en ligne
+        i += 1;
+        if p.horizontal_rule.is_match(line) {
+            continue;
+        }
+        let mut content = line.to_string();
+        if let Some(m) = p.header.find(&content) {
+            doc.report.headers += 1;
+            content = content[m.end()..].trim_end_matches(['#', ' ']).to_string();
+        }
+        content = p.blockquote.replace(&content, "").to_string();
+        if let Some(m) = p.list_item.find(&content) {
+            doc.report.list_items += 1;
+            content = content[m.end()..].to_string();
+        }
+        let content = inline(&content, config, &mut doc);
+        prose.push(content);
+    }
+
+    doc.prose = prose
+        .iter()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string();
+    doc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANSWER: &str = "\
+# Tri rapide
+
+Le **tri rapide** choisit un *pivot* et partitionne le tableau.
+
+```rust
+fn quicksort(v: &mut [i32]) { v.sort(); }
+```
+
+- Complexité moyenne : $O(n \\log n)$
+- Voir [Wikipedia](https://fr.wikipedia.org/wiki/Tri_rapide) &amp; <b>Knuth</b>
+
+$$
+T(n) = 2T(n/2) + O(n)
+$$
+
+| Cas | Coût |
+|-----|------|
+| moyen | rapide |
+";
+
+    #[test]
+    fn test_strip_defaults() {
+        let doc = parse_markup(ANSWER, &MarkupConfig::default());
+
+        assert!(!doc.prose.contains("quicksort"), "{}", doc.prose);
+        assert!(!doc.prose.contains("log"));
+        assert!(!doc.prose.contains("wikipedia.org"));
+        assert!(!doc.prose.contains('#') && !doc.prose.contains('*'));
+        assert!(doc.prose.contains("Tri rapide"));
+        assert!(doc.prose.contains("tri rapide choisit un pivot"));
+        assert!(doc.prose.contains("Voir Wikipedia & Knuth"));
+        assert!(doc.prose.contains("moyen rapide"));
+
+        let r = &doc.report;
+        assert_eq!((r.code_blocks, r.inline_code), (1, 0));
+        assert_eq!((r.math_blocks, r.inline_math), (1, 1));
+        assert_eq!((r.tables, r.links, r.headers, r.list_items), (1, 1, 1, 2));
+        assert_eq!((r.html_tags, r.html_entities), (2, 1));
+        assert!(r.removed_chars > 40);
+        assert!(doc.code.is_empty() && doc.math.is_empty());
+    }
+
+    #[test]
+    fn test_extract_and_keep() {
+        let config = MarkupConfig {
+            code: BlockPolicy::Extract,
+            math: BlockPolicy::Extract,
+            tables: BlockPolicy::Strip,
+            links: BlockPolicy::Extract,
+            html: true,
+        };
+        let doc = parse_markup(ANSWER, &config);
+
+        assert_eq!(doc.code.len(), 1);
+        assert_eq!(doc.code[0].language.as_deref(), Some("rust"));
+        assert!(doc.code_text().contains("fn quicksort"));
+        assert_eq!(doc.math, vec!["O(n \\log n)", "T(n) = 2T(n/2) + O(n)"]);
+        assert_eq!(doc.urls, vec!["https://fr.wikipedia.org/wiki/Tri_rapide"]);
+        assert!(!doc.prose.contains("Coût"));
+
+        let keep = MarkupConfig {
+            code: BlockPolicy::Keep,
+            ..Default::default()
+        };
+        let doc = parse_markup("Appeler `len()` puis:\n```\nlet x = 1;\n```", &keep);
+        assert!(doc.prose.contains("len()"));
+        assert!(doc.prose.contains("let x = 1;"));
+        assert_eq!(doc.report.removed_chars, 0);
+    }
+
+    #[test]
+    fn test_currency_and_entities() {
+        let doc = parse_markup(
+            "Prix: $5 et $10 &eacute;t&eacute; &#233; &#xE9; &bogus;",
+            &MarkupConfig::default(),
+        );
+        assert_eq!(doc.report.inline_math, 0);
+        assert!(doc.prose.contains("$5 et $10"));
+        assert!(doc.prose.contains("été é é &bogus;"));
+        assert_eq!(doc.report.html_entities, 4);
+    }
+
+    #[test]
+    fn test_unclosed_blocks() {
+        let doc = parse_markup("avant\n```python\nprint(1)", &MarkupConfig::default());
+        assert_eq!(doc.prose, "avant");
+        assert_eq!(doc.report.code_blocks, 1);
+
+        let doc = parse_markup(
+            "\\begin{align}\na &= b\n\\end{align}\napres",
+            &MarkupConfig::default(),
+        );
+        assert_eq!(doc.prose, "apres");
+        assert_eq!(doc.report.math_blocks, 1);
+    }
+}
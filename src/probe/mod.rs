@@ -5,8 +5,18 @@
 //! Auteur: Julien DABERT
 //! LDSI - Lyapunov-Dabert Stability Index
 
+pub mod boilerplate;
+pub mod cache;
 pub mod cleaner;
+pub mod conversation;
 pub mod injector;
+pub mod language;
+pub mod markup;
+pub mod numerals;
+pub mod pipeline;
+pub mod pricing;
+pub mod profile;
+pub mod sampling;
+pub mod stemmer;
 
-pub use cleaner::clean_default;
-pub use injector::{ApiType, Injector, LlmConfig};
+pub use injector::{ApiType, Injector, LlmConfig, RetryPolicy};
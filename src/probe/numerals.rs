@@ -0,0 +1,955 @@
+//! Module Numerals - Normalisation des Nombres, Unités et Dates
+//!
+//! "vingt-cinq degrés" et "25 °C" disent la même chose mais n'ont presque
+//! aucun octet en commun : la NCD y lit une divergence. Ce module ramène
+//! les notations à une forme canonique avant le calcul :
+//! - nombres écrits en toutes lettres (français, anglais) -> chiffres,
+//!   ou l'inverse ;
+//! - séparateurs de milliers et virgule décimale ;
+//! - dates -> ISO 8601 (`2020-12-25`, `2020-12`) ;
+//! - ordinaux (`1er`, `21st`, `vingt et unième`) -> `1º`, `21º` ;
+//! - unités courantes -> symbole SI (`°C`, `km/h`, `%`, `kg`…).
+//!
+//! Auteur: Julien DABERT
+//! LDSI - Lyapunov-Dabert Stability Index
+
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+use super::cleaner::Language;
+use super::language::detect_language;
+
+/// Indicateur ordinal canonique (lettre : survit au retrait de ponctuation)
+const ORDINAL_MARK: char = 'º';
+
+/// Forme canonique des nombres
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NumeralForm {
+    /// "vingt-cinq" -> "25"
+    #[default]
+    Digits,
+    /// "25" -> "vingt-cinq" (survit à `remove_numbers`)
+    Words,
+}
+
+/// Configuration de la normalisation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NumeralConfig {
+    pub target: NumeralForm,
+    /// Unités -> symboles (°C, km/h, %, km, kg, h, €…)
+    pub units: bool,
+    /// Dates -> ISO 8601
+    pub dates: bool,
+    /// Ordinaux -> `Nº`
+    pub ordinals: bool,
+}
+
+impl Default for NumeralConfig {
+    fn default() -> Self {
+        Self {
+            target: NumeralForm::Digits,
+            units: true,
+            dates: true,
+            ordinals: true,
+        }
+    }
+}
+
+// ============================================================================
+// NOMBRES EN TOUTES LETTRES
+// ============================================================================
+
+const CARDINALS: &[(&str, u64)] = &[
+    // Français
+    ("zéro", 0),
+    ("un", 1),
+    ("une", 1),
+    ("deux", 2),
+    ("trois", 3),
+    ("quatre", 4),
+    ("cinq", 5),
+    ("six", 6),
+    ("sept", 7),
+    ("huit", 8),
+    ("neuf", 9),
+    ("dix", 10),
+    ("onze", 11),
+    ("douze", 12),
+    ("treize", 13),
+    ("quatorze", 14),
+    ("quinze", 15),
+    ("seize", 16),
+    ("vingt", 20),
+    ("vingts", 20),
+    ("trente", 30),
+    ("quarante", 40),
+    ("cinquante", 50),
+    ("soixante", 60),
+    ("cent", 100),
+    ("cents", 100),
+    ("mille", 1_000),
+    ("million", 1_000_000),
+    ("millions", 1_000_000),
+    ("milliard", 1_000_000_000),
+    ("milliards", 1_000_000_000),
+    // Anglais
+    ("zero", 0),
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+    ("ten", 10),
+    ("eleven", 11),
+    ("twelve", 12),
+    ("thirteen", 13),
+    ("fourteen", 14),
+    ("fifteen", 15),
+    ("sixteen", 16),
+    ("seventeen", 17),
+    ("eighteen", 18),
+    ("nineteen", 19),
+    ("twenty", 20),
+    ("thirty", 30),
+    ("forty", 40),
+    ("fifty", 50),
+    ("sixty", 60),
+    ("seventy", 70),
+    ("eighty", 80),
+    ("ninety", 90),
+    ("hundred", 100),
+    ("thousand", 1_000),
+    ("million", 1_000_000),
+    ("billion", 1_000_000_000),
+];
+
+/// Mots qui, isolés, sont plus souvent autre chose qu'un nombre
+/// ("un chat", "one of them", "un livre neuf", "a second")
+const AMBIGUOUS: &[&str] = &["un", "une", "one", "neuf", "second", "seconde"];
+
+/// Ordinaux irréguliers (les autres : cardinal + "ième" / "th")
+const IRREGULAR_ORDINALS: &[(&str, u64)] = &[
+    ("premier", 1),
+    ("première", 1),
+    ("unième", 1),
+    ("cinquième", 5),
+    ("neuvième", 9),
+    ("first", 1),
+    ("second", 2),
+    ("third", 3),
+    ("fifth", 5),
+    ("eighth", 8),
+    ("ninth", 9),
+    ("twelfth", 12),
+];
+
+fn cardinal(word: &str) -> Option<u64> {
+    CARDINALS
+        .iter()
+        .find(|(w, _)| *w == word)
+        .map(|(_, value)| *value)
+}
+
+/// Valeur d'un ordinal écrit ("vingtième" -> 20, "twenty-first" -> "first" -> 1)
+fn ordinal(word: &str) -> Option<u64> {
+    if let Some((_, value)) = IRREGULAR_ORDINALS.iter().find(|(w, _)| *w == word) {
+        return Some(*value);
+    }
+    if let Some(stem) = word.strip_suffix("ième") {
+        return cardinal(stem).or_else(|| cardinal(&format!("{}e", stem)));
+    }
+    if let Some(stem) = word.strip_suffix("ieth") {
+        return cardinal(&format!("{}y", stem));
+    }
+    word.strip_suffix("th").and_then(cardinal)
+}
+
+/// Accumulateur d'un nombre écrit, mot par mot
+#[derive(Default)]
+struct Accumulator {
+    total: u64,
+    current: u64,
+    last_scale: u64,
+    last_word: String,
+    words: usize,
+    closed: bool,
+}
+
+impl Accumulator {
+    /// Ajoute un mot ; false si le mot ne peut pas prolonger ce nombre
+    fn push(&mut self, word: &str, value: u64) -> bool {
+        if self.closed {
+            return false;
+        }
+        let rest = self.current % 100;
+        let accepted = match value {
+            // "quatre-vingt(s)" : multiplicatif
+            20 if self.last_word == "quatre" && rest == 4 => {
+                self.current += 76;
+                true
+            }
+            0 => {
+                self.closed = self.words == 0;
+                self.closed
+            }
+            v if v >= 1_000 => {
+                let ok = self.last_scale == 0 || v < self.last_scale;
+                if ok {
+                    self.total += self.current.max(1) * v;
+                    self.current = 0;
+                    self.last_scale = v;
+                }
+                ok
+            }
+            100 => {
+                let ok = self.current < 100;
+                if ok {
+                    self.current = self.current.max(1) * 100;
+                }
+                ok
+            }
+            v => {
+                let ok = rest == 0
+                    || (rest >= 20 && rest.is_multiple_of(10) && v < 10)
+                    || (rest == 10 && (7..=9).contains(&v))
+                    || ((rest == 60 || rest == 80) && (10..=19).contains(&v));
+                if ok {
+                    self.current += v;
+                }
+                ok
+            }
+        };
+        if accepted {
+            self.last_word = word.to_string();
+            self.words += 1;
+        }
+        accepted
+    }
+
+    fn value(&self) -> u64 {
+        self.total + self.current
+    }
+}
+
+/// Segment de texte : mot (lettres) ou séparateur
+fn segments(text: &str) -> Vec<(usize, usize, bool)> {
+    static SEGMENT: OnceLock<Regex> = OnceLock::new();
+    SEGMENT
+        .get_or_init(|| Regex::new(r"\p{L}+|[^\p{L}]+").unwrap())
+        .find_iter(text)
+        .map(|m| {
+            let is_word = m.as_str().chars().next().is_some_and(char::is_alphabetic);
+            (m.start(), m.end(), is_word)
+        })
+        .collect()
+}
+
+/// Séparateur interne à un nombre écrit : espaces et au plus un trait d'union
+fn joins_number(separator: &str) -> bool {
+    separator.chars().all(|c| c == '-' || c == ' ')
+        && separator.chars().filter(|c| *c == '-').count() <= 1
+        && !separator.is_empty()
+}
+
+/// Remplace les nombres écrits en toutes lettres par des chiffres
+fn words_to_digits(text: &str, ordinals: bool) -> String {
+    let segs = segments(text);
+    let word_at = |i: usize| -> Option<String> {
+        segs.get(i)
+            .filter(|s| s.2)
+            .map(|s| text[s.0..s.1].to_lowercase())
+    };
+
+    let mut out = String::with_capacity(text.len());
+    let mut cursor = 0;
+    let mut i = 0;
+
+    while i < segs.len() {
+        let Some(word) = word_at(i) else {
+            i += 1;
+            continue;
+        };
+        // "pour cent" : unité, pas nombre
+        let after_pour = i >= 2 && word_at(i - 2).as_deref() == Some("pour");
+        let starts = match cardinal(&word) {
+            Some(_) => !(after_pour && word.starts_with("cent")),
+            None => ordinals && ordinal(&word).is_some(),
+        };
+        if !starts {
+            i += 1;
+            continue;
+        }
+
+        let mut acc = Accumulator::default();
+        let mut end = i;
+        let mut is_ordinal = false;
+        let mut j = i;
+        while let Some(word) = word_at(j) {
+            if let Some(value) = cardinal(&word) {
+                if !acc.push(&word, value) {
+                    break;
+                }
+                end = j;
+            } else if ordinals && let Some(value) = ordinal(&word) {
+                // "second" isolé : unité de temps
+                if (word == "second" && acc.words == 0) || !acc.push(&word, value) {
+                    break;
+                }
+                end = j;
+                is_ordinal = true;
+                break;
+            } else {
+                break;
+            }
+
+            // Mot suivant, éventuellement après "et" / "and"
+            let joined = |k: usize| segs.get(k).is_some_and(|s| joins_number(&text[s.0..s.1]));
+            if !joined(j + 1) {
+                break;
+            }
+            match word_at(j + 2).as_deref() {
+                Some("et" | "and") if joined(j + 3) => j += 4,
+                _ => j += 2,
+            }
+        }
+
+        let single = end == i;
+        if acc.words == 0 || (single && !is_ordinal && AMBIGUOUS.contains(&word.as_str())) {
+            i += 1;
+            continue;
+        }
+
+        out.push_str(&text[cursor..segs[i].0]);
+        out.push_str(&acc.value().to_string());
+        if is_ordinal {
+            out.push(ORDINAL_MARK);
+        }
+        cursor = segs[end].1;
+        i = end + 1;
+    }
+    out.push_str(&text[cursor..]);
+    out
+}
+
+// ============================================================================
+// CHIFFRES -> TOUTES LETTRES
+// ============================================================================
+
+/// Retire le pluriel de "cents", "vingts", "millions", "milliards"
+fn drop_plural(words: &str) -> &str {
+    match words.strip_suffix('s') {
+        Some(stem)
+            if ["cent", "vingt", "million", "milliard"]
+                .iter()
+                .any(|w| stem.ends_with(w)) =>
+        {
+            stem
+        }
+        _ => words,
+    }
+}
+
+const FR_UNITS: [&str; 17] = [
+    "zéro", "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf", "dix", "onze",
+    "douze", "treize", "quatorze", "quinze", "seize",
+];
+
+const FR_TENS: [&str; 7] = [
+    "vingt",
+    "trente",
+    "quarante",
+    "cinquante",
+    "soixante",
+    "",
+    "",
+];
+
+/// Nombre en toutes lettres, orthographe traditionnelle
+fn french_words(n: u64) -> String {
+    match n {
+        0..=16 => FR_UNITS[n as usize].to_string(),
+        17..=19 => format!("dix-{}", FR_UNITS[(n - 10) as usize]),
+        20..=99 => {
+            let (tens, unit) = (n / 10, n % 10);
+            match tens {
+                7 | 9 => {
+                    let base = if tens == 7 {
+                        "soixante"
+                    } else {
+                        "quatre-vingt"
+                    };
+                    let link = if n == 71 { " et " } else { "-" };
+                    format!("{}{}{}", base, link, french_words(10 + unit))
+                }
+                8 if unit == 0 => "quatre-vingts".to_string(),
+                8 => format!("quatre-vingt-{}", french_words(unit)),
+                _ => {
+                    let base = FR_TENS[(tens - 2) as usize];
+                    match unit {
+                        0 => base.to_string(),
+                        1 => format!("{} et un", base),
+                        _ => format!("{}-{}", base, french_words(unit)),
+                    }
+                }
+            }
+        }
+        100..=999 => {
+            let (hundreds, rest) = (n / 100, n % 100);
+            let head = if hundreds == 1 {
+                "cent".to_string()
+            } else {
+                format!("{} cent", french_words(hundreds))
+            };
+            match rest {
+                0 if hundreds > 1 => format!("{}s", head),
+                0 => head,
+                _ => format!("{} {}", head, french_words(rest)),
+            }
+        }
+        _ => {
+            let (scale, name) = [
+                (1_000_000_000, "milliard"),
+                (1_000_000, "million"),
+                (1_000, "mille"),
+            ]
+            .into_iter()
+            .find(|(scale, _)| n >= *scale)
+            .unwrap();
+            let (count, rest) = (n / scale, n % scale);
+            let head = match (count, name) {
+                (1, "mille") => "mille".to_string(),
+                (_, "mille") => {
+                    // "deux cents" -> "deux cent mille", "quatre-vingts" -> "quatre-vingt mille"
+                    format!("{} mille", drop_plural(&french_words(count)))
+                }
+                (1, _) => format!("un {}", name),
+                _ => format!("{} {}s", french_words(count), name),
+            };
+            match rest {
+                0 => head,
+                _ => format!("{} {}", head, french_words(rest)),
+            }
+        }
+    }
+}
+
+const EN_UNITS: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const EN_TENS: [&str; 8] = [
+    "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+fn english_words(n: u64) -> String {
+    match n {
+        0..=19 => EN_UNITS[n as usize].to_string(),
+        20..=99 => {
+            let base = EN_TENS[(n / 10 - 2) as usize];
+            match n % 10 {
+                0 => base.to_string(),
+                unit => format!("{}-{}", base, EN_UNITS[unit as usize]),
+            }
+        }
+        _ => {
+            let (scale, name) = [
+                (1_000_000_000, "billion"),
+                (1_000_000, "million"),
+                (1_000, "thousand"),
+                (100, "hundred"),
+            ]
+            .into_iter()
+            .find(|(scale, _)| n >= *scale)
+            .unwrap();
+            let head = format!("{} {}", english_words(n / scale), name);
+            match n % scale {
+                0 => head,
+                rest => format!("{} {}", head, english_words(rest)),
+            }
+        }
+    }
+}
+
+/// Ordinal en toutes lettres ("vingt et unième", "twenty-first")
+fn ordinal_words(n: u64, english: bool) -> String {
+    if english {
+        let words = english_words(n);
+        let split = words.rfind([' ', '-']).map_or(0, |p| p + 1);
+        let (head, last) = words.split_at(split);
+        let last = match last {
+            "one" => "first".to_string(),
+            "two" => "second".to_string(),
+            "three" => "third".to_string(),
+            "five" => "fifth".to_string(),
+            "eight" => "eighth".to_string(),
+            "nine" => "ninth".to_string(),
+            "twelve" => "twelfth".to_string(),
+            l if l.ends_with('y') => format!("{}ieth", &l[..l.len() - 1]),
+            l => format!("{}th", l),
+        };
+        return format!("{}{}", head, last);
+    }
+    if n == 1 {
+        return "premier".to_string();
+    }
+    let words = french_words(n);
+    let words = drop_plural(&words);
+    if let Some(stem) = words.strip_suffix("cinq") {
+        format!("{}cinquième", stem)
+    } else if let Some(stem) = words.strip_suffix("neuf") {
+        format!("{}neuvième", stem)
+    } else if let Some(stem) = words.strip_suffix('e') {
+        format!("{}ième", stem)
+    } else {
+        format!("{}ième", words)
+    }
+}
+
+/// Remplace les entiers isolés (hors dates, décimaux, heures) par des mots
+fn digits_to_words(text: &str, english: bool) -> String {
+    static NUMBER: OnceLock<Regex> = OnceLock::new();
+    let number = NUMBER.get_or_init(|| Regex::new(r"\d+(?:[.,:/-]\d+)*º?").unwrap());
+    number
+        .replace_all(text, |caps: &Captures| {
+            let token = &caps[0];
+            let (digits, is_ordinal) = match token.strip_suffix(ORDINAL_MARK) {
+                Some(digits) => (digits, true),
+                None => (token, false),
+            };
+            match digits.parse::<u64>() {
+                Ok(n) if n < 1_000_000_000_000 && is_ordinal && n > 0 => ordinal_words(n, english),
+                Ok(n) if n < 1_000_000_000_000 && !is_ordinal => match english {
+                    true => english_words(n),
+                    false => french_words(n),
+                },
+                _ => token.to_string(),
+            }
+        })
+        .to_string()
+}
+
+// ============================================================================
+// SÉPARATEURS, DATES, ORDINAUX, UNITÉS
+// ============================================================================
+
+/// Milliers et décimales : "1,000" (anglais), "1 000" et "3,5" (autres)
+///
+/// Langue inconnue (`Both`, `Auto`) : "1,000" peut valoir mille ou un, le
+/// groupe `d,ddd` est laissé tel quel.
+fn normalize_separators(text: &str, language: Language) -> String {
+    static PATTERNS: OnceLock<[Regex; 3]> = OnceLock::new();
+    let [comma_thousands, space_thousands, decimal_comma] = PATTERNS.get_or_init(|| {
+        [
+            Regex::new(r"\b\d{1,3}(?:,\d{3})+\b").unwrap(),
+            Regex::new(r"\b\d{1,3}(?:[ \u{00A0}\u{202F}\u{2009}]\d{3})+\b").unwrap(),
+            Regex::new(r"(\d),(\d+)").unwrap(),
+        ]
+    });
+    let strip = |caps: &Captures| {
+        caps[0]
+            .chars()
+            .filter(char::is_ascii_digit)
+            .collect::<String>()
+    };
+
+    if language == Language::English {
+        return comma_thousands.replace_all(text, strip).to_string();
+    }
+    let known = !matches!(language, Language::Both | Language::Auto);
+    let text = space_thousands.replace_all(text, strip);
+    decimal_comma
+        .replace_all(&text, |caps: &Captures| {
+            if known || caps[2].len() != 3 {
+                format!("{}.{}", &caps[1], &caps[2])
+            } else {
+                caps[0].to_string()
+            }
+        })
+        .to_string()
+}
+
+const MONTHS: &str = r"janvier|février|fevrier|mars|avril|mai|juin|juillet|août|aout|septembre|octobre|novembre|décembre|decembre|janv|févr|fév|fevr|fev|avr|juil|sept|déc|january|february|march|april|may|june|july|august|september|october|november|december|jan|feb|mar|apr|jun|jul|aug|sep|oct|nov|dec";
+
+/// Formes ambiguës sans jour ni point (abréviations, et "may")
+const SHORT_MONTHS: &[&str] = &[
+    "janv", "févr", "fév", "fevr", "fev", "avr", "juil", "sept", "déc", "jan", "feb", "mar", "apr",
+    "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+const DAY: &str = r"(\d{1,2})(?:er|re|º|st|nd|rd|th)?";
+
+fn month_number(name: &str) -> Option<u32> {
+    let name = name
+        .to_lowercase()
+        .replace(['é', 'è'], "e")
+        .replace('û', "u");
+    let month = match name.get(..3)? {
+        "jan" => 1,
+        "fev" | "feb" => 2,
+        "mar" => 3,
+        "avr" | "apr" => 4,
+        "mai" | "may" => 5,
+        "jun" => 6,
+        "jul" => 7,
+        "jui" if name.starts_with("juin") => 6,
+        "jui" => 7,
+        "aou" | "aug" => 8,
+        "sep" => 9,
+        "oct" => 10,
+        "nov" => 11,
+        "dec" => 12,
+        _ => return None,
+    };
+    Some(month)
+}
+
+fn iso_date(year: &str, month: u32, day: &str) -> Option<String> {
+    let day: u32 = day.parse().ok()?;
+    ((1..=31).contains(&day) && (1..=12).contains(&month))
+        .then(|| format!("{}-{:02}-{:02}", year, month, day))
+}
+
+/// Un mois suivi d'une seule année en est-il bien un ?
+///
+/// Sans jour, "may", "mar" ou "mars" sont souvent autre chose ("you may
+/// 2020…", la planète) : les abréviations exigent un point ou une
+/// majuscule, et "mars" n'est retenu qu'en français.
+fn bare_month(name: &str, dot: &str, language: Language) -> bool {
+    let lower = name.to_lowercase();
+    if lower == "mars" {
+        return language == Language::French;
+    }
+    !SHORT_MONTHS.contains(&lower.as_str())
+        || !dot.is_empty()
+        || name.starts_with(char::is_uppercase)
+}
+
+/// Dates -> ISO 8601 ; "12/03/2020" est lu jour d'abord sauf si impossible
+fn normalize_dates(text: &str, language: Language) -> String {
+    static PATTERNS: OnceLock<[Regex; 4]> = OnceLock::new();
+    let [day_month, month_day, month_year, numeric] = PATTERNS.get_or_init(|| {
+        [
+            Regex::new(&format!(
+                r"(?i)\b(?:{}|premier)\s+(?:of\s+)?({})\.?,?\s+(\d{{4}})\b",
+                DAY, MONTHS
+            ))
+            .unwrap(),
+            Regex::new(&format!(r"(?i)\b({})\.?\s+{},?\s+(\d{{4}})\b", MONTHS, DAY)).unwrap(),
+            Regex::new(&format!(r"(?i)\b({})(\.?)\s+(\d{{4}})\b", MONTHS)).unwrap(),
+            Regex::new(r"\b(\d{1,2})[/.](\d{1,2})[/.](\d{4})\b").unwrap(),
+        ]
+    });
+
+    let text = day_month.replace_all(text, |caps: &Captures| {
+        let day = caps.get(1).map_or("1", |m| m.as_str());
+        month_number(&caps[2])
+            .and_then(|month| iso_date(&caps[3], month, day))
+            .unwrap_or_else(|| caps[0].to_string())
+    });
+    let text = month_day.replace_all(&text, |caps: &Captures| {
+        month_number(&caps[1])
+            .and_then(|month| iso_date(&caps[3], month, &caps[2]))
+            .unwrap_or_else(|| caps[0].to_string())
+    });
+    let text = month_year.replace_all(&text, |caps: &Captures| {
+        match month_number(&caps[1]).filter(|_| bare_month(&caps[1], &caps[2], language)) {
+            Some(month) => format!("{}-{:02}", &caps[3], month),
+            None => caps[0].to_string(),
+        }
+    });
+    numeric
+        .replace_all(&text, |caps: &Captures| {
+            let (first, second): (u32, u32) = (caps[1].parse().unwrap(), caps[2].parse().unwrap());
+            let (day, month) = if second > 12 {
+                (second, first)
+            } else {
+                (first, second)
+            };
+            iso_date(&caps[3], month, &day.to_string()).unwrap_or_else(|| caps[0].to_string())
+        })
+        .to_string()
+}
+
+/// Ordinaux en chiffres : "1er", "2e", "3ème", "21st" -> "1º", "2º", "3º", "21º"
+fn normalize_ordinals(text: &str) -> String {
+    static ORDINAL: OnceLock<Regex> = OnceLock::new();
+    ORDINAL
+        .get_or_init(|| {
+            Regex::new(r"\b(\d+)(?:er|re|ère|ere|ème|eme|ième|e|st|nd|rd|th)\b").unwrap()
+        })
+        .replace_all(text, format!("${{1}}{}", ORDINAL_MARK).as_str())
+        .to_string()
+}
+
+/// Unités : (symbole canonique, variantes), les plus longues d'abord
+const UNITS: &[(&str, &str)] = &[
+    (
+        "°C",
+        r"°\s*c|degr[ée]s?\s+celsius|degrees?\s+celsius|celsius",
+    ),
+    (
+        "°F",
+        r"°\s*f|degr[ée]s?\s+fahrenheit|degrees?\s+fahrenheit|fahrenheit",
+    ),
+    ("°", r"°|degr[ée]s?|degrees?"),
+    (
+        "km/h",
+        r"km\s*/\s*h|kmh|kph|kilom[èe]tres?\s+(?:par|à\s+l['’])\s*heure|kilomet(?:er|re)s?\s+(?:per|an)\s+hour",
+    ),
+    ("mph", r"mph|miles?\s+(?:per|an)\s+hour"),
+    ("%", r"%|pour\s*cent|pourcents?|per\s*cent|percent"),
+    ("km", r"km|kilom[èe]tres?|kilomet(?:er|re)s?"),
+    ("cm", r"cm|centim[èe]tres?|centimet(?:er|re)s?"),
+    ("mm", r"mm|millim[èe]tres?|millimet(?:er|re)s?"),
+    ("m", r"m|m[èe]tres?|met(?:er|re)s?"),
+    ("kg", r"kg|kilo(?:gramme|gram)?s?"),
+    ("g", r"g|grammes?|grams?"),
+    ("min", r"min|minutes?"),
+    ("h", r"h|heures?|hours?"),
+    ("€", r"€|euros?|eur"),
+    ("$", r"\$|dollars?|usd"),
+];
+
+fn normalize_units(text: &str) -> String {
+    static PATTERNS: OnceLock<(Regex, Vec<(&'static str, Regex)>)> = OnceLock::new();
+    let (dollar_prefix, units) = PATTERNS.get_or_init(|| {
+        (
+            Regex::new(r"\$\s*(\d+(?:\.\d+)?)").unwrap(),
+            UNITS
+                .iter()
+                .map(|(symbol, variants)| {
+                    let pattern =
+                        format!(r"(?i)(\d+(?:\.\d+)?)\s*(?:{})([^\p{{L}}\d]|$)", variants);
+                    (*symbol, Regex::new(&pattern).unwrap())
+                })
+                .collect(),
+        )
+    });
+
+    let mut text = dollar_prefix.replace_all(text, "$1 $$").to_string();
+    for (symbol, regex) in units {
+        let replacement = format!("${{1}} {}${{2}}", symbol.replace('$', "$$"));
+        text = regex.replace_all(&text, replacement.as_str()).to_string();
+    }
+    text
+}
+
+/// Normalise nombres, dates, ordinaux et unités
+///
+/// `language` choisit les conventions de séparateurs et la langue des
+/// nombres écrits avec `NumeralForm::Words` : anglais pour `English`,
+/// français sinon. `Both` et `Auto` sont résolus par détection sur le texte,
+/// comme pour le stemmer. La lecture des nombres écrits accepte les deux
+/// langues.
+///
+/// Les dates sont normalisées avant la lecture des nombres écrits, qui
+/// ferait de l'abréviation "sept" un 7, puis une seconde fois pour les
+/// dates écrites en toutes lettres.
+pub fn normalize_numerals(text: &str, config: &NumeralConfig, language: Language) -> String {
+    let language = match language {
+        Language::Both | Language::Auto => detect_language(text).unwrap_or(language),
+        other => other,
+    };
+    let english = language == Language::English;
+
+    let mut text = normalize_separators(text, language);
+    if config.dates {
+        text = normalize_dates(&text, language);
+    }
+    text = words_to_digits(&text, config.ordinals);
+    if config.dates {
+        text = normalize_dates(&text, language);
+    }
+    if config.ordinals {
+        text = normalize_ordinals(&text);
+    }
+    if config.units {
+        text = normalize_units(&text);
+    }
+    if config.target == NumeralForm::Words {
+        text = digits_to_words(&text, english);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(text: &str) -> String {
+        normalize_numerals(text, &NumeralConfig::default(), Language::French)
+    }
+
+    #[test]
+    fn test_written_numbers() {
+        let cases = [
+            ("vingt-cinq degrés", "25 °"),
+            ("soixante et onze ans", "71 ans"),
+            ("quatre-vingt-dix-sept", "97"),
+            ("deux cent quatre-vingts", "280"),
+            ("mille deux cents", "1200"),
+            ("trois millions cinq cent mille", "3500000"),
+            ("two hundred and five", "205"),
+            ("Twenty-one thousand", "21000"),
+            ("un chat et un chien", "un chat et un chien"),
+            ("un livre neuf", "un livre neuf"),
+            ("deux trois", "2 3"),
+            ("vingt pour cent", "20 %"),
+            ("onze et deux", "11 et 2"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(digits(text), expected, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_words() {
+        let samples = (0..=1200).chain([1999, 2024, 80_000, 200_000, 1_000_001, 71_071_071]);
+        for n in samples {
+            for english in [false, true] {
+                let words = if english {
+                    english_words(n)
+                } else {
+                    french_words(n)
+                };
+                let parsed = words_to_digits(&words, true);
+                let expected = if AMBIGUOUS.contains(&words.as_str()) {
+                    words.clone() // "un", "neuf", "one" isolés
+                } else {
+                    n.to_string()
+                };
+                assert_eq!(parsed, expected, "{} ({})", words, n);
+
+                let ordinal = ordinal_words(n, english);
+                if n > 0 && !AMBIGUOUS.contains(&ordinal.as_str()) {
+                    assert_eq!(
+                        words_to_digits(&ordinal, true),
+                        format!("{}º", n),
+                        "{}",
+                        ordinal
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_units_dates_ordinals() {
+        let cases = [
+            ("25 degrés Celsius", "25 °C"),
+            ("25°C", "25 °C"),
+            ("90 kilomètres par heure", "90 km/h"),
+            ("90 km / h", "90 km/h"),
+            ("15 pour cent", "15 %"),
+            ("15 percent", "15 %"),
+            ("3,5 kilos", "3.5 kg"),
+            ("$20", "20 $"),
+            ("25 000 habitants", "25000 habitants"),
+            ("5 mois", "5 mois"),
+            ("le 25 décembre 2020", "le 2020-12-25"),
+            ("le premier janvier deux mille vingt", "le 2020-01-01"),
+            ("December 25th, 2020", "2020-12-25"),
+            ("the 4th of July 1776", "the 1776-07-04"),
+            ("en mars 2021", "en 2021-03"),
+            ("le 3 sept 2020", "le 2020-09-03"),
+            ("sept. 2020", "2020-09"),
+            ("25/12/2020", "2020-12-25"),
+            ("12/25/2020", "2020-12-25"),
+            ("le 21e siècle", "le 21º siècle"),
+            ("le vingt et unième siècle", "le 21º siècle"),
+            ("the twenty-first century", "the 21º century"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(digits(text), expected, "{}", text);
+        }
+        let english =
+            normalize_numerals("1,500 people", &NumeralConfig::default(), Language::English);
+        assert_eq!(english, "1500 people");
+
+        // Mois ambigus sans jour
+        let config = NumeralConfig::default();
+        for (text, language, expected) in [
+            (
+                "You may 2020 be fine",
+                Language::English,
+                "You may 2020 be fine",
+            ),
+            ("Mars 2020 landed", Language::English, "Mars 2020 landed"),
+            ("since May 2020", Language::English, "since 2020-05"),
+            ("in mar 2020", Language::English, "in mar 2020"),
+            ("in Mar. 2020", Language::English, "in 2020-03"),
+            ("le 3 mars 2020", Language::Both, "le 2020-03-03"),
+        ] {
+            assert_eq!(
+                normalize_numerals(text, &config, language),
+                expected,
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn test_default_language() {
+        // `Both` (défaut du CLI) : langue détectée, les paraphrases convergent
+        let config = NumeralConfig::default();
+        let words = "The new stadium can hold one thousand people during the summer concerts.";
+        let digits = "The new stadium can hold 1,000 people during the summer concerts.";
+        assert_eq!(
+            normalize_numerals(words, &config, Language::Both),
+            normalize_numerals(digits, &config, Language::Both)
+        );
+        let french = "Le nouveau stade accueille 3,5 millions de visiteurs chaque année.";
+        assert!(normalize_numerals(french, &config, Language::Both).contains("3.5"));
+        // Langue inconnue : "1,000" reste ambigu
+        assert_eq!(
+            normalize_numerals("1,000", &config, Language::Both),
+            "1,000"
+        );
+    }
+
+    #[test]
+    fn test_words_target() {
+        let config = NumeralConfig {
+            target: NumeralForm::Words,
+            ..Default::default()
+        };
+        assert_eq!(
+            normalize_numerals("Il fait 25 degrés, le 3e jour", &config, Language::French),
+            "Il fait vingt-cinq °, le troisième jour"
+        );
+        assert_eq!(
+            normalize_numerals("twenty-five or 25 km/h", &config, Language::English),
+            "twenty-five or twenty-five km/h"
+        );
+        // Dates et décimaux inchangés
+        assert_eq!(
+            normalize_numerals("2020-12-25 et 3.5", &config, Language::French),
+            "2020-12-25 et 3.5"
+        );
+    }
+}
@@ -0,0 +1,680 @@
+//! Module Pipeline - Nettoyage Composable et Traçable
+//!
+//! Le nettoyage est une suite ordonnée d'étapes configurables, construite
+//! en code, depuis un `CleanerConfig` ou depuis un fichier TOML :
+//!
+//! ```toml
+//! [[stage]]
+//! stage = "lowercase"
+//!
+//! [[stage]]
+//! stage = "stopwords"
+//! language = "French"
+//! extra = ["donc", "voilà"]
+//! ```
+//!
+//! Chaque exécution produit une trace par étape (caractères et tokens avant
+//! et après, tokens supprimés) : l'audit montre exactement le texte noté.
+//!
+//! Auteur: Julien DABERT
+//! LDSI - Lyapunov-Dabert Stability Index
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+use unicode_normalization::UnicodeNormalization;
+
+use super::boilerplate::split_boilerplate;
+use super::cleaner::{CleanerConfig, Language, split_reasoning};
+use super::markup::{MarkupConfig, parse_markup};
+use super::numerals::{NumeralConfig, normalize_numerals};
+use super::stemmer::stemmer_for;
+
+/// Contexte partagé par les étapes d'une exécution
+pub struct StageContext {
+    /// Texte servant à la détection de langue : entrée de la première étape lexicale
+    pub source: String,
+}
+
+/// Étape de nettoyage
+///
+/// Les étapes personnalisées implémentent ce trait et s'ajoutent par
+/// `CleaningPipeline::with_stage`.
+pub trait CleaningStage: Send + Sync {
+    /// Nom affiché dans la trace
+    fn name(&self) -> String;
+
+    /// Étape lexicale (opère sur des mots) plutôt que structurelle
+    /// (balises, markdown) : la langue est détectée avant la première
+    fn lexical(&self) -> bool {
+        true
+    }
+
+    /// Applique l'étape ; `removed` reçoit les tokens supprimés
+    fn apply(&self, text: &str, context: &StageContext, removed: &mut Vec<String>) -> String;
+}
+
+/// Étape intégrée, sérialisable (TOML, audit)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum StageSpec {
+    /// Supprime les traces <think>…</think>
+    StripReasoning,
+    /// Prétraitement markdown/code/LaTeX/HTML
+    Markup(MarkupConfig),
+    /// Phrases de refus, avertissement, mise en garde et clôture retirées
+    Boilerplate,
+    /// Normalisation Unicode (NFD -> NFC)
+    Normalize,
+    /// Nombres, unités, dates et ordinaux sous forme canonique
+    Numerals {
+        #[serde(flatten)]
+        config: NumeralConfig,
+        /// Conventions de séparateurs ; détectée seulement si `Auto`
+        #[serde(default = "auto_language")]
+        language: Language,
+    },
+    Lowercase,
+    /// Chiffres remplacés par des espaces
+    Numbers,
+    /// Tout sauf lettres et espaces remplacé par des espaces
+    Punctuation,
+    /// Tokens plus courts que `min` octets supprimés
+    MinLength {
+        min: usize,
+    },
+    /// Tokens de fréquence relative >= `threshold` (au moins 3 occurrences)
+    DynamicStopwords {
+        #[serde(default = "default_threshold")]
+        threshold: f64,
+    },
+    /// Stop-words de la langue, plus une liste libre
+    Stopwords {
+        #[serde(default = "default_language")]
+        language: Language,
+        #[serde(default)]
+        extra: Vec<String>,
+    },
+    /// Racinisation Snowball (français/anglais)
+    Stem {
+        #[serde(default = "default_language")]
+        language: Language,
+    },
+    /// Remplacement par expression régulière
+    Replace {
+        pattern: String,
+        #[serde(default)]
+        replacement: String,
+    },
+}
+
+fn default_threshold() -> f64 {
+    0.01
+}
+
+fn default_language() -> Language {
+    Language::Both
+}
+
+fn auto_language() -> Language {
+    Language::Auto
+}
+
+/// Étape intégrée avec ses ressources précompilées
+struct Builtin {
+    spec: StageSpec,
+    regex: Option<Regex>,
+}
+
+fn digits() -> &'static Regex {
+    static DIGITS: OnceLock<Regex> = OnceLock::new();
+    DIGITS.get_or_init(|| Regex::new(r"\d+").unwrap())
+}
+
+/// Filtre les tokens (séparateur espace), en collectant ceux supprimés
+fn filter_tokens(text: &str, removed: &mut Vec<String>, keep: impl Fn(&str) -> bool) -> String {
+    let mut kept = Vec::new();
+    for token in text.split_whitespace() {
+        if keep(token) {
+            kept.push(token);
+        } else {
+            removed.push(token.to_string());
+        }
+    }
+    kept.join(" ")
+}
+
+impl CleaningStage for Builtin {
+    fn name(&self) -> String {
+        match &self.spec {
+            StageSpec::StripReasoning => "strip_reasoning".to_string(),
+            StageSpec::Markup(_) => "markup".to_string(),
+            StageSpec::Boilerplate => "boilerplate".to_string(),
+            StageSpec::Normalize => "normalize".to_string(),
+            StageSpec::Numerals { config, language } => {
+                format!("numerals({:?}, {:?})", config.target, language)
+            }
+            StageSpec::Lowercase => "lowercase".to_string(),
+            StageSpec::Numbers => "numbers".to_string(),
+            StageSpec::Punctuation => "punctuation".to_string(),
+            StageSpec::MinLength { min } => format!("min_length({})", min),
+            StageSpec::DynamicStopwords { threshold } => {
+                format!("dynamic_stopwords({})", threshold)
+            }
+            StageSpec::Stopwords { language, extra } if extra.is_empty() => {
+                format!("stopwords({:?})", language)
+            }
+            StageSpec::Stopwords { language, extra } => {
+                format!("stopwords({:?}+{})", language, extra.len())
+            }
+            StageSpec::Stem { language } => format!("stem({:?})", language),
+            StageSpec::Replace { pattern, .. } => format!("replace(/{}/)", pattern),
+        }
+    }
+
+    fn lexical(&self) -> bool {
+        !matches!(
+            self.spec,
+            StageSpec::StripReasoning | StageSpec::Markup(_) | StageSpec::Boilerplate
+        )
+    }
+
+    fn apply(&self, text: &str, context: &StageContext, removed: &mut Vec<String>) -> String {
+        match &self.spec {
+            StageSpec::StripReasoning => split_reasoning(text).0,
+            StageSpec::Markup(config) => parse_markup(text, config).prose,
+            StageSpec::Boilerplate => {
+                let (content, matches) = split_boilerplate(text);
+                removed.extend(
+                    matches
+                        .iter()
+                        .flat_map(|m| m.sentence.split_whitespace())
+                        .map(str::to_string),
+                );
+                content
+            }
+            StageSpec::Normalize => text.nfd().collect::<String>().nfc().collect(),
+            StageSpec::Numerals { config, language } => {
+                normalize_numerals(text, config, language.resolve(&context.source))
+            }
+            StageSpec::Lowercase => text.to_lowercase(),
+            StageSpec::Numbers => digits().replace_all(text, " ").to_string(),
+            StageSpec::Punctuation => text
+                .chars()
+                .map(|c| {
+                    if c.is_alphabetic() || c.is_whitespace() {
+                        c
+                    } else {
+                        ' '
+                    }
+                })
+                .collect(),
+            StageSpec::MinLength { min } => filter_tokens(text, removed, |w| w.len() >= *min),
+            StageSpec::DynamicStopwords { threshold } => {
+                // Loi de Zipf : les tokens sur-représentés sont du bruit structurel
+                let mut freq: HashMap<&str, usize> = HashMap::new();
+                let mut total = 0;
+                for token in text.split_whitespace() {
+                    *freq.entry(token).or_insert(0) += 1;
+                    total += 1;
+                }
+                let min_count = ((total as f64 * threshold).ceil() as usize).max(3);
+                let frequent: HashSet<&str> = freq
+                    .into_iter()
+                    .filter(|(_, count)| *count >= min_count)
+                    .map(|(token, _)| token)
+                    .collect();
+                filter_tokens(text, removed, |w| !frequent.contains(w))
+            }
+            StageSpec::Stopwords { language, extra } => {
+                let mut stopwords: HashSet<&str> = language
+                    .resolve(&context.source)
+                    .stopwords()
+                    .into_iter()
+                    .collect();
+                stopwords.extend(extra.iter().map(String::as_str));
+                filter_tokens(text, removed, |w| !stopwords.contains(w))
+            }
+            StageSpec::Stem { language } => {
+                let resolved = language.resolve(&context.source);
+                match stemmer_for(resolved, &context.source) {
+                    Some(stem) => text
+                        .split_whitespace()
+                        .map(stem)
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    None => text.to_string(),
+                }
+            }
+            StageSpec::Replace { replacement, .. } => {
+                let regex = self
+                    .regex
+                    .as_ref()
+                    .expect("regex compilée à la construction");
+                regex.replace_all(text, replacement.as_str()).to_string()
+            }
+        }
+    }
+}
+
+/// Token supprimé et nombre d'occurrences
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenCount {
+    pub token: String,
+    pub count: usize,
+}
+
+/// Trace d'une étape
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageTrace {
+    pub stage: String,
+    pub chars_before: usize,
+    pub chars_after: usize,
+    pub tokens_before: usize,
+    pub tokens_after: usize,
+    /// Tokens supprimés, par fréquence décroissante
+    pub removed: Vec<TokenCount>,
+}
+
+/// Trace complète d'une exécution du pipeline
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CleaningTrace {
+    /// Caractères du texte d'entrée (rendus tels quels par un pipeline vide)
+    #[serde(default)]
+    pub input_chars: usize,
+    pub stages: Vec<StageTrace>,
+}
+
+impl CleaningTrace {
+    /// Caractères en entrée du pipeline
+    pub fn chars_before(&self) -> usize {
+        self.input_chars
+    }
+
+    /// Caractères en sortie du pipeline
+    pub fn chars_after(&self) -> usize {
+        self.stages
+            .last()
+            .map_or(self.input_chars, |s| s.chars_after)
+    }
+
+    /// Nombre total de tokens supprimés par les étapes de filtrage
+    pub fn removed_tokens(&self) -> usize {
+        self.stages
+            .iter()
+            .flat_map(|s| &s.removed)
+            .map(|t| t.count)
+            .sum()
+    }
+}
+
+/// Erreur de construction du pipeline
+#[derive(Debug, Clone)]
+pub enum PipelineError {
+    /// Fichier TOML invalide
+    Toml(String),
+    /// Expression régulière invalide dans une étape `replace`
+    Regex(String),
+}
+
+impl std::fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PipelineError::Toml(e) => write!(f, "TOML error: {}", e),
+            PipelineError::Regex(e) => write!(f, "Regex error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PipelineError {}
+
+/// Fichier TOML : liste `[[stage]]`
+#[derive(Deserialize)]
+struct PipelineFile {
+    #[serde(default)]
+    stage: Vec<StageSpec>,
+}
+
+/// Suite ordonnée d'étapes de nettoyage
+#[derive(Default)]
+pub struct CleaningPipeline {
+    stages: Vec<Box<dyn CleaningStage>>,
+}
+
+impl CleaningPipeline {
+    /// Pipeline vide (texte inchangé)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ajoute une étape (intégrée ou personnalisée) en fin de pipeline
+    pub fn with_stage(mut self, stage: impl CleaningStage + 'static) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    /// Ajoute une étape intégrée
+    pub fn with_spec(self, spec: StageSpec) -> Result<Self, PipelineError> {
+        let regex = match &spec {
+            StageSpec::Replace { pattern, .. } => {
+                Some(Regex::new(pattern).map_err(|e| PipelineError::Regex(e.to_string()))?)
+            }
+            _ => None,
+        };
+        Ok(self.with_stage(Builtin { spec, regex }))
+    }
+
+    /// Construit un pipeline à partir d'étapes intégrées
+    pub fn from_specs(specs: &[StageSpec]) -> Result<Self, PipelineError> {
+        specs.iter().try_fold(Self::new(), |pipeline, spec| {
+            pipeline.with_spec(spec.clone())
+        })
+    }
+
+    /// Construit un pipeline depuis un document TOML (`[[stage]]`)
+    pub fn from_toml(source: &str) -> Result<Self, PipelineError> {
+        let file: PipelineFile =
+            toml::from_str(source).map_err(|e| PipelineError::Toml(e.to_string()))?;
+        Self::from_specs(&file.stage)
+    }
+
+    /// Étapes équivalentes à une configuration du nettoyeur
+    ///
+    /// La longueur minimale précède les stop-words dynamiques, dont les
+    /// fréquences sont calculées sur les seuls mots assez longs.
+    pub fn specs_for(config: &CleanerConfig) -> Vec<StageSpec> {
+        let mut specs = Vec::new();
+        if config.strip_reasoning {
+            specs.push(StageSpec::StripReasoning);
+        }
+        if let Some(markup) = &config.markup {
+            specs.push(StageSpec::Markup(markup.clone()));
+        }
+        if config.strip_boilerplate {
+            specs.push(StageSpec::Boilerplate);
+        }
+        if config.normalize_unicode {
+            specs.push(StageSpec::Normalize);
+        }
+        if let Some(numerals) = &config.numerals {
+            specs.push(StageSpec::Numerals {
+                config: numerals.clone(),
+                language: config.language,
+            });
+        }
+        if config.lowercase {
+            specs.push(StageSpec::Lowercase);
+        }
+        if config.remove_numbers {
+            specs.push(StageSpec::Numbers);
+        }
+        if config.remove_punctuation {
+            specs.push(StageSpec::Punctuation);
+        }
+        specs.push(StageSpec::MinLength {
+            min: config.min_word_length,
+        });
+        if config.dynamic_stopwords {
+            specs.push(StageSpec::DynamicStopwords {
+                threshold: config.dynamic_stopwords_threshold,
+            });
+        }
+        if config.remove_stopwords {
+            specs.push(StageSpec::Stopwords {
+                language: config.language,
+                extra: Vec::new(),
+            });
+        }
+        if config.stem {
+            specs.push(StageSpec::Stem {
+                language: config.language,
+            });
+        }
+        specs
+    }
+
+    /// Pipeline équivalent à une configuration du nettoyeur
+    pub fn from_config(config: &CleanerConfig) -> Self {
+        Self::from_specs(&Self::specs_for(config)).expect("étapes sans expression régulière")
+    }
+
+    /// Noms des étapes, dans l'ordre
+    pub fn stage_names(&self) -> Vec<String> {
+        self.stages.iter().map(|stage| stage.name()).collect()
+    }
+
+    /// Nettoie un texte et trace chaque étape
+    pub fn run(&self, text: &str) -> (String, CleaningTrace) {
+        let mut current = text.to_string();
+        let mut context: Option<StageContext> = None;
+        let mut trace = CleaningTrace {
+            input_chars: text.chars().count(),
+            stages: Vec::new(),
+        };
+
+        for stage in &self.stages {
+            if stage.lexical() && context.is_none() {
+                context = Some(StageContext {
+                    source: current.clone(),
+                });
+            }
+            let structural;
+            let ctx = match &context {
+                Some(ctx) => ctx,
+                None => {
+                    structural = StageContext {
+                        source: current.clone(),
+                    };
+                    &structural
+                }
+            };
+
+            let mut removed = Vec::new();
+            let next = stage.apply(&current, ctx, &mut removed);
+
+            let mut counts: HashMap<String, usize> = HashMap::new();
+            for token in removed {
+                *counts.entry(token).or_insert(0) += 1;
+            }
+            let mut removed: Vec<TokenCount> = counts
+                .into_iter()
+                .map(|(token, count)| TokenCount { token, count })
+                .collect();
+            removed.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.token.cmp(&b.token)));
+
+            trace.stages.push(StageTrace {
+                stage: stage.name(),
+                chars_before: current.chars().count(),
+                chars_after: next.chars().count(),
+                tokens_before: current.split_whitespace().count(),
+                tokens_after: next.split_whitespace().count(),
+                removed,
+            });
+            current = next;
+        }
+
+        (current, trace)
+    }
+
+    /// Nettoie un texte sans conserver la trace
+    pub fn clean(&self, text: &str) -> String {
+        self.run(text).0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PIPELINE: &str = r#"
+[[stage]]
+stage = "strip_reasoning"
+
+[[stage]]
+stage = "lowercase"
+
+[[stage]]
+stage = "replace"
+pattern = "https?://\\S+"
+
+[[stage]]
+stage = "punctuation"
+
+[[stage]]
+stage = "stopwords"
+language = "French"
+extra = ["voilà"]
+
+[[stage]]
+stage = "min_length"
+min = 3
+"#;
+
+    #[test]
+    fn test_from_toml_and_trace() {
+        let pipeline = CleaningPipeline::from_toml(PIPELINE).unwrap();
+        assert_eq!(
+            pipeline.stage_names(),
+            vec![
+                "strip_reasoning",
+                "lowercase",
+                "replace(/https?://\\S+/)",
+                "punctuation",
+                "stopwords(French+1)",
+                "min_length(3)",
+            ]
+        );
+
+        let text =
+            "<think>caché</think>Voilà le chat, le chien et un ou deux oiseaux https://x.org";
+        let (cleaned, trace) = pipeline.run(text);
+        assert_eq!(cleaned, "chat chien deux oiseaux");
+
+        assert_eq!(trace.stages.len(), 6);
+        assert_eq!(trace.chars_before(), text.chars().count());
+        assert_eq!(trace.chars_after(), cleaned.chars().count());
+        assert!(trace.stages[0].chars_after < trace.stages[0].chars_before);
+
+        let stopwords = &trace.stages[4];
+        assert_eq!(stopwords.removed[0].token, "le");
+        assert_eq!(stopwords.removed[0].count, 2);
+        assert_eq!(stopwords.tokens_before - stopwords.tokens_after, 6);
+        assert!(trace.stages[5].removed.is_empty());
+        assert_eq!(trace.removed_tokens(), 6);
+    }
+
+    #[test]
+    fn test_matches_cleaner_config() {
+        let config = CleanerConfig {
+            dynamic_stopwords: true,
+            ..Default::default()
+        };
+        let text = "Le chat mange. Le chat dort. Le chat joue! 42";
+        let pipeline = CleaningPipeline::from_config(&config);
+        assert_eq!(
+            pipeline.clean(text),
+            crate::probe::cleaner::clean_text(text, &config)
+        );
+        assert_eq!(pipeline.stage_names()[0], "strip_reasoning");
+    }
+
+    #[test]
+    fn test_boilerplate_stage() {
+        let config = CleanerConfig {
+            strip_boilerplate: true,
+            remove_stopwords: false,
+            ..Default::default()
+        };
+        let pipeline = CleaningPipeline::from_config(&config);
+        let (cleaned, trace) = pipeline
+            .run("En tant qu'IA, je n'ai pas d'avis. Le ciel est bleu. N'hésitez pas à demander.");
+        assert_eq!(cleaned, "le ciel est bleu");
+        let stage = &trace.stages[1];
+        assert_eq!(stage.stage, "boilerplate");
+        assert_eq!(
+            stage.tokens_before,
+            stage.tokens_after + stage.removed.iter().map(|t| t.count).sum::<usize>()
+        );
+    }
+
+    #[test]
+    fn test_numerals_stage_language() {
+        let config = CleanerConfig {
+            numerals: Some(NumeralConfig::default()),
+            language: Language::English,
+            remove_numbers: false,
+            remove_punctuation: false,
+            ..Default::default()
+        };
+        let pipeline = CleaningPipeline::from_config(&config);
+        assert!(
+            pipeline
+                .stage_names()
+                .contains(&"numerals(Digits, English)".to_string())
+        );
+        // Lu à la française, "1,500" deviendrait le décimal "1.500"
+        assert_eq!(pipeline.clean("1,500 km"), "1500 km");
+
+        let pipeline = CleaningPipeline::from_toml(
+            "[[stage]]\nstage = \"numerals\"\nlanguage = \"English\"\nunits = false",
+        )
+        .unwrap();
+        assert_eq!(pipeline.clean("1,500 km"), "1500 km");
+        assert_eq!(pipeline.clean("le 3 sept 2020"), "le 2020-09-03");
+
+        // Configuration par défaut (`Both`) : la langue est détectée
+        let pipeline = CleaningPipeline::from_config(&CleanerConfig {
+            numerals: Some(NumeralConfig::default()),
+            remove_numbers: false,
+            ..Default::default()
+        });
+        assert_eq!(
+            pipeline.clean("The new stadium can hold 1,000 people during the summer concerts."),
+            pipeline
+                .clean("The new stadium can hold one thousand people during the summer concerts.")
+        );
+    }
+
+    struct Reverse;
+
+    impl CleaningStage for Reverse {
+        fn name(&self) -> String {
+            "reverse".to_string()
+        }
+
+        fn apply(&self, text: &str, _: &StageContext, _: &mut Vec<String>) -> String {
+            text.split_whitespace().rev().collect::<Vec<_>>().join(" ")
+        }
+    }
+
+    #[test]
+    fn test_custom_stage() {
+        let pipeline = CleaningPipeline::new()
+            .with_spec(StageSpec::Lowercase)
+            .unwrap()
+            .with_stage(Reverse);
+        let (cleaned, trace) = pipeline.run("Un Deux Trois");
+        assert_eq!(cleaned, "trois deux un");
+        assert_eq!(trace.stages[1].stage, "reverse");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(
+            CleaningPipeline::from_toml("[[stage]]\nstage = \"inconnue\""),
+            Err(PipelineError::Toml(_))
+        ));
+        assert!(matches!(
+            CleaningPipeline::from_specs(&[StageSpec::Replace {
+                pattern: "(".to_string(),
+                replacement: String::new(),
+            }]),
+            Err(PipelineError::Regex(_))
+        ));
+        let (text, trace) = CleaningPipeline::new().run("inchangé");
+        assert_eq!(text, "inchangé");
+        assert_eq!(trace.chars_before(), "inchangé".chars().count());
+        assert_eq!(trace.chars_after(), trace.chars_before());
+    }
+}
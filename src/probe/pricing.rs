@@ -0,0 +1,253 @@
+//! Module Pricing - Usage et coût des requêtes
+//!
+//! Table locale de prix par modèle, en USD par million de tokens, chargée
+//! depuis un fichier TOML :
+//!
+//! ```toml
+//! [models."gpt-4o"]
+//! input = 2.50
+//! output = 10.00
+//!
+//! [models."anthropic/claude-3.5-sonnet"]
+//! input = 3.00
+//! output = 15.00
+//! ```
+//!
+//! Le prix est cherché sous le nom configuré, puis sous l'identifiant
+//! renvoyé par le provider. Un identifiant daté (`gpt-4o-2024-08-06`) prend
+//! le prix de l'entrée la plus longue dont il est préfixe.
+//!
+//! Auteur: Julien DABERT
+//! LDSI - Lyapunov-Dabert Stability Index
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::injector::{Completion, CompletionMeta};
+
+/// Prix d'un modèle, en USD par million de tokens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    /// Tokens du prompt
+    pub input: f64,
+    /// Tokens générés
+    pub output: f64,
+}
+
+/// Table de prix par modèle
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PriceTable {
+    #[serde(default)]
+    pub models: HashMap<String, ModelPrice>,
+}
+
+/// Erreur de chargement de la table de prix
+#[derive(Debug, Clone, PartialEq)]
+pub enum PricingError {
+    /// Fichier illisible
+    Io(String),
+    /// Fichier TOML invalide
+    Toml(String),
+}
+
+impl std::fmt::Display for PricingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PricingError::Io(e) => write!(f, "IO error: {}", e),
+            PricingError::Toml(e) => write!(f, "TOML error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PricingError {}
+
+impl PriceTable {
+    /// Charge une table depuis du TOML (`[models."<id>"]`, `input`, `output`)
+    pub fn from_toml(source: &str) -> Result<Self, PricingError> {
+        toml::from_str(source).map_err(|e| PricingError::Toml(e.to_string()))
+    }
+
+    /// Charge une table depuis un fichier TOML
+    pub fn load(path: &str) -> Result<Self, PricingError> {
+        let source = std::fs::read_to_string(path).map_err(|e| PricingError::Io(e.to_string()))?;
+        Self::from_toml(&source)
+    }
+
+    /// Prix exact, sinon celui de l'identifiant le plus long qui préfixe `model`
+    pub fn price(&self, model: &str) -> Option<ModelPrice> {
+        if let Some(price) = self.models.get(model) {
+            return Some(*price);
+        }
+        self.models
+            .iter()
+            .filter(|(id, _)| model.starts_with(id.as_str()))
+            .max_by_key(|(id, _)| id.len())
+            .map(|(_, price)| *price)
+    }
+
+    /// Coût d'une réponse (None si modèle inconnu ou usage non déclaré)
+    pub fn cost(&self, model: &str, meta: &CompletionMeta) -> Option<f64> {
+        if meta.prompt_tokens.is_none() && meta.completion_tokens.is_none() {
+            return None;
+        }
+        let price = self
+            .price(model)
+            .or_else(|| meta.model_id.as_deref().and_then(|id| self.price(id)))?;
+        let tokens = |count: Option<u32>| count.unwrap_or(0) as f64 / 1_000_000.0;
+        Some(
+            tokens(meta.prompt_tokens) * price.input
+                + tokens(meta.completion_tokens) * price.output,
+        )
+    }
+
+    /// Usage, troncature et coût d'une réponse de `model`
+    ///
+    /// Une réponse rejouée depuis le cache (`retry.attempts == 0`) n'a rien
+    /// coûté : son coût est nul.
+    pub fn usage(&self, model: &str, completion: &Completion) -> CompletionUsage {
+        let cached = completion.retry.attempts == 0;
+        CompletionUsage {
+            meta: completion.meta.clone(),
+            latency_ms: completion.latency_ms,
+            truncated: completion.is_truncated(),
+            cached,
+            cost_usd: if cached {
+                Some(0.0)
+            } else {
+                self.cost(model, &completion.meta)
+            },
+        }
+    }
+}
+
+/// Usage d'une réponse, sans son texte (audit, serveur)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompletionUsage {
+    #[serde(flatten)]
+    pub meta: CompletionMeta,
+    /// Durée de la tentative ayant abouti en millisecondes, délais de reprise exclus
+    pub latency_ms: f64,
+    /// Coupée à `max_tokens` : le NCD compare un texte incomplet
+    pub truncated: bool,
+    /// Rejouée depuis le cache : ni tokens ni dollars dépensés
+    #[serde(default)]
+    pub cached: bool,
+    /// Coût en USD (None si prix ou usage inconnu)
+    pub cost_usd: Option<f64>,
+}
+
+/// Totaux d'une session de benchmark
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionCost {
+    pub requests: u32,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// Réponses coupées à `max_tokens`
+    pub truncated: u32,
+    /// Réponses sans prix (modèle absent de la table ou usage non déclaré)
+    pub unpriced: u32,
+    /// Réponses rejouées depuis le cache, hors tokens et coût
+    #[serde(default)]
+    pub cached: u32,
+    pub total_usd: f64,
+}
+
+impl SessionCost {
+    /// Ajoute une réponse au total
+    pub fn add(&mut self, usage: &CompletionUsage) {
+        self.requests += 1;
+        self.truncated += usage.truncated as u32;
+        if usage.cached {
+            self.cached += 1;
+            return;
+        }
+        self.prompt_tokens += usage.meta.prompt_tokens.unwrap_or(0) as u64;
+        self.completion_tokens += usage.meta.completion_tokens.unwrap_or(0) as u64;
+        match usage.cost_usd {
+            Some(cost) => self.total_usd += cost,
+            None => self.unpriced += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::probe::injector::{FinishReason, RetryStats};
+
+    const PRICES: &str = r#"
+[models."gpt-4o"]
+input = 2.5
+output = 10.0
+
+[models."gpt-4o-mini"]
+input = 0.15
+output = 0.6
+"#;
+
+    #[test]
+    fn test_price_lookup() {
+        let table = PriceTable::from_toml(PRICES).unwrap();
+        assert_eq!(table.price("gpt-4o").unwrap().input, 2.5);
+        // Le préfixe le plus long l'emporte
+        assert_eq!(table.price("gpt-4o-mini-2024-07-18").unwrap().input, 0.15);
+        assert_eq!(table.price("gpt-4o-2024-08-06").unwrap().output, 10.0);
+        assert!(table.price("llama3").is_none());
+        assert!(matches!(
+            PriceTable::from_toml("[models.x]\ninput = \"cher\""),
+            Err(PricingError::Toml(_))
+        ));
+    }
+
+    #[test]
+    fn test_cost_and_session() {
+        let table = PriceTable::from_toml(PRICES).unwrap();
+        let meta = CompletionMeta {
+            prompt_tokens: Some(1_000),
+            completion_tokens: Some(2_000),
+            finish_reason: Some(FinishReason::Length),
+            model_id: Some("gpt-4o-2024-08-06".to_string()),
+        };
+        let cost = table.cost("gpt-4o", &meta).unwrap();
+        assert!((cost - 0.0225).abs() < 1e-12, "{}", cost);
+        // Nom configuré inconnu : repli sur l'identifiant du provider
+        assert_eq!(table.cost("openai/gpt-4o", &meta), Some(cost));
+        assert_eq!(table.cost("gpt-4o", &CompletionMeta::default()), None);
+
+        let network = RetryStats {
+            attempts: 1,
+            ..Default::default()
+        };
+        let completion = Completion {
+            meta,
+            retry: network.clone(),
+            ..Default::default()
+        };
+        let unpriced = Completion {
+            retry: network,
+            ..Default::default()
+        };
+        let mut session = SessionCost::default();
+        session.add(&table.usage("gpt-4o", &completion));
+        session.add(&table.usage("llama3", &unpriced));
+        assert_eq!(session.requests, 2);
+        assert_eq!(session.truncated, 1);
+        assert_eq!(session.unpriced, 1);
+        assert_eq!(session.completion_tokens, 2_000);
+        assert!((session.total_usd - cost).abs() < 1e-12);
+
+        // Rejouée depuis le cache : rien n'est dépensé
+        let replayed = Completion {
+            retry: RetryStats::default(),
+            ..completion
+        };
+        let usage = table.usage("gpt-4o", &replayed);
+        assert!(usage.cached);
+        assert_eq!(usage.cost_usd, Some(0.0));
+        session.add(&usage);
+        assert_eq!(session.requests, 3);
+        assert_eq!(session.cached, 1);
+        assert_eq!(session.completion_tokens, 2_000);
+        assert!((session.total_usd - cost).abs() < 1e-12);
+    }
+}
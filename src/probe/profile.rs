@@ -0,0 +1,186 @@
+//! Module Profile - Endpoints nommés (TOML)
+//!
+//! Un profil décrit un endpoint une fois pour toutes : type d'API, URL,
+//! modèle, forme de l'endpoint et variable d'environnement de la clé (la
+//! clé elle-même n'est jamais écrite dans le fichier) :
+//!
+//! ```toml
+//! [profiles.azure-gpt4o]
+//! api_type = "OpenAICompatible"
+//! base_url = "https://ma-ressource.openai.azure.com"
+//! model = "gpt-4o"
+//! api_key_env = "AZURE_OPENAI_KEY"
+//! path = "/openai/deployments/gpt-4o/chat/completions"
+//! auth = { header = "api-key" }
+//! query = { api-version = "2024-06-01" }
+//!
+//! [profiles.vllm]
+//! api_type = "OpenAICompatible"
+//! base_url = "http://gpu-01:8000"
+//! model = "mistralai/Mistral-7B-Instruct-v0.3"
+//! auth = "none"
+//! ```
+//!
+//! Auteur: Julien DABERT
+//! LDSI - Lyapunov-Dabert Stability Index
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::injector::{ApiType, EndpointConfig, LlmConfig};
+
+/// Endpoint nommé
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub api_type: ApiType,
+    pub base_url: String,
+    pub model: String,
+    /// Variable d'environnement contenant la clé API
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    /// Timeout en secondes (défaut de `LlmConfig` sinon)
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Chemin, en-têtes, authentification et paramètres de requête
+    #[serde(flatten)]
+    pub endpoint: EndpointConfig,
+}
+
+/// Fichier de profils (`[profiles.<nom>]`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfileSet {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// Erreur de chargement ou de résolution d'un profil
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProfileError {
+    /// Fichier illisible
+    Io(String),
+    /// Fichier TOML invalide
+    Toml(String),
+    /// Aucun profil de ce nom
+    Unknown(String),
+    /// Variable d'environnement de la clé absente
+    MissingKey(String),
+}
+
+impl std::fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProfileError::Io(e) => write!(f, "IO error: {}", e),
+            ProfileError::Toml(e) => write!(f, "TOML error: {}", e),
+            ProfileError::Unknown(name) => write!(f, "Profil inconnu: {}", name),
+            ProfileError::MissingKey(var) => {
+                write!(f, "Variable d'environnement {} non définie", var)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+impl ProfileSet {
+    /// Charge des profils depuis du TOML
+    pub fn from_toml(source: &str) -> Result<Self, ProfileError> {
+        toml::from_str(source).map_err(|e| ProfileError::Toml(e.to_string()))
+    }
+
+    /// Charge des profils depuis un fichier TOML
+    pub fn load(path: &str) -> Result<Self, ProfileError> {
+        let source = std::fs::read_to_string(path).map_err(|e| ProfileError::Io(e.to_string()))?;
+        Self::from_toml(&source)
+    }
+
+    /// Configuration du profil `name`, clé lue dans l'environnement
+    pub fn config(&self, name: &str) -> Result<LlmConfig, ProfileError> {
+        self.profiles
+            .get(name)
+            .ok_or_else(|| ProfileError::Unknown(name.to_string()))?
+            .to_config()
+    }
+}
+
+impl Profile {
+    /// Configuration de l'injecteur (échantillonnage et reprises par défaut)
+    pub fn to_config(&self) -> Result<LlmConfig, ProfileError> {
+        let api_key = match &self.api_key_env {
+            Some(var) => {
+                Some(std::env::var(var).map_err(|_| ProfileError::MissingKey(var.clone()))?)
+            }
+            None => None,
+        };
+        let defaults = LlmConfig::default();
+        Ok(LlmConfig {
+            base_url: self.base_url.clone(),
+            model: self.model.clone(),
+            api_key,
+            api_type: self.api_type,
+            timeout_secs: self.timeout_secs.unwrap_or(defaults.timeout_secs),
+            endpoint: self.endpoint.clone(),
+            ..defaults
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::probe::injector::AuthScheme;
+
+    const PROFILES: &str = r#"
+[profiles.azure]
+api_type = "OpenAICompatible"
+base_url = "https://ressource.openai.azure.com"
+model = "gpt-4o"
+api_key_env = "LDSI_TEST_PROFILE_ABSENT"
+path = "/openai/deployments/gpt-4o/chat/completions"
+auth = { header = "api-key" }
+query = { api-version = "2024-06-01" }
+
+[profiles.vllm]
+api_type = "OpenAICompatible"
+base_url = "http://gpu-01:8000"
+model = "mistral-7b"
+auth = "none"
+timeout_secs = 600
+headers = { X-Team = "ldsi" }
+"#;
+
+    #[test]
+    fn test_profiles() {
+        let set = ProfileSet::from_toml(PROFILES).unwrap();
+        let azure = &set.profiles["azure"];
+        assert_eq!(
+            azure.endpoint.auth,
+            AuthScheme::Header("api-key".to_string())
+        );
+        assert_eq!(azure.endpoint.query["api-version"], "2024-06-01");
+
+        let vllm = set.config("vllm").unwrap();
+        assert_eq!(vllm.api_type, ApiType::OpenAICompatible);
+        assert_eq!(vllm.endpoint.auth, AuthScheme::None);
+        assert_eq!(vllm.endpoint.path, None);
+        assert_eq!(vllm.endpoint.headers["X-Team"], "ldsi");
+        assert_eq!(vllm.timeout_secs, 600);
+        assert_eq!(vllm.api_key, None);
+    }
+
+    #[test]
+    fn test_profile_errors() {
+        let set = ProfileSet::from_toml(PROFILES).unwrap();
+        assert_eq!(
+            set.config("absent").unwrap_err(),
+            ProfileError::Unknown("absent".to_string())
+        );
+        assert_eq!(
+            set.config("azure").unwrap_err().to_string(),
+            "Variable d'environnement LDSI_TEST_PROFILE_ABSENT non définie"
+        );
+        assert!(matches!(
+            ProfileSet::from_toml("[profiles.x]\napi_type = \"Cohere\""),
+            Err(ProfileError::Toml(_))
+        ));
+    }
+}
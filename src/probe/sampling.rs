@@ -0,0 +1,271 @@
+//! Module Sampling - Paramètres d'échantillonnage contrôlés
+//!
+//! Graine, nucleus (top_p), top_k, séquences d'arrêt et pénalités, traduits
+//! dans le vocabulaire de chaque provider. Un paramètre que le provider
+//! ignorerait est refusé avant l'envoi : un run n'est reproductible que si
+//! tout ce qui est demandé est effectivement appliqué.
+//!
+//! | Paramètre           | OpenAI | OpenRouter | Ollama | Anthropic | Gemini | Mistral |
+//! |---------------------|--------|------------|--------|-----------|--------|---------|
+//! | `seed`              | oui    | oui        | oui    | non       | oui    | oui     |
+//! | `top_p`             | oui    | oui        | oui    | oui       | oui    | oui     |
+//! | `top_k`             | non    | oui        | oui    | oui       | oui    | non     |
+//! | `stop`              | oui (4)| oui        | oui    | oui       | oui (5)| oui     |
+//! | `frequency_penalty` | oui    | oui        | oui    | non       | oui    | oui     |
+//! | `presence_penalty`  | oui    | oui        | oui    | non       | oui    | oui     |
+//! | `repeat_penalty`    | non    | oui        | oui    | non       | non    | non     |
+//!
+//! Mistral reçoit la graine sous le nom `random_seed`, Gemini sous `seed`
+//! dans `generationConfig`.
+//!
+//! Une passerelle OpenAI-compatible reçoit tous les paramètres : c'est le
+//! moteur derrière elle (vLLM, LiteLLM...) qui les applique.
+//!
+//! Auteur: Julien DABERT
+//! LDSI - Lyapunov-Dabert Stability Index
+
+use serde::{Deserialize, Serialize};
+
+use super::injector::ApiType;
+
+/// Nombre maximal de séquences d'arrêt acceptées par OpenAI
+const OPENAI_MAX_STOP: usize = 4;
+
+/// Nombre maximal de séquences d'arrêt acceptées par Gemini
+const GEMINI_MAX_STOP: usize = 5;
+
+/// Paramètres d'échantillonnage au-delà de la température
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SamplingParams {
+    /// Graine du tirage (reproductibilité)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Masse de probabilité conservée (nucleus sampling), dans ]0, 1]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Nombre de tokens candidats conservés
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_k: Option<u32>,
+    /// Séquences qui arrêtent la génération
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+    /// Pénalité proportionnelle à la fréquence d'un token, dans [-2, 2]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    /// Pénalité de présence d'un token déjà émis, dans [-2, 2]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    /// Pénalité multiplicative de répétition (1.0 = neutre)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repeat_penalty: Option<f32>,
+}
+
+/// Paramètre refusé ou hors bornes
+#[derive(Debug, Clone, PartialEq)]
+pub enum SamplingError {
+    /// Paramètres que le provider ne sait pas appliquer
+    Unsupported {
+        api_type: ApiType,
+        parameters: Vec<&'static str>,
+    },
+    /// Valeur hors de l'intervalle accepté
+    OutOfRange(String),
+}
+
+impl std::fmt::Display for SamplingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SamplingError::Unsupported {
+                api_type,
+                parameters,
+            } => write!(
+                f,
+                "Paramètres non supportés par {:?}: {}",
+                api_type,
+                parameters.join(", ")
+            ),
+            SamplingError::OutOfRange(e) => write!(f, "Paramètre hors bornes: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SamplingError {}
+
+/// Le provider applique-t-il ce paramètre ?
+fn supports(api_type: ApiType, parameter: &str) -> bool {
+    match api_type {
+        // Une passerelle compatible transmet tout au moteur qu'elle expose
+        ApiType::OpenRouter | ApiType::Ollama | ApiType::OpenAICompatible => true,
+        ApiType::OpenAI | ApiType::Mistral => !matches!(parameter, "top_k" | "repeat_penalty"),
+        ApiType::Anthropic => matches!(parameter, "top_p" | "top_k" | "stop"),
+        ApiType::Gemini => parameter != "repeat_penalty",
+    }
+}
+
+impl SamplingParams {
+    /// Noms des paramètres renseignés
+    pub fn set_parameters(&self) -> Vec<&'static str> {
+        [
+            ("seed", self.seed.is_some()),
+            ("top_p", self.top_p.is_some()),
+            ("top_k", self.top_k.is_some()),
+            ("stop", !self.stop.is_empty()),
+            ("frequency_penalty", self.frequency_penalty.is_some()),
+            ("presence_penalty", self.presence_penalty.is_some()),
+            ("repeat_penalty", self.repeat_penalty.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, set)| set.then_some(name))
+        .collect()
+    }
+
+    /// Vérifie que le provider applique chaque paramètre et que les valeurs
+    /// sont dans leurs bornes
+    pub fn validate(&self, api_type: ApiType) -> Result<(), SamplingError> {
+        let parameters: Vec<&'static str> = self
+            .set_parameters()
+            .into_iter()
+            .filter(|p| !supports(api_type, p))
+            .collect();
+        if !parameters.is_empty() {
+            return Err(SamplingError::Unsupported {
+                api_type,
+                parameters,
+            });
+        }
+
+        if let Some(top_p) = self.top_p
+            && !(top_p > 0.0 && top_p <= 1.0)
+        {
+            return Err(SamplingError::OutOfRange(format!(
+                "top_p = {} (attendu dans ]0, 1])",
+                top_p
+            )));
+        }
+        for (name, value) in [
+            ("frequency_penalty", self.frequency_penalty),
+            ("presence_penalty", self.presence_penalty),
+        ] {
+            if let Some(value) = value
+                && !(-2.0..=2.0).contains(&value)
+            {
+                return Err(SamplingError::OutOfRange(format!(
+                    "{} = {} (attendu dans [-2, 2])",
+                    name, value
+                )));
+            }
+        }
+        if let Some(penalty) = self.repeat_penalty
+            && penalty <= 0.0
+        {
+            return Err(SamplingError::OutOfRange(format!(
+                "repeat_penalty = {} (attendu > 0)",
+                penalty
+            )));
+        }
+        let max_stop = match api_type {
+            ApiType::OpenAI => Some(OPENAI_MAX_STOP),
+            ApiType::Gemini => Some(GEMINI_MAX_STOP),
+            _ => None,
+        };
+        if let Some(max) = max_stop
+            && self.stop.len() > max
+        {
+            return Err(SamplingError::OutOfRange(format!(
+                "{} séquences d'arrêt ({:?} en accepte {})",
+                self.stop.len(),
+                api_type,
+                max
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Paramètres effectivement appliqués à une requête, pour l'audit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EffectiveSampling {
+    pub api_type: ApiType,
+    pub model: String,
+    pub temperature: f32,
+    pub max_tokens: u32,
+    #[serde(flatten)]
+    pub params: SamplingParams,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_support() {
+        let params = SamplingParams {
+            seed: Some(42),
+            top_k: Some(40),
+            repeat_penalty: Some(1.1),
+            ..Default::default()
+        };
+        assert!(params.validate(ApiType::Ollama).is_ok());
+        assert!(params.validate(ApiType::OpenRouter).is_ok());
+        assert_eq!(
+            params.validate(ApiType::OpenAI),
+            Err(SamplingError::Unsupported {
+                api_type: ApiType::OpenAI,
+                parameters: vec!["top_k", "repeat_penalty"],
+            })
+        );
+        assert_eq!(
+            params.validate(ApiType::Anthropic).unwrap_err().to_string(),
+            "Paramètres non supportés par Anthropic: seed, repeat_penalty"
+        );
+        assert!(
+            SamplingParams::default()
+                .validate(ApiType::Anthropic)
+                .is_ok()
+        );
+        assert_eq!(
+            params.validate(ApiType::Gemini).unwrap_err().to_string(),
+            "Paramètres non supportés par Gemini: repeat_penalty"
+        );
+        assert_eq!(
+            params.validate(ApiType::Mistral).unwrap_err().to_string(),
+            "Paramètres non supportés par Mistral: top_k, repeat_penalty"
+        );
+    }
+
+    #[test]
+    fn test_ranges() {
+        let out_of_range = |params: SamplingParams| {
+            matches!(
+                params.validate(ApiType::Ollama),
+                Err(SamplingError::OutOfRange(_))
+            )
+        };
+        assert!(out_of_range(SamplingParams {
+            top_p: Some(0.0),
+            ..Default::default()
+        }));
+        assert!(out_of_range(SamplingParams {
+            presence_penalty: Some(2.5),
+            ..Default::default()
+        }));
+        assert!(out_of_range(SamplingParams {
+            repeat_penalty: Some(0.0),
+            ..Default::default()
+        }));
+        let stops = SamplingParams {
+            stop: (0..5).map(|i| i.to_string()).collect(),
+            ..Default::default()
+        };
+        assert!(stops.validate(ApiType::Ollama).is_ok());
+        assert!(stops.validate(ApiType::OpenAI).is_err());
+        assert!(stops.validate(ApiType::Gemini).is_ok());
+        let stops = SamplingParams {
+            stop: (0..6).map(|i| i.to_string()).collect(),
+            ..Default::default()
+        };
+        assert!(stops.validate(ApiType::Gemini).is_err());
+        assert!(stops.validate(ApiType::Mistral).is_ok());
+    }
+}
@@ -0,0 +1,704 @@
+//! Module Stemmer - Racinisation Déterministe FR/EN
+//!
+//! Implémentation à règles des algorithmes Snowball français et anglais
+//! (Porter2), sans dictionnaire ni modèle : "chats" et "chat", ou
+//! "transcends" et "transcendent", partagent une même racine.
+//!
+//! Sans racinisation, chaque flexion est un type distinct : TTR et hapax
+//! sont gonflés et le graphe de co-occurrence est fragmenté.
+//!
+//! Auteur: Julien DABERT
+//! LDSI - Lyapunov-Dabert Stability Index
+
+use super::cleaner::Language;
+use super::language::detect_language;
+
+/// Racinisateur d'un mot (minuscules attendues)
+pub type Stemmer = fn(&str) -> String;
+
+/// Racinisateur adapté à la langue du texte
+///
+/// `Both` et `Auto` choisissent entre français et anglais par détection.
+/// None pour les autres langues (pas de racinisation).
+pub fn stemmer_for(language: Language, text: &str) -> Option<Stemmer> {
+    let language = match language {
+        Language::Both | Language::Auto => detect_language(text)?,
+        other => other,
+    };
+    match language {
+        Language::French => Some(stem_french),
+        Language::English => Some(stem_english),
+        _ => None,
+    }
+}
+
+// ============ Outils communs ============
+
+fn char_len(s: &str) -> usize {
+    s.chars().count()
+}
+
+fn ends(w: &[char], suffix: &str) -> bool {
+    let n = char_len(suffix);
+    n <= w.len() && w[w.len() - n..].iter().copied().eq(suffix.chars())
+}
+
+/// Début du suffixe (indice en caractères)
+fn start(w: &[char], suffix: &str) -> usize {
+    w.len() - char_len(suffix)
+}
+
+fn truncate(w: &mut Vec<char>, suffix: &str) {
+    w.truncate(start(w, suffix));
+}
+
+fn replace(w: &mut Vec<char>, suffix: &str, by: &str) {
+    truncate(w, suffix);
+    w.extend(by.chars());
+}
+
+/// Plus long suffixe de la liste commençant au plus tôt à `from`
+fn longest<'a>(w: &[char], suffixes: &[&'a str], from: usize) -> Option<&'a str> {
+    suffixes
+        .iter()
+        .filter(|s| ends(w, s) && start(w, s) >= from)
+        .max_by_key(|s| char_len(s))
+        .copied()
+}
+
+/// Région après la première non-voyelle suivant une voyelle, à partir de `from`
+fn region_after(w: &[char], from: usize, vowel: fn(char) -> bool) -> usize {
+    (from + 1..w.len())
+        .find(|&i| vowel(w[i - 1]) && !vowel(w[i]))
+        .map_or(w.len(), |i| i + 1)
+}
+
+// ============ Anglais (Porter2) ============
+
+fn is_vowel_en(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y')
+}
+
+fn ends_short_syllable(w: &[char]) -> bool {
+    match w.len() {
+        0 | 1 => false,
+        2 => is_vowel_en(w[0]) && !is_vowel_en(w[1]),
+        n => {
+            !is_vowel_en(w[n - 3])
+                && is_vowel_en(w[n - 2])
+                && !is_vowel_en(w[n - 1])
+                && !matches!(w[n - 1], 'w' | 'x' | 'Y')
+        }
+    }
+}
+
+/// Racinise un mot anglais (Snowball English / Porter2)
+pub fn stem_english(word: &str) -> String {
+    if char_len(word) <= 2 {
+        return word.to_string();
+    }
+    let invariant = [
+        "sky", "news", "howe", "atlas", "cosmos", "bias", "andes", "inning", "outing", "canning",
+        "herring", "earring", "proceed", "exceed", "succeed",
+    ];
+    if invariant.contains(&word) {
+        return word.to_string();
+    }
+    let exception = match word {
+        "skis" => Some("ski"),
+        "skies" => Some("sky"),
+        "dying" => Some("die"),
+        "lying" => Some("lie"),
+        "tying" => Some("tie"),
+        "idly" => Some("idl"),
+        "gently" => Some("gentl"),
+        "ugly" => Some("ugli"),
+        "early" => Some("earli"),
+        "only" => Some("onli"),
+        "singly" => Some("singl"),
+        _ => None,
+    };
+    if let Some(stem) = exception {
+        return stem.to_string();
+    }
+
+    let mut w: Vec<char> = word.trim_start_matches('\'').chars().collect();
+    if w.len() <= 2 {
+        return w.iter().collect();
+    }
+    for i in 0..w.len() {
+        if w[i] == 'y' && (i == 0 || is_vowel_en(w[i - 1])) {
+            w[i] = 'Y';
+        }
+    }
+    let prefix = ["gener", "commun", "arsen"]
+        .iter()
+        .find(|p| w.iter().copied().take(p.len()).eq(p.chars()));
+    let r1 = prefix.map_or_else(|| region_after(&w, 0, is_vowel_en), |p| p.len());
+    let r2 = region_after(&w, r1, is_vowel_en);
+    let vowel_in = |w: &[char]| w.iter().any(|&c| is_vowel_en(c));
+
+    // Step 0 : apostrophes
+    if let Some(s) = longest(&w, &["'s'", "'s", "'"], 0) {
+        truncate(&mut w, s);
+    }
+
+    // Step 1a : pluriels
+    match longest(&w, &["sses", "ied", "ies", "us", "ss", "s"], 0) {
+        Some("sses") => w.truncate(w.len() - 2),
+        Some(s @ ("ied" | "ies")) => {
+            let keep = if w.len() > 4 { "i" } else { "ie" };
+            replace(&mut w, s, keep);
+        }
+        Some("s") if vowel_in(&w[..w.len() - 2]) => {
+            w.pop();
+        }
+        _ => {}
+    }
+    let word_1a: String = w.iter().collect();
+    if invariant.contains(&word_1a.as_str()) {
+        return word_1a;
+    }
+
+    // Step 1b : -ed, -ing
+    match longest(&w, &["eed", "eedly", "ed", "edly", "ing", "ingly"], 0) {
+        Some(s @ ("eed" | "eedly")) if start(&w, s) >= r1 => replace(&mut w, s, "ee"),
+        Some("eed" | "eedly") => {}
+        Some(s) if vowel_in(&w[..start(&w, s)]) => {
+            truncate(&mut w, s);
+            if ends(&w, "at") || ends(&w, "bl") || ends(&w, "iz") {
+                w.push('e');
+            } else if ["bb", "dd", "ff", "gg", "mm", "nn", "pp", "rr", "tt"]
+                .iter()
+                .any(|d| ends(&w, d))
+            {
+                w.pop();
+            } else if ends_short_syllable(&w) && r1 >= w.len() {
+                w.push('e');
+            }
+        }
+        _ => {}
+    }
+
+    // Step 1c : y final
+    let n = w.len();
+    if n > 2 && matches!(w[n - 1], 'y' | 'Y') && !is_vowel_en(w[n - 2]) {
+        w[n - 1] = 'i';
+    }
+
+    // Step 2
+    const STEP2: &[(&str, &str)] = &[
+        ("tional", "tion"),
+        ("enci", "ence"),
+        ("anci", "ance"),
+        ("abli", "able"),
+        ("entli", "ent"),
+        ("izer", "ize"),
+        ("ization", "ize"),
+        ("ational", "ate"),
+        ("ation", "ate"),
+        ("ator", "ate"),
+        ("alism", "al"),
+        ("aliti", "al"),
+        ("alli", "al"),
+        ("fulness", "ful"),
+        ("ousli", "ous"),
+        ("ousness", "ous"),
+        ("iveness", "ive"),
+        ("iviti", "ive"),
+        ("biliti", "ble"),
+        ("bli", "ble"),
+        ("ogi", "og"),
+        ("fulli", "ful"),
+        ("lessli", "less"),
+        ("li", ""),
+    ];
+    let suffixes: Vec<&str> = STEP2.iter().map(|(s, _)| *s).collect();
+    if let Some(s) = longest(&w, &suffixes, 0) {
+        let at = start(&w, s);
+        let before = at.checked_sub(1).map(|i| w[i]);
+        let allowed = match s {
+            "ogi" => before == Some('l'),
+            "li" => matches!(
+                before,
+                Some('c' | 'd' | 'e' | 'g' | 'h' | 'k' | 'm' | 'n' | 'r' | 't')
+            ),
+            _ => true,
+        };
+        if at >= r1 && allowed {
+            let by = STEP2.iter().find(|(suffix, _)| *suffix == s).unwrap().1;
+            replace(&mut w, s, by);
+        }
+    }
+
+    // Step 3
+    const STEP3: &[(&str, &str)] = &[
+        ("tional", "tion"),
+        ("ational", "ate"),
+        ("alize", "al"),
+        ("icate", "ic"),
+        ("iciti", "ic"),
+        ("ical", "ic"),
+        ("ful", ""),
+        ("ness", ""),
+        ("ative", ""),
+    ];
+    let suffixes: Vec<&str> = STEP3.iter().map(|(s, _)| *s).collect();
+    if let Some(s) = longest(&w, &suffixes, 0) {
+        let at = start(&w, s);
+        if at >= r1 && (s != "ative" || at >= r2) {
+            let by = STEP3.iter().find(|(suffix, _)| *suffix == s).unwrap().1;
+            replace(&mut w, s, by);
+        }
+    }
+
+    // Step 4
+    const STEP4: &[&str] = &[
+        "al", "ance", "ence", "er", "ic", "able", "ible", "ant", "ement", "ment", "ent", "ism",
+        "ate", "iti", "ous", "ive", "ize", "ion",
+    ];
+    if let Some(s) = longest(&w, STEP4, 0) {
+        let at = start(&w, s);
+        let allowed = s != "ion" || (at > 0 && matches!(w[at - 1], 's' | 't'));
+        if at >= r2 && allowed {
+            truncate(&mut w, s);
+        }
+    }
+
+    // Step 5
+    let n = w.len();
+    if n > 0 && w[n - 1] == 'e' {
+        let at = n - 1;
+        if at >= r2 || (at >= r1 && !ends_short_syllable(&w[..at])) {
+            w.pop();
+        }
+    } else if n > 1 && w[n - 1] == 'l' && n > r2 && w[n - 2] == 'l' {
+        w.pop();
+    }
+
+    w.iter().map(|&c| if c == 'Y' { 'y' } else { c }).collect()
+}
+
+// ============ Français ============
+
+fn is_vowel_fr(c: char) -> bool {
+    matches!(
+        c,
+        'a' | 'e'
+            | 'i'
+            | 'o'
+            | 'u'
+            | 'y'
+            | 'â'
+            | 'à'
+            | 'ë'
+            | 'é'
+            | 'ê'
+            | 'è'
+            | 'ï'
+            | 'î'
+            | 'ô'
+            | 'û'
+            | 'ù'
+    )
+}
+
+/// Étape 1 : suffixes dérivationnels
+///
+/// # Returns
+/// (mot modifié, étape 2a à tenter)
+fn french_standard_suffix(w: &mut Vec<char>, rv: usize, r1: usize, r2: usize) -> (bool, bool) {
+    const STEP1: &[&str] = &[
+        "ance",
+        "iqUe",
+        "isme",
+        "able",
+        "iste",
+        "eux",
+        "ances",
+        "iqUes",
+        "ismes",
+        "ables",
+        "istes",
+        "atrice",
+        "ateur",
+        "ation",
+        "atrices",
+        "ateurs",
+        "ations",
+        "logie",
+        "logies",
+        "usion",
+        "ution",
+        "usions",
+        "utions",
+        "ence",
+        "ences",
+        "ement",
+        "ements",
+        "ité",
+        "ités",
+        "if",
+        "ive",
+        "ifs",
+        "ives",
+        "eaux",
+        "aux",
+        "euse",
+        "euses",
+        "issement",
+        "issements",
+        "amment",
+        "emment",
+        "ment",
+        "ments",
+    ];
+    let Some(s) = longest(w, STEP1, 0) else {
+        return (false, true);
+    };
+    let at = start(w, s);
+    // "ic" précédant : supprimé en R2, sinon réécrit en "iqU"
+    let ic = |w: &mut Vec<char>| {
+        if ends(w, "ic") {
+            if start(w, "ic") >= r2 {
+                truncate(w, "ic");
+            } else {
+                replace(w, "ic", "iqU");
+            }
+        }
+    };
+
+    match s {
+        "ance" | "iqUe" | "isme" | "able" | "iste" | "eux" | "ances" | "iqUes" | "ismes"
+        | "ables" | "istes" => {
+            if at < r2 {
+                return (false, true);
+            }
+            truncate(w, s);
+        }
+        "atrice" | "ateur" | "ation" | "atrices" | "ateurs" | "ations" => {
+            if at < r2 {
+                return (false, true);
+            }
+            truncate(w, s);
+            ic(w);
+        }
+        "logie" | "logies" => {
+            if at < r2 {
+                return (false, true);
+            }
+            replace(w, s, "log");
+        }
+        "usion" | "ution" | "usions" | "utions" => {
+            if at < r2 {
+                return (false, true);
+            }
+            replace(w, s, "u");
+        }
+        "ence" | "ences" => {
+            if at < r2 {
+                return (false, true);
+            }
+            replace(w, s, "ent");
+        }
+        "ement" | "ements" => {
+            if at < rv {
+                return (false, true);
+            }
+            truncate(w, s);
+            if ends(w, "iv") {
+                if start(w, "iv") >= r2 {
+                    truncate(w, "iv");
+                    if ends(w, "at") && start(w, "at") >= r2 {
+                        truncate(w, "at");
+                    }
+                }
+            } else if ends(w, "eus") {
+                if start(w, "eus") >= r2 {
+                    truncate(w, "eus");
+                } else if start(w, "eus") >= r1 {
+                    replace(w, "eus", "eux");
+                }
+            } else if ends(w, "abl") || ends(w, "iqU") {
+                if start(w, "abl") >= r2 {
+                    w.truncate(w.len() - 3);
+                }
+            } else if (ends(w, "ièr") || ends(w, "Ièr")) && start(w, "ièr") >= rv {
+                replace(w, "ièr", "i");
+            }
+        }
+        "ité" | "ités" => {
+            if at < r2 {
+                return (false, true);
+            }
+            truncate(w, s);
+            if ends(w, "abil") {
+                if start(w, "abil") >= r2 {
+                    truncate(w, "abil");
+                } else {
+                    replace(w, "abil", "abl");
+                }
+            } else if ends(w, "ic") {
+                ic(w);
+            } else if ends(w, "iv") && start(w, "iv") >= r2 {
+                truncate(w, "iv");
+            }
+        }
+        "if" | "ive" | "ifs" | "ives" => {
+            if at < r2 {
+                return (false, true);
+            }
+            truncate(w, s);
+            if ends(w, "at") && start(w, "at") >= r2 {
+                truncate(w, "at");
+                ic(w);
+            }
+        }
+        "eaux" => replace(w, s, "eau"),
+        "aux" => {
+            if at < r1 {
+                return (false, true);
+            }
+            replace(w, s, "al");
+        }
+        "euse" | "euses" => {
+            if at >= r2 {
+                truncate(w, s);
+            } else if at >= r1 {
+                replace(w, s, "eux");
+            } else {
+                return (false, true);
+            }
+        }
+        "issement" | "issements" => {
+            if at < r1 || at == 0 || is_vowel_fr(w[at - 1]) {
+                return (false, true);
+            }
+            truncate(w, s);
+        }
+        // Suffixes adverbiaux : l'étape 2a est tentée dans tous les cas
+        "amment" => {
+            if at < rv {
+                return (false, true);
+            }
+            replace(w, s, "ant");
+            return (true, true);
+        }
+        "emment" => {
+            if at < rv {
+                return (false, true);
+            }
+            replace(w, s, "ent");
+            return (true, true);
+        }
+        _ => {
+            // "ment", "ments" : précédé d'une voyelle dans RV
+            if at == 0 || at - 1 < rv || !is_vowel_fr(w[at - 1]) {
+                return (false, true);
+            }
+            truncate(w, s);
+            return (true, true);
+        }
+    }
+    (true, false)
+}
+
+/// Racinise un mot français (Snowball French)
+pub fn stem_french(word: &str) -> String {
+    let mut w: Vec<char> = word.chars().collect();
+    if w.len() <= 2 {
+        return word.to_string();
+    }
+
+    // Marquage des semi-voyelles (non-voyelles en majuscule)
+    for i in 0..w.len() {
+        let before = i > 0 && is_vowel_fr(w[i - 1]);
+        let after = i + 1 < w.len() && is_vowel_fr(w[i + 1]);
+        match w[i] {
+            'u' | 'i' if before && after => w[i] = w[i].to_ascii_uppercase(),
+            'y' if before || after => w[i] = 'Y',
+            'u' if i > 0 && w[i - 1] == 'q' => w[i] = 'U',
+            _ => {}
+        }
+    }
+
+    let n = w.len();
+    let prefixed = ["par", "col", "tap"]
+        .iter()
+        .any(|p| w.iter().copied().take(3).eq(p.chars()));
+    let rv = if (is_vowel_fr(w[0]) && is_vowel_fr(w[1])) || prefixed {
+        3
+    } else {
+        (1..n).find(|&i| is_vowel_fr(w[i])).map_or(n, |i| i + 1)
+    };
+    let r1 = region_after(&w, 0, is_vowel_fr);
+    let r2 = region_after(&w, r1, is_vowel_fr);
+
+    // Étape 1 puis, si besoin, étapes 2a/2b (suffixes verbaux, dans RV)
+    let (mut altered, try_verb) = french_standard_suffix(&mut w, rv, r1, r2);
+    if try_verb {
+        const STEP2A: &[&str] = &[
+            "îmes", "ît", "îtes", "i", "ie", "ies", "ir", "ira", "irai", "iraIent", "irais",
+            "irait", "iras", "irent", "irez", "iriez", "irions", "irons", "iront", "is",
+            "issaIent", "issais", "issait", "issant", "issante", "issantes", "issants", "isse",
+            "issent", "isses", "issez", "issiez", "issions", "issons", "it",
+        ];
+        const STEP2B_E: &[&str] = &[
+            "é", "ée", "ées", "és", "èrent", "er", "era", "erai", "eraIent", "erais", "erait",
+            "eras", "erez", "eriez", "erions", "erons", "eront", "ez", "iez",
+        ];
+        const STEP2B_A: &[&str] = &[
+            "âmes", "ât", "âtes", "a", "ai", "aIent", "ais", "ait", "ant", "ante", "antes", "ants",
+            "as", "asse", "assent", "asses", "assiez", "assions",
+        ];
+
+        let verb_i = longest(&w, STEP2A, rv).filter(|s| {
+            let at = start(&w, s);
+            at > rv && !is_vowel_fr(w[at - 1])
+        });
+        if let Some(s) = verb_i {
+            truncate(&mut w, s);
+            altered = true;
+        } else {
+            let all: Vec<&str> = ["ions"]
+                .iter()
+                .chain(STEP2B_E)
+                .chain(STEP2B_A)
+                .copied()
+                .collect();
+            match longest(&w, &all, rv) {
+                Some("ions") if start(&w, "ions") >= r2 => {
+                    truncate(&mut w, "ions");
+                    altered = true;
+                }
+                Some("ions") => {}
+                Some(s) if STEP2B_E.contains(&s) => {
+                    truncate(&mut w, s);
+                    altered = true;
+                }
+                Some(s) => {
+                    truncate(&mut w, s);
+                    altered = true;
+                    if ends(&w, "e") && start(&w, "e") >= rv {
+                        w.pop();
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+
+    if altered {
+        // Étape 3
+        match w.last() {
+            Some('Y') => *w.last_mut().unwrap() = 'i',
+            Some('ç') => *w.last_mut().unwrap() = 'c',
+            _ => {}
+        }
+    } else {
+        // Étape 4 : suffixes résiduels
+        let n = w.len();
+        if n > 1 && w[n - 1] == 's' && !matches!(w[n - 2], 'a' | 'i' | 'o' | 'u' | 'è' | 's') {
+            w.pop();
+        }
+        match longest(&w, &["ion", "ier", "ière", "Ier", "Ière", "e", "ë"], rv) {
+            Some("ion") => {
+                let at = start(&w, "ion");
+                if at >= r2 && at > rv && matches!(w[at - 1], 's' | 't') {
+                    truncate(&mut w, "ion");
+                }
+            }
+            Some(s @ ("ier" | "ière" | "Ier" | "Ière")) => replace(&mut w, s, "i"),
+            Some("e") => {
+                w.pop();
+            }
+            Some("ë") if ends(&w[..w.len() - 1], "gu") => {
+                w.pop();
+            }
+            _ => {}
+        }
+    }
+
+    // Étape 5 : consonnes doublées
+    if ["enn", "onn", "ett", "ell", "eill"]
+        .iter()
+        .any(|s| ends(&w, s))
+    {
+        w.pop();
+    }
+
+    // Étape 6 : é/è devant des consonnes finales
+    let consonants = w.iter().rev().take_while(|&&c| !is_vowel_fr(c)).count();
+    if consonants > 0 && consonants < w.len() {
+        let i = w.len() - consonants - 1;
+        if matches!(w[i], 'é' | 'è') {
+            w[i] = 'e';
+        }
+    }
+
+    w.iter()
+        .map(|&c| match c {
+            'I' => 'i',
+            'U' => 'u',
+            'Y' => 'y',
+            other => other,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_porter2() {
+        let cases = [
+            ("caresses", "caress"),
+            ("ponies", "poni"),
+            ("ties", "tie"),
+            ("cats", "cat"),
+            ("running", "run"),
+            ("hopping", "hop"),
+            ("hoped", "hope"),
+            ("agreed", "agre"),
+            ("happiness", "happi"),
+            ("generously", "generous"),
+            ("relational", "relat"),
+            ("transcendent", "transcend"),
+            ("transcends", "transcend"),
+            ("skies", "sky"),
+            ("news", "news"),
+            ("dying", "die"),
+        ];
+        for (word, expected) in cases {
+            assert_eq!(stem_english(word), expected, "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_french_snowball() {
+        let cases = [
+            ("chats", "chat"),
+            ("chat", "chat"),
+            ("continuellement", "continuel"),
+            ("abandonnée", "abandon"),
+            ("abandonnaient", "abandon"),
+            ("chevaux", "cheval"),
+            ("nationalité", "national"),
+            ("transcende", "transcend"),
+            ("transcender", "transcend"),
+        ];
+        for (word, expected) in cases {
+            assert_eq!(stem_french(word), expected, "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_stemmer_for() {
+        let english = "The cats are sleeping on the couch while we are cooking dinner.";
+        let stem = stemmer_for(Language::Both, english).unwrap();
+        assert_eq!(stem("cats"), "cat");
+        assert!(stemmer_for(Language::Spanish, english).is_none());
+        assert!(stemmer_for(Language::French, "").is_some());
+    }
+}
@@ -19,7 +19,11 @@ use super::state::{
     ModelStatus, ProviderType, TopologyData, TopologyMetrics,
 };
 use super::{StaticFiles, Templates};
+use crate::core::centrality::{DEFAULT_TOP_K, concept_shift};
 use crate::core::compute_ldsi;
+use crate::core::topology::TopologyConfig;
+use crate::probe::boilerplate::{apply_refusal, detect_boilerplate};
+use crate::probe::cache::CacheMode;
 use crate::probe::{Injector, LlmConfig};
 
 /// Charge et rend un template Tera
@@ -45,6 +49,7 @@ pub async fn dashboard(Extension(state): Extension<Arc<RwLock<AppState>>>) -> im
     context.insert("title", "LDSI Control Center");
     context.insert("has_api_key", &state.openrouter_key.is_some());
     context.insert("models", &AvailableModels::default());
+    context.insert("profiles", &state.profiles.profiles);
 
     // Récupérer les benchmarks récents
     let recent: Vec<_> = state.benchmarks.values().take(10).cloned().collect();
@@ -93,10 +98,21 @@ pub async fn run_benchmark(
     Json(request): Json<BenchmarkRequest>,
 ) -> impl IntoResponse {
     // Créer la session
-    let (benchmark_id, openrouter_key) = {
+    let (benchmark_id, openrouter_key, prices, profiles, cache) = {
         let mut state = state.write().await;
         let id = state.create_benchmark(request.clone());
-        (id, state.openrouter_key.clone())
+        (
+            id,
+            state.openrouter_key.clone(),
+            state.prices.clone(),
+            state.profiles.clone(),
+            state.cache.clone(),
+        )
+    };
+    // Rejeu hors ligne : la clé n'est jamais envoyée, elle n'est pas requise
+    let openrouter_key = match cache.mode {
+        CacheMode::ReplayOnly => openrouter_key.or_else(|| Some(String::new())),
+        _ => openrouter_key,
     };
 
     // Lancer le benchmark en arrière-plan
@@ -112,6 +128,7 @@ pub async fn run_benchmark(
         }
 
         let mut results = Vec::new();
+        let (conversation_a, conversation_b) = request_clone.conversations();
 
         for model_config in &request_clone.models {
             let start = Instant::now();
@@ -121,60 +138,90 @@ pub async fn run_benchmark(
                     if let Some(ref key) = openrouter_key {
                         LlmConfig::openrouter(&model_config.model_id, key)
                     } else {
-                        results.push(ModelResult {
-                            model_name: model_config.display_name.clone(),
-                            status: ModelStatus::Failed,
-                            response_a: None,
-                            response_b: None,
-                            ldsi: None,
-                            topology: None,
-                            error: Some("OpenRouter API key not configured".into()),
-                            duration_ms: None,
-                        });
+                        results.push(ModelResult::failed(
+                            &model_config.display_name,
+                            "OpenRouter API key not configured",
+                        ));
                         continue;
                     }
                 }
                 ProviderType::Ollama => LlmConfig::ollama_local(&model_config.model_id),
                 ProviderType::OpenAI => {
-                    results.push(ModelResult {
-                        model_name: model_config.display_name.clone(),
-                        status: ModelStatus::Failed,
-                        response_a: None,
-                        response_b: None,
-                        ldsi: None,
-                        topology: None,
-                        error: Some("Direct OpenAI not implemented, use OpenRouter".into()),
-                        duration_ms: None,
-                    });
+                    results.push(ModelResult::failed(
+                        &model_config.display_name,
+                        "Direct OpenAI not implemented, use OpenRouter",
+                    ));
                     continue;
                 }
                 ProviderType::Anthropic => {
-                    results.push(ModelResult {
-                        model_name: model_config.display_name.clone(),
-                        status: ModelStatus::Failed,
-                        response_a: None,
-                        response_b: None,
-                        ldsi: None,
-                        topology: None,
-                        error: Some("Direct Anthropic not implemented, use OpenRouter".into()),
-                        duration_ms: None,
-                    });
+                    results.push(ModelResult::failed(
+                        &model_config.display_name,
+                        "Direct Anthropic not implemented, use OpenRouter",
+                    ));
                     continue;
                 }
+                ProviderType::OpenAICompatible => {
+                    let profile = model_config.profile.as_deref().unwrap_or_default();
+                    match profiles.config(profile) {
+                        // L'identifiant du modèle remplace celui du profil
+                        Ok(config) if !model_config.model_id.is_empty() => LlmConfig {
+                            model: model_config.model_id.clone(),
+                            ..config
+                        },
+                        Ok(config) => config,
+                        Err(e) => {
+                            results.push(ModelResult::failed(
+                                &model_config.display_name,
+                                e.to_string(),
+                            ));
+                            continue;
+                        }
+                    }
+                }
             };
 
+            let config = LlmConfig {
+                stream: request_clone.stream,
+                sampling: request_clone.sampling.clone(),
+                cache: cache.clone(),
+                ..config
+            };
+            let sampling = config.effective_sampling();
             let injector = Injector::new(config);
 
             match injector
-                .inject_ab(&request_clone.prompt_a, &request_clone.prompt_b)
+                .inject_ab_conversations(&conversation_a, &conversation_b)
                 .await
             {
-                Ok((response_a, response_b)) => {
-                    let ldsi_result = compute_ldsi(&response_a, &response_b, None);
+                Ok((full_a, full_b)) => {
+                    let usage_a = prices.usage(&model_config.model_id, &full_a);
+                    let usage_b = prices.usage(&model_config.model_id, &full_b);
+                    let (response_a, response_b) = (full_a.content, full_b.content);
+                    let boilerplate_a = detect_boilerplate(&response_a);
+                    let boilerplate_b = detect_boilerplate(&response_b);
+                    // Un refus n'est jamais noté comme divergence créative
+                    let ldsi_result = apply_refusal(
+                        compute_ldsi(&response_a, &response_b, None),
+                        &boilerplate_a,
+                        &boilerplate_b,
+                    );
+                    // λLD des traces de raisonnement, calculé à part
+                    let ldsi_reasoning = match (&full_a.reasoning, &full_b.reasoning) {
+                        (Some(a), Some(b)) => {
+                            Some(LdsiResultSummary::from(&compute_ldsi(a, b, None)))
+                        }
+                        _ => None,
+                    };
                     let duration = start.elapsed().as_millis() as u64;
 
                     // Générer les données de topologie pour la réponse B
                     let topo_b = crate::core::topology::analyze_topology(&response_b);
+                    let concepts = concept_shift(
+                        &response_a,
+                        &response_b,
+                        DEFAULT_TOP_K,
+                        &TopologyConfig::default(),
+                    );
 
                     results.push(ModelResult {
                         model_name: model_config.display_name.clone(),
@@ -182,26 +229,33 @@ pub async fn run_benchmark(
                         response_a: Some(response_a),
                         response_b: Some(response_b),
                         ldsi: Some(LdsiResultSummary::from(&ldsi_result)),
+                        reasoning_a: full_a.reasoning,
+                        reasoning_b: full_b.reasoning,
+                        ldsi_reasoning,
+                        boilerplate_a: Some(boilerplate_a),
+                        boilerplate_b: Some(boilerplate_b),
+                        retry_a: Some(full_a.retry),
+                        retry_b: Some(full_b.retry),
+                        timing_a: full_a.timing,
+                        timing_b: full_b.timing,
+                        sampling: Some(sampling),
+                        usage_a: Some(usage_a),
+                        usage_b: Some(usage_b),
                         topology: Some(TopologyData {
                             nodes: vec![], // Simplifié pour l'instant
                             edges: vec![],
                             metrics: TopologyMetrics::from(&topo_b),
                         }),
+                        concepts: Some(concepts),
                         error: None,
                         duration_ms: Some(duration),
                     });
                 }
                 Err(e) => {
-                    results.push(ModelResult {
-                        model_name: model_config.display_name.clone(),
-                        status: ModelStatus::Failed,
-                        response_a: None,
-                        response_b: None,
-                        ldsi: None,
-                        topology: None,
-                        error: Some(e.to_string()),
-                        duration_ms: None,
-                    });
+                    results.push(ModelResult::failed(
+                        &model_config.display_name,
+                        e.to_string(),
+                    ));
                 }
             }
         }
@@ -238,6 +292,7 @@ pub async fn benchmark_status(
             "id": session.id,
             "status": format!("{:?}", session.status),
             "results": session.results,
+            "cost": session.cost,
         }))
     } else {
         Json(serde_json::json!({
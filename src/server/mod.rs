@@ -19,6 +19,9 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use tower_http::cors::{Any, CorsLayer};
 
+use crate::probe::cache::CacheConfig;
+use crate::probe::pricing::PriceTable;
+use crate::probe::profile::ProfileSet;
 use state::AppState;
 
 /// Fichiers statiques embarqués dans le binaire
@@ -32,8 +35,18 @@ pub struct StaticFiles;
 pub struct Templates;
 
 /// Lance le serveur Control Center
-pub async fn start_server(port: u16, openrouter_key: Option<String>) {
-    let state = Arc::new(RwLock::new(AppState::new(openrouter_key)));
+pub async fn start_server(
+    port: u16,
+    openrouter_key: Option<String>,
+    prices: PriceTable,
+    profiles: ProfileSet,
+    cache: CacheConfig,
+) {
+    let mut app_state = AppState::new(openrouter_key);
+    app_state.prices = prices;
+    app_state.profiles = profiles;
+    app_state.cache = cache;
+    let state = Arc::new(RwLock::new(app_state));
 
     let cors = CorsLayer::new()
         .allow_origin(Any)
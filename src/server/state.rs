@@ -10,8 +10,16 @@ use std::io::BufWriter;
 use std::path::Path;
 use uuid::Uuid;
 
+use crate::core::centrality::ConceptShift;
 use crate::core::topology::TopologyResult;
 use crate::core::{LdsiResult, LdsiVerdict};
+use crate::probe::boilerplate::BoilerplateReport;
+use crate::probe::cache::CacheConfig;
+use crate::probe::conversation::{Conversation, Role};
+use crate::probe::injector::{RetryStats, StreamTiming};
+use crate::probe::pricing::{CompletionUsage, PriceTable, SessionCost};
+use crate::probe::profile::ProfileSet;
+use crate::probe::sampling::{EffectiveSampling, SamplingParams};
 
 /// Répertoire d'audit
 const AUDIT_DIR: &str = "audits";
@@ -22,6 +30,12 @@ pub struct AppState {
     pub openrouter_key: Option<String>,
     /// Sessions de benchmark en cours ou terminées
     pub benchmarks: HashMap<String, BenchmarkSession>,
+    /// Prix par modèle pour le coût des sessions (vide = coût non calculé)
+    pub prices: PriceTable,
+    /// Endpoints nommés (passerelles OpenAI-compatibles)
+    pub profiles: ProfileSet,
+    /// Cache des réponses, appliqué à chaque modèle du benchmark
+    pub cache: CacheConfig,
 }
 
 impl AppState {
@@ -29,6 +43,9 @@ impl AppState {
         Self {
             openrouter_key,
             benchmarks: HashMap::new(),
+            prices: PriceTable::default(),
+            profiles: ProfileSet::default(),
+            cache: CacheConfig::default(),
         }
     }
 
@@ -39,6 +56,7 @@ impl AppState {
             status: BenchmarkStatus::Pending,
             request,
             results: Vec::new(),
+            cost: SessionCost::default(),
             created_at: chrono::Utc::now().to_rfc3339(),
         };
         self.benchmarks.insert(id.clone(), session);
@@ -56,8 +74,17 @@ impl AppState {
         results: Vec<ModelResult>,
     ) {
         if let Some(session) = self.benchmarks.get_mut(id) {
+            let mut cost = SessionCost::default();
+            for result in &results {
+                result
+                    .usage_a
+                    .iter()
+                    .chain(&result.usage_b)
+                    .for_each(|u| cost.add(u));
+            }
             session.status = status;
             session.results = results;
+            session.cost = cost;
         }
     }
 }
@@ -71,6 +98,43 @@ pub struct BenchmarkRequest {
     pub prompt_b: String,
     /// Liste des modèles à tester
     pub models: Vec<ModelConfig>,
+    /// Réception en streaming (mesure TTFT et latence inter-token)
+    #[serde(default)]
+    pub stream: bool,
+    /// Prompts système A/B
+    #[serde(default)]
+    pub system_a: Option<String>,
+    #[serde(default)]
+    pub system_b: Option<String>,
+    /// Tours précédant les prompts A/B (fichiers de conversation importés)
+    #[serde(default)]
+    pub conversation_a: Option<Conversation>,
+    #[serde(default)]
+    pub conversation_b: Option<Conversation>,
+    /// Graine, top_p, top_k, séquences d'arrêt et pénalités
+    #[serde(default)]
+    pub sampling: SamplingParams,
+}
+
+impl BenchmarkRequest {
+    /// Conversations A/B : tours importés, prompt système, puis prompt
+    pub fn conversations(&self) -> (Conversation, Conversation) {
+        let build = |base: &Option<Conversation>, system: &Option<String>, prompt: &str| {
+            let mut conversation = base.clone().unwrap_or_default();
+            if let Some(system) = system.as_deref().filter(|s| !s.trim().is_empty()) {
+                conversation = conversation.with_system(system);
+            }
+            if prompt.trim().is_empty() {
+                conversation
+            } else {
+                conversation.with_message(Role::User, prompt)
+            }
+        };
+        (
+            build(&self.conversation_a, &self.system_a, &self.prompt_a),
+            build(&self.conversation_b, &self.system_b, &self.prompt_b),
+        )
+    }
 }
 
 /// Configuration d'un modèle pour le benchmark
@@ -82,6 +146,9 @@ pub struct ModelConfig {
     pub display_name: String,
     /// Type de provider
     pub provider: ProviderType,
+    /// Profil d'endpoint du serveur (requis pour OpenAICompatible)
+    #[serde(default)]
+    pub profile: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -90,6 +157,8 @@ pub enum ProviderType {
     Ollama,
     OpenAI,
     Anthropic,
+    /// Passerelle décrite par un profil (`serve --profiles`)
+    OpenAICompatible,
 }
 
 /// Session de benchmark
@@ -99,6 +168,9 @@ pub struct BenchmarkSession {
     pub status: BenchmarkStatus,
     pub request: BenchmarkRequest,
     pub results: Vec<ModelResult>,
+    /// Tokens, troncatures et coût cumulés de tous les modèles
+    #[serde(default)]
+    pub cost: SessionCost,
     pub created_at: String,
 }
 
@@ -145,14 +217,76 @@ pub struct ModelResult {
     pub response_b: Option<String>,
     /// Score LDSI
     pub ldsi: Option<LdsiResultSummary>,
+    /// Traces de raisonnement A/B (modèles "thinking")
+    #[serde(default)]
+    pub reasoning_a: Option<String>,
+    #[serde(default)]
+    pub reasoning_b: Option<String>,
+    /// Score LDSI des traces de raisonnement (si A et B en ont une)
+    #[serde(default)]
+    pub ldsi_reasoning: Option<LdsiResultSummary>,
+    /// Refus et boilerplate détectés dans les réponses A/B
+    #[serde(default)]
+    pub boilerplate_a: Option<BoilerplateReport>,
+    #[serde(default)]
+    pub boilerplate_b: Option<BoilerplateReport>,
+    /// Tentatives et délais de reprise des requêtes A/B
+    #[serde(default)]
+    pub retry_a: Option<RetryStats>,
+    #[serde(default)]
+    pub retry_b: Option<RetryStats>,
+    /// Profil de latence A/B (TTFT, intervalles), si reçu en streaming
+    #[serde(default)]
+    pub timing_a: Option<StreamTiming>,
+    #[serde(default)]
+    pub timing_b: Option<StreamTiming>,
+    /// Paramètres d'échantillonnage appliqués
+    #[serde(default)]
+    pub sampling: Option<EffectiveSampling>,
+    /// Tokens, raison d'arrêt, troncature et coût des réponses A/B
+    #[serde(default)]
+    pub usage_a: Option<CompletionUsage>,
+    #[serde(default)]
+    pub usage_b: Option<CompletionUsage>,
     /// Données de topologie pour visualisation
     pub topology: Option<TopologyData>,
+    /// Glissement des concepts centraux entre A et B
+    pub concepts: Option<ConceptShift>,
     /// Message d'erreur si échec
     pub error: Option<String>,
     /// Temps d'exécution en ms
     pub duration_ms: Option<u64>,
 }
 
+impl ModelResult {
+    /// Résultat d'un modèle en échec, sans réponse ni score
+    pub fn failed(model_name: &str, error: impl Into<String>) -> Self {
+        Self {
+            model_name: model_name.to_string(),
+            status: ModelStatus::Failed,
+            response_a: None,
+            response_b: None,
+            ldsi: None,
+            reasoning_a: None,
+            reasoning_b: None,
+            ldsi_reasoning: None,
+            boilerplate_a: None,
+            boilerplate_b: None,
+            retry_a: None,
+            retry_b: None,
+            timing_a: None,
+            timing_b: None,
+            sampling: None,
+            usage_a: None,
+            usage_b: None,
+            topology: None,
+            concepts: None,
+            error: Some(error.into()),
+            duration_ms: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ModelStatus {
     Pending,
@@ -183,6 +317,7 @@ impl From<&LdsiResult> for LdsiResultSummary {
             LdsiVerdict::Rebelle => ("REBELLE", "rebelle"),
             LdsiVerdict::Architecte => ("ARCHITECTE", "architecte"),
             LdsiVerdict::Fou => ("FOU", "fou"),
+            LdsiVerdict::Refus => ("REFUS", "refus"),
         };
 
         Self {
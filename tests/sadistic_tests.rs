@@ -10,8 +10,10 @@ use ldsi::core::entropy::{compute_entropy, compute_ngram_entropy};
 use ldsi::core::ncd::compute_ncd;
 use ldsi::core::topology::analyze_topology;
 use ldsi::core::{LdsiCoefficients, LdsiVerdict, compute_ldsi};
-use ldsi::probe::clean_default;
-use ldsi::probe::cleaner::{CleanerConfig, Language, clean_text};
+use ldsi::probe::boilerplate::{apply_refusal, detect_boilerplate};
+use ldsi::probe::cleaner::{CleanerConfig, Language, clean_default, clean_text};
+use ldsi::probe::numerals::{NumeralConfig, NumeralForm};
+use ldsi::probe::pipeline::CleaningPipeline;
 
 // ============================================================================
 // NCD - TESTS DE TORTURE
@@ -665,6 +667,7 @@ mod ldsi_torture {
                 alpha: 1.0,
                 beta: 0.0,
                 gamma: 0.0,
+                ..Default::default()
             }),
         );
 
@@ -676,6 +679,7 @@ mod ldsi_torture {
                 alpha: 0.0,
                 beta: 1.0,
                 gamma: 0.0,
+                ..Default::default()
             }),
         );
 
@@ -687,6 +691,7 @@ mod ldsi_torture {
                 alpha: 0.0,
                 beta: 0.0,
                 gamma: 1.0,
+                ..Default::default()
             }),
         );
 
@@ -749,6 +754,31 @@ mod ldsi_torture {
         assert!(result.lambda.is_finite());
     }
 
+    #[test]
+    fn test_ldsi_refusal_never_creative() {
+        // Un refus face à une réponse utile a une NCD élevée : sans garde,
+        // il tomberait en REBELLE ou ARCHITECTE
+        let helpful = "Pour réussir une pâte brisée, mélangez 250 g de farine, 125 g de beurre \
+                       froid en dés, une pincée de sel et 5 cl d'eau. Sablez du bout des doigts, \
+                       formez une boule sans trop la travailler puis laissez reposer une heure \
+                       au frais avant d'étaler.";
+        let refusals = [
+            "Je suis désolé, mais je ne peux pas vous aider avec cette demande.",
+            "I'm sorry, but I can't help with that. Is there anything else I can do?",
+            "Lo siento, no puedo ayudar con eso.",
+        ];
+
+        for refusal in refusals {
+            let result = apply_refusal(
+                compute_ldsi(helpful, refusal, None),
+                &detect_boilerplate(helpful),
+                &detect_boilerplate(refusal),
+            );
+            assert_eq!(result.verdict, LdsiVerdict::Refus, "{}", refusal);
+            assert!(result.lambda.is_finite());
+        }
+    }
+
     #[test]
     fn test_ldsi_symmetry() {
         // LDSI n'est PAS symétrique (A vs B ≠ B vs A à cause du ratio d'entropie)
@@ -799,6 +829,7 @@ mod ldsi_torture {
                 alpha: -1.0,
                 beta: -1.0,
                 gamma: -1.0,
+                ..Default::default()
             }),
         );
 
@@ -818,6 +849,7 @@ mod ldsi_torture {
                 alpha: 0.0,
                 beta: 0.0,
                 gamma: 0.0,
+                ..Default::default()
             }),
         );
 
@@ -1001,6 +1033,86 @@ mod cleaner_torture {
             );
         }
     }
+
+    #[test]
+    fn test_stemming_collapses_inflections() {
+        // Flexions d'un même mot : la racinisation doit faire chuter le TTR
+        let text = "chat chats chatte connecte connecter connectons connectaient \
+                    transforme transformer transformations transformation";
+        let plain = CleanerConfig {
+            language: Language::French,
+            ..Default::default()
+        };
+        let stemmed = CleanerConfig {
+            stem: true,
+            ..plain.clone()
+        };
+
+        let ttr_plain = compute_entropy(&clean_text(text, &plain)).ttr;
+        let ttr_stemmed = compute_entropy(&clean_text(text, &stemmed)).ttr;
+        assert!(
+            ttr_stemmed < ttr_plain * 0.6,
+            "TTR racinisé {} vs brut {}",
+            ttr_stemmed,
+            ttr_plain
+        );
+    }
+
+    #[test]
+    fn test_numerals_collapse_notation() {
+        // Cas des notes de recherche : seule la notation diffère
+        let a = "La température est de vingt-cinq degrés aujourd'hui, le premier mars deux mille vingt.";
+        let b = "La température est de 25 degrés aujourd'hui, le 1er mars 2020.";
+        let config = CleanerConfig {
+            remove_numbers: false,
+            numerals: Some(NumeralConfig::default()),
+            ..Default::default()
+        };
+        assert_eq!(clean_text(a, &config), clean_text(b, &config));
+
+        // Forme "Words" : survit au retrait des chiffres
+        let words = CleanerConfig {
+            numerals: Some(NumeralConfig {
+                target: NumeralForm::Words,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let cleaned = clean_text(b, &words);
+        assert!(cleaned.contains("vingt cinq"), "{}", cleaned);
+        assert_eq!(cleaned, clean_text(a, &words));
+    }
+
+    #[test]
+    fn test_pipeline_trace_is_consistent() {
+        // Texte hostile : la trace doit s'enchaîner sans trou ni double compte
+        let text = "<think>plan</think>L'été 2024, le 🦀 a mangé 42 crabes!!! \u{0301}                     e\u{0301}té le le le le le le zz a b c";
+        let config = CleanerConfig {
+            dynamic_stopwords: true,
+            stem: true,
+            ..Default::default()
+        };
+        let (cleaned, trace) = CleaningPipeline::from_config(&config).run(text);
+
+        assert_eq!(cleaned, clean_text(text, &config));
+        assert_eq!(trace.chars_before(), text.chars().count());
+        assert_eq!(trace.chars_after(), cleaned.chars().count());
+        for pair in trace.stages.windows(2) {
+            assert_eq!(pair[0].chars_after, pair[1].chars_before);
+            assert_eq!(pair[0].tokens_after, pair[1].tokens_before);
+        }
+        for stage in &trace.stages {
+            let removed: usize = stage.removed.iter().map(|t| t.count).sum();
+            if removed > 0 {
+                assert_eq!(
+                    stage.tokens_before,
+                    stage.tokens_after + removed,
+                    "{}",
+                    stage.stage
+                );
+            }
+        }
+    }
 }
 
 // ============================================================================